@@ -2,10 +2,11 @@
 extern crate criterion;
 
 use ark_crypto_primitives::crh::{
-    pedersen::{Window, CRH as PedersenCRH},
+    bowe_hopwood::{self, CRH as BoweHopwoodCRH},
+    pedersen::{self, Window, CRH as PedersenCRH},
     CRHScheme,
 };
-use ark_ed_on_bls12_377::EdwardsProjective as Edwards;
+use ark_ed_on_bls12_377::{EdwardsConfig, EdwardsProjective as Edwards};
 use criterion::Criterion;
 
 #[derive(Clone, PartialEq, Eq, Hash)]
@@ -30,7 +31,60 @@ fn pedersen_crh_eval(c: &mut Criterion) {
     let parameters = PedersenCRH::<Edwards, HashWindow>::setup(&mut rng).unwrap();
     let input = vec![5u8; 128];
     c.bench_function("Pedersen CRH Eval", move |b| {
-        b.iter(|| PedersenCRH::<Edwards, HashWindow>::evaluate(&parameters, input.clone()).unwrap())
+        b.iter(|| {
+            PedersenCRH::<Edwards, HashWindow>::evaluate(&parameters, input.as_slice()).unwrap()
+        })
+    });
+}
+
+fn pedersen_crh_eval_with_tables(c: &mut Criterion) {
+    let mut rng = &mut ark_std::test_rng();
+    let parameters = PedersenCRH::<Edwards, HashWindow>::setup(&mut rng).unwrap();
+    let parameters = pedersen::PrecomputedParameters::new(parameters, 8).unwrap();
+    let input = vec![5u8; 128];
+    c.bench_function("Pedersen CRH Eval with tables", move |b| {
+        b.iter(|| {
+            PedersenCRH::<Edwards, HashWindow>::evaluate_with_tables(&parameters, &input).unwrap()
+        })
+    });
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct BoweHopwoodWindow;
+
+impl Window for BoweHopwoodWindow {
+    const WINDOW_SIZE: usize = 63;
+    const NUM_WINDOWS: usize = 8;
+}
+
+fn bowe_hopwood_crh_eval(c: &mut Criterion) {
+    let mut rng = &mut ark_std::test_rng();
+    let parameters = BoweHopwoodCRH::<EdwardsConfig, BoweHopwoodWindow>::setup(&mut rng).unwrap();
+    let input = vec![5u8; 189];
+    c.bench_function("Bowe-Hopwood CRH Eval", move |b| {
+        b.iter(|| {
+            BoweHopwoodCRH::<EdwardsConfig, BoweHopwoodWindow>::evaluate(
+                &parameters,
+                input.as_slice(),
+            )
+            .unwrap()
+        })
+    });
+}
+
+fn bowe_hopwood_crh_eval_with_tables(c: &mut Criterion) {
+    let mut rng = &mut ark_std::test_rng();
+    let parameters = BoweHopwoodCRH::<EdwardsConfig, BoweHopwoodWindow>::setup(&mut rng).unwrap();
+    let parameters = bowe_hopwood::PrecomputedParameters::new(parameters, 3).unwrap();
+    let input = vec![5u8; 189];
+    c.bench_function("Bowe-Hopwood CRH Eval with tables", move |b| {
+        b.iter(|| {
+            BoweHopwoodCRH::<EdwardsConfig, BoweHopwoodWindow>::evaluate_with_tables(
+                &parameters,
+                &input,
+            )
+            .unwrap()
+        })
     });
 }
 
@@ -43,7 +97,7 @@ criterion_group! {
 criterion_group! {
     name = crh_eval;
     config = Criterion::default().sample_size(10);
-    targets = pedersen_crh_eval
+    targets = pedersen_crh_eval, pedersen_crh_eval_with_tables, bowe_hopwood_crh_eval, bowe_hopwood_crh_eval_with_tables
 }
 
 criterion_main!(crh_setup, crh_eval);
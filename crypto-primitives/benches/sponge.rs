@@ -0,0 +1,48 @@
+#[macro_use]
+extern crate criterion;
+
+use ark_bls12_377::Fr;
+use ark_crypto_primitives::sponge::poseidon::{
+    find_poseidon_ark_and_mds, PoseidonConfig, PoseidonSponge,
+};
+use ark_crypto_primitives::sponge::{CryptographicSponge, FieldBasedCryptographicSponge};
+use ark_ff::PrimeField;
+use ark_std::UniformRand;
+use criterion::Criterion;
+
+fn poseidon_config(rate: usize) -> PoseidonConfig<Fr> {
+    let (ark, mds) = find_poseidon_ark_and_mds::<Fr>(Fr::MODULUS_BIT_SIZE as u64, rate, 8, 57, 0);
+    PoseidonConfig::new(8, 57, 5, mds, ark, rate, 1)
+}
+
+fn poseidon_sponge_absorb_squeeze(c: &mut Criterion) {
+    let mut rng = ark_std::test_rng();
+    for rate in [2, 4, 8] {
+        let optimized = poseidon_config(rate);
+        let mut reference = optimized.clone();
+        reference.set_optimized(false);
+        let input: Vec<Fr> = (0..rate).map(|_| Fr::rand(&mut rng)).collect();
+
+        for (name, config) in [("optimized", optimized), ("reference", reference)] {
+            let input = input.clone();
+            let mut sponge = PoseidonSponge::new(&config);
+            c.bench_function(
+                &format!("Poseidon Absorb and Squeeze (rate {rate}, {name})"),
+                move |b| {
+                    b.iter(|| {
+                        sponge.absorb(&input);
+                        sponge.squeeze_native_field_elements(1)
+                    })
+                },
+            );
+        }
+    }
+}
+
+criterion_group! {
+    name = sponge_hash;
+    config = Criterion::default().sample_size(10);
+    targets = poseidon_sponge_absorb_squeeze
+}
+
+criterion_main!(sponge_hash);
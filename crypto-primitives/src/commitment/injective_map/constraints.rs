@@ -58,3 +58,62 @@ where
         IG::evaluate(&result)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::commitment::{
+        injective_map::{constraints::CommitmentCompressorGadget, PedersenCommCompressor},
+        pedersen::Randomness,
+        CommitmentGadget, CommitmentScheme,
+    };
+    use crate::crh::{
+        injective_map::{constraints::SWCompressorGadget, SWCompressor},
+        pedersen,
+    };
+    use ark_bls12_377::{constraints::G1Var, Fq, Fr, G1Projective};
+    use ark_r1cs_std::prelude::*;
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::{test_rng, UniformRand};
+
+    #[derive(Clone, PartialEq, Eq, Hash)]
+    struct Window;
+
+    impl pedersen::Window for Window {
+        const WINDOW_SIZE: usize = 4;
+        const NUM_WINDOWS: usize = 9;
+    }
+
+    type TestCOMM = PedersenCommCompressor<G1Projective, SWCompressor, Window>;
+    type TestCOMMGadget =
+        CommitmentCompressorGadget<G1Projective, SWCompressor, Window, G1Var, SWCompressorGadget>;
+
+    #[test]
+    fn test_sw_compressor_equality() {
+        let rng = &mut test_rng();
+        let cs = ConstraintSystem::<Fq>::new_ref();
+
+        let input = [1u8, 2, 3, 4];
+        let randomness = Randomness(Fr::rand(rng));
+        let parameters = TestCOMM::setup(rng).unwrap();
+        let primitive_result = TestCOMM::commit(&parameters, &input, &randomness).unwrap();
+
+        let input_var = UInt8::new_witness_vec(cs.clone(), &input).unwrap();
+        let randomness_var =
+            <TestCOMMGadget as CommitmentGadget<TestCOMM, Fq>>::RandomnessVar::new_witness(
+                ark_relations::ns!(cs, "gadget_randomness"),
+                || Ok(&randomness),
+            )
+            .unwrap();
+        let parameters_var =
+            <TestCOMMGadget as CommitmentGadget<TestCOMM, Fq>>::ParametersVar::new_constant(
+                ark_relations::ns!(cs, "gadget_parameters"),
+                &parameters,
+            )
+            .unwrap();
+        let result_var =
+            TestCOMMGadget::commit(&parameters_var, &input_var, &randomness_var).unwrap();
+
+        assert_eq!(primitive_result, result_var.value().unwrap());
+        assert!(cs.is_satisfied().unwrap());
+    }
+}
@@ -10,8 +10,8 @@ use ark_std::UniformRand;
 
 use super::CommitmentScheme;
 
-use crate::crh::pedersen;
 pub use crate::crh::pedersen::Window;
+use crate::crh::pedersen::{self, GroupHash};
 
 #[cfg(feature = "r1cs")]
 pub mod constraints;
@@ -38,6 +38,24 @@ impl<C: CurveGroup> UniformRand for Randomness<C> {
     }
 }
 
+impl<C: GroupHash, W: Window> Commitment<C, W> {
+    /// Derives the generators deterministically from `personalization`, which must be at most 8
+    /// bytes, as [`pedersen::CRH::setup_from_domain`]. The base of the randomness generators is
+    /// `FindGroupHash(personalization, "r")`, as the randomness base of the Sapling Pedersen
+    /// commitment.
+    pub fn setup_from_domain(personalization: &[u8]) -> Result<Parameters<C>, Error> {
+        let num_powers = <C::ScalarField as PrimeField>::MODULUS_BIT_SIZE as usize;
+        let randomness_base = pedersen::find_group_hash(personalization, b"r")?;
+        let randomness_generator = pedersen::powers_of_two(randomness_base, num_powers);
+        let generators = pedersen::CRH::<C, W>::create_generators_from_domain(personalization)?;
+
+        Ok(Parameters {
+            randomness_generator,
+            generators,
+        })
+    }
+}
+
 impl<C: CurveGroup, W: Window> CommitmentScheme for Commitment<C, W> {
     type Parameters = Parameters<C>;
     type Randomness = Randomness<C>;
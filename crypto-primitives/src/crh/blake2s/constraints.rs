@@ -0,0 +1,146 @@
+use crate::crh::blake2s::{Parameters, TwoToOneCRH, CRH};
+use crate::crh::{CRHSchemeGadget, TwoToOneCRHSchemeGadget};
+use crate::prf::blake2s::constraints::{evaluate_blake2s_with_parameters, OutputVar};
+use ark_ff::PrimeField;
+use ark_r1cs_std::prelude::*;
+use ark_relations::r1cs::{Namespace, SynthesisError};
+use ark_std::borrow::Borrow;
+use ark_std::marker::PhantomData;
+#[cfg(not(feature = "std"))]
+use ark_std::vec::Vec;
+
+/// The parameters of the BLAKE2s CRH. They are public, so they are always allocated as
+/// constants.
+#[derive(Clone)]
+pub struct ParametersVar {
+    pub parameters: Parameters,
+}
+
+impl<F: PrimeField> AllocVar<Parameters, F> for ParametersVar {
+    fn new_variable<T: Borrow<Parameters>>(
+        _cs: impl Into<Namespace<F>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        _mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        Ok(Self {
+            parameters: f()?.borrow().clone(),
+        })
+    }
+}
+
+pub struct CRHGadget<F: PrimeField> {
+    _field: PhantomData<F>,
+}
+
+impl<F: PrimeField> CRHSchemeGadget<CRH, F> for CRHGadget<F> {
+    type InputVar = [UInt8<F>];
+    type OutputVar = OutputVar<F>;
+    type ParametersVar = ParametersVar;
+
+    #[tracing::instrument(target = "r1cs", skip(parameters, input))]
+    fn evaluate(
+        parameters: &Self::ParametersVar,
+        input: &Self::InputVar,
+    ) -> Result<Self::OutputVar, SynthesisError> {
+        let mut input_bits = Vec::with_capacity(input.len() * 8);
+        for byte in input {
+            input_bits.extend(byte.to_bits_le()?);
+        }
+        let block = parameters.parameters.parameter_block().parameters();
+        let mut output = Vec::with_capacity(32);
+        for word in evaluate_blake2s_with_parameters(&input_bits, &block)? {
+            output.extend(word.to_bytes_le()?);
+        }
+        Ok(OutputVar(output))
+    }
+}
+
+pub struct TwoToOneCRHGadget<F: PrimeField> {
+    _field: PhantomData<F>,
+}
+
+impl<F: PrimeField> TwoToOneCRHSchemeGadget<TwoToOneCRH, F> for TwoToOneCRHGadget<F> {
+    type InputVar = [UInt8<F>];
+    type OutputVar = OutputVar<F>;
+    type ParametersVar = ParametersVar;
+
+    #[tracing::instrument(target = "r1cs", skip(parameters))]
+    fn evaluate(
+        parameters: &Self::ParametersVar,
+        left_input: &Self::InputVar,
+        right_input: &Self::InputVar,
+    ) -> Result<Self::OutputVar, SynthesisError> {
+        let input: Vec<_> = left_input.iter().chain(right_input).cloned().collect();
+        CRHGadget::evaluate(parameters, &input)
+    }
+
+    #[tracing::instrument(target = "r1cs", skip(parameters))]
+    fn compress(
+        parameters: &Self::ParametersVar,
+        left_input: &Self::OutputVar,
+        right_input: &Self::OutputVar,
+    ) -> Result<Self::OutputVar, SynthesisError> {
+        Self::evaluate(parameters, &left_input.0, &right_input.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::crh::blake2s::{
+        self,
+        constraints::{CRHGadget, ParametersVar, TwoToOneCRHGadget},
+    };
+    use crate::crh::{CRHScheme, CRHSchemeGadget, TwoToOneCRHScheme, TwoToOneCRHSchemeGadget};
+    use ark_ed_on_bls12_381::Fq as Fr;
+    use ark_r1cs_std::prelude::*;
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::rand::RngCore;
+    use ark_std::test_rng;
+
+    #[test]
+    fn test_blake2s_crh_gadget() {
+        let rng = &mut test_rng();
+        let mut params = blake2s::CRH::setup(rng).unwrap();
+        params.personalization = *b"arkworks";
+
+        for len in [0, 1, 63, 64, 65, 130] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let mut input = vec![0u8; len];
+            rng.fill_bytes(&mut input);
+
+            let params_var = ParametersVar::new_constant(cs.clone(), &params).unwrap();
+            let input_var = UInt8::new_witness_vec(cs.clone(), &input).unwrap();
+            let output_var = CRHGadget::evaluate(&params_var, &input_var).unwrap();
+
+            let output = blake2s::CRH::evaluate(&params, input.as_slice()).unwrap();
+            assert_eq!(output_var.value().unwrap(), output);
+            assert!(cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_blake2s_two_to_one_crh_gadget() {
+        let rng = &mut test_rng();
+        let params = blake2s::TwoToOneCRH::setup(rng).unwrap();
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let params_var = ParametersVar::new_constant(cs.clone(), &params).unwrap();
+
+        let mut left = [0u8; 32];
+        let mut right = [0u8; 32];
+        rng.fill_bytes(&mut left);
+        rng.fill_bytes(&mut right);
+        let left_var = UInt8::new_witness_vec(cs.clone(), &left).unwrap();
+        let right_var = UInt8::new_witness_vec(cs.clone(), &right).unwrap();
+
+        let output =
+            blake2s::TwoToOneCRH::evaluate(&params, left.as_slice(), right.as_slice()).unwrap();
+        let output_var = TwoToOneCRHGadget::evaluate(&params_var, &left_var, &right_var).unwrap();
+        assert_eq!(output_var.value().unwrap(), output);
+
+        let compressed = blake2s::TwoToOneCRH::compress(&params, output, output).unwrap();
+        let compressed_var =
+            TwoToOneCRHGadget::compress(&params_var, &output_var, &output_var).unwrap();
+        assert_eq!(compressed_var.value().unwrap(), compressed);
+        assert!(cs.is_satisfied().unwrap());
+    }
+}
@@ -0,0 +1,126 @@
+use crate::crh::{CRHScheme, TwoToOneCRHScheme};
+use crate::prf::blake2s::Blake2sWithParameterBlock;
+use crate::Error;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::borrow::Borrow;
+use ark_std::rand::Rng;
+#[cfg(not(feature = "std"))]
+use ark_std::vec::Vec;
+use blake2::Blake2sVarCore;
+use digest::core_api::{Buffer, UpdateCore, VariableOutputCore};
+use digest::Output;
+
+#[cfg(feature = "r1cs")]
+pub mod constraints;
+
+/// The parameters of the BLAKE2s CRH: the personalization of the BLAKE2s parameter block,
+/// which separates the domains of different uses of the hash function.
+#[derive(Clone, Debug, Default, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Parameters {
+    pub personalization: [u8; 8],
+}
+
+impl Parameters {
+    /// Returns the parameter block of BLAKE2s with a 32-byte digest, no key and no salt.
+    pub fn parameter_block(&self) -> Blake2sWithParameterBlock {
+        Blake2sWithParameterBlock {
+            output_size: 32,
+            key_size: 0,
+            salt: [0; 8],
+            personalization: self.personalization,
+        }
+    }
+}
+
+/// BLAKE2s with a 32-byte digest.
+pub struct CRH;
+
+impl CRHScheme for CRH {
+    type Input = [u8];
+    type Output = [u8; 32];
+    type Parameters = Parameters;
+
+    /// Returns the parameters with an all-zero personalization, for which `evaluate` is the
+    /// standard BLAKE2s-256.
+    fn setup<R: Rng>(_rng: &mut R) -> Result<Self::Parameters, Error> {
+        Ok(Parameters::default())
+    }
+
+    fn evaluate<T: Borrow<Self::Input>>(
+        parameters: &Self::Parameters,
+        input: T,
+    ) -> Result<Self::Output, Error> {
+        // `Blake2sMac` with an empty key would still process a key block, so the unkeyed hash
+        // is computed with the core of `Blake2s256`.
+        let mut core = Blake2sVarCore::new_with_params(&[], &parameters.personalization, 0, 32);
+        let mut buffer = Buffer::<Blake2sVarCore>::default();
+        buffer.digest_blocks(input.borrow(), |blocks| core.update_blocks(blocks));
+        let mut digest = Output::<Blake2sVarCore>::default();
+        core.finalize_variable_core(&mut buffer, &mut digest);
+
+        let mut output = [0u8; 32];
+        output.copy_from_slice(&digest);
+        Ok(output)
+    }
+}
+
+/// BLAKE2s with a 32-byte digest of the concatenation of the two inputs.
+pub struct TwoToOneCRH;
+
+impl TwoToOneCRHScheme for TwoToOneCRH {
+    type Input = [u8];
+    type Output = [u8; 32];
+    type Parameters = Parameters;
+
+    fn setup<R: Rng>(rng: &mut R) -> Result<Self::Parameters, Error> {
+        CRH::setup(rng)
+    }
+
+    fn evaluate<T: Borrow<Self::Input>>(
+        parameters: &Self::Parameters,
+        left_input: T,
+        right_input: T,
+    ) -> Result<Self::Output, Error> {
+        let input: Vec<u8> = [left_input.borrow(), right_input.borrow()].concat();
+        CRH::evaluate(parameters, input)
+    }
+
+    fn compress<T: Borrow<Self::Output>>(
+        parameters: &Self::Parameters,
+        left_input: T,
+        right_input: T,
+    ) -> Result<Self::Output, Error> {
+        <Self as TwoToOneCRHScheme>::evaluate(
+            parameters,
+            left_input.borrow().as_slice(),
+            right_input.borrow().as_slice(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::crh::{blake2s, CRHScheme, TwoToOneCRHScheme};
+    use ark_std::test_rng;
+    use blake2::Blake2s256;
+    use digest::Digest;
+
+    #[test]
+    fn matches_blake2s() {
+        let input = b"a message to hash";
+        let mut params = blake2s::CRH::setup(&mut test_rng()).unwrap();
+        let output = blake2s::CRH::evaluate(&params, input.as_slice()).unwrap();
+        assert_eq!(output.as_slice(), Blake2s256::digest(input).as_slice());
+
+        params.personalization = *b"arkworks";
+        let personalized = blake2s::CRH::evaluate(&params, input.as_slice()).unwrap();
+        assert_ne!(personalized, output);
+
+        let compressed = blake2s::TwoToOneCRH::compress(&params, output, output).unwrap();
+        let concatenated = [output, output].concat();
+        assert_eq!(
+            compressed,
+            blake2s::CRH::evaluate(&params, concatenated.as_slice()).unwrap()
+        );
+    }
+}
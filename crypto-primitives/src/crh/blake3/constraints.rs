@@ -0,0 +1,313 @@
+use crate::crh::blake3::{
+    Parameters, TwoToOneCRH, BLOCK_LEN, CHUNK_END, CHUNK_LEN, CHUNK_START, CRH, IV, KEYED_HASH,
+    MSG_PERMUTATION, PARENT, ROOT,
+};
+use crate::crh::{CRHSchemeGadget, TwoToOneCRHSchemeGadget};
+use crate::prf::blake2s::constraints::{mixing_g, OutputVar};
+use ark_ff::PrimeField;
+use ark_r1cs_std::prelude::*;
+use ark_relations::r1cs::{Namespace, SynthesisError};
+use ark_std::borrow::Borrow;
+use ark_std::marker::PhantomData;
+#[cfg(not(feature = "std"))]
+use ark_std::vec::Vec;
+
+/// The parameters of the BLAKE3 CRH. In the keyed mode, the key words are allocated in the
+/// requested mode, so that the key can be kept secret.
+#[derive(Clone)]
+pub struct ParametersVar<F: PrimeField> {
+    pub key: Option<Vec<UInt32<F>>>,
+}
+
+impl<F: PrimeField> ParametersVar<F> {
+    /// Returns the initial chaining value and the flags of the mode.
+    fn key_words_and_flags(&self) -> (Vec<UInt32<F>>, u32) {
+        match &self.key {
+            None => (IV.iter().map(|w| UInt32::constant(*w)).collect(), 0),
+            Some(key) => (key.clone(), KEYED_HASH),
+        }
+    }
+}
+
+impl<F: PrimeField> AllocVar<Parameters, F> for ParametersVar<F> {
+    fn new_variable<T: Borrow<Parameters>>(
+        cs: impl Into<Namespace<F>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        let ns = cs.into();
+        let cs = ns.cs();
+        let key = match f()?.borrow().key {
+            None => None,
+            Some(key) => {
+                let bytes = Vec::<UInt8<F>>::new_variable(cs, || Ok(key.as_slice()), mode)?;
+                Some(
+                    bytes
+                        .chunks(4)
+                        .map(UInt32::from_bytes_le)
+                        .collect::<Result<_, _>>()?,
+                )
+            }
+        };
+        Ok(Self { key })
+    }
+}
+
+/// The BLAKE3 compression function. Returns the 16 output words, whose first 8 words are the
+/// new chaining value.
+pub fn compress<F: PrimeField>(
+    chaining_value: &[UInt32<F>],
+    block_words: &[UInt32<F>],
+    counter: u64,
+    block_len: u32,
+    flags: u32,
+) -> Result<Vec<UInt32<F>>, SynthesisError> {
+    assert_eq!(chaining_value.len(), 8);
+    assert_eq!(block_words.len(), 16);
+
+    let mut v = Vec::with_capacity(16);
+    v.extend_from_slice(chaining_value);
+    v.extend(IV[..4].iter().map(|w| UInt32::constant(*w)));
+    v.push(UInt32::constant(counter as u32));
+    v.push(UInt32::constant((counter >> 32) as u32));
+    v.push(UInt32::constant(block_len));
+    v.push(UInt32::constant(flags));
+
+    let mut m = block_words.to_vec();
+    for round in 0..7 {
+        mixing_g(&mut v, 0, 4, 8, 12, &m[0], &m[1])?;
+        mixing_g(&mut v, 1, 5, 9, 13, &m[2], &m[3])?;
+        mixing_g(&mut v, 2, 6, 10, 14, &m[4], &m[5])?;
+        mixing_g(&mut v, 3, 7, 11, 15, &m[6], &m[7])?;
+        mixing_g(&mut v, 0, 5, 10, 15, &m[8], &m[9])?;
+        mixing_g(&mut v, 1, 6, 11, 12, &m[10], &m[11])?;
+        mixing_g(&mut v, 2, 7, 8, 13, &m[12], &m[13])?;
+        mixing_g(&mut v, 3, 4, 9, 14, &m[14], &m[15])?;
+        if round < 6 {
+            m = MSG_PERMUTATION.iter().map(|i| m[*i].clone()).collect();
+        }
+    }
+
+    let mut output = Vec::with_capacity(16);
+    for i in 0..8 {
+        output.push(&v[i] ^ &v[i + 8]);
+    }
+    for i in 0..8 {
+        output.push(&v[i + 8] ^ &chaining_value[i]);
+    }
+    Ok(output)
+}
+
+/// Reads little-endian words from at most 64 bytes, padded with zeros.
+fn block_words<F: PrimeField>(block: &[UInt8<F>]) -> Result<Vec<UInt32<F>>, SynthesisError> {
+    let mut bytes = block.to_vec();
+    bytes.resize(BLOCK_LEN, UInt8::constant(0));
+    bytes.chunks(4).map(UInt32::from_bytes_le).collect()
+}
+
+/// Compresses a chunk of at most `CHUNK_LEN` bytes into its chaining value. If the chunk is
+/// the root node, `ROOT` is set on the compression of its last block.
+fn chunk_chaining_value<F: PrimeField>(
+    key: &[UInt32<F>],
+    chunk: &[UInt8<F>],
+    counter: u64,
+    flags: u32,
+    root: bool,
+) -> Result<Vec<UInt32<F>>, SynthesisError> {
+    let num_blocks = chunk.len().div_ceil(BLOCK_LEN).max(1);
+    let mut cv = key.to_vec();
+    for i in 0..num_blocks {
+        let block = &chunk[i * BLOCK_LEN..chunk.len().min((i + 1) * BLOCK_LEN)];
+        let mut block_flags = flags;
+        if i == 0 {
+            block_flags |= CHUNK_START;
+        }
+        if i == num_blocks - 1 {
+            block_flags |= CHUNK_END;
+            if root {
+                block_flags |= ROOT;
+            }
+        }
+        cv = compress(
+            &cv,
+            &block_words(block)?,
+            counter,
+            block.len() as u32,
+            block_flags,
+        )?;
+        cv.truncate(8);
+    }
+    Ok(cv)
+}
+
+fn parent_chaining_value<F: PrimeField>(
+    key: &[UInt32<F>],
+    left: &[UInt32<F>],
+    right: &[UInt32<F>],
+    flags: u32,
+) -> Result<Vec<UInt32<F>>, SynthesisError> {
+    let block: Vec<_> = left.iter().chain(right).cloned().collect();
+    let mut cv = compress(key, &block, 0, BLOCK_LEN as u32, PARENT | flags)?;
+    cv.truncate(8);
+    Ok(cv)
+}
+
+/// Hashes `input` with the given key words and mode flags, into a 32-byte digest. The tree
+/// only depends on the length of the input, which is fixed at synthesis time.
+fn hash<F: PrimeField>(
+    key: &[UInt32<F>],
+    flags: u32,
+    input: &[UInt8<F>],
+) -> Result<OutputVar<F>, SynthesisError> {
+    let num_chunks = input.len().div_ceil(CHUNK_LEN).max(1);
+    let chunk = |i: usize| &input[i * CHUNK_LEN..input.len().min((i + 1) * CHUNK_LEN)];
+
+    let mut stack: Vec<Vec<UInt32<F>>> = Vec::new();
+    for i in 0..num_chunks - 1 {
+        let mut cv = chunk_chaining_value(key, chunk(i), i as u64, flags, false)?;
+        let mut total_chunks = i + 1;
+        while total_chunks % 2 == 0 {
+            cv = parent_chaining_value(key, &stack.pop().unwrap(), &cv, flags)?;
+            total_chunks >>= 1;
+        }
+        stack.push(cv);
+    }
+
+    let last = chunk(num_chunks - 1);
+    let root = match stack.pop() {
+        None => chunk_chaining_value(key, last, 0, flags, true)?,
+        Some(left) => {
+            let mut right = chunk_chaining_value(key, last, num_chunks as u64 - 1, flags, false)?;
+            let mut left = left;
+            while let Some(next_left) = stack.pop() {
+                right = parent_chaining_value(key, &left, &right, flags)?;
+                left = next_left;
+            }
+            parent_chaining_value(key, &left, &right, flags | ROOT)?
+        }
+    };
+
+    let mut output = Vec::with_capacity(32);
+    for word in root {
+        output.extend(word.to_bytes_le()?);
+    }
+    Ok(OutputVar(output))
+}
+
+pub struct CRHGadget<F: PrimeField> {
+    _field: PhantomData<F>,
+}
+
+impl<F: PrimeField> CRHSchemeGadget<CRH, F> for CRHGadget<F> {
+    type InputVar = [UInt8<F>];
+    type OutputVar = OutputVar<F>;
+    type ParametersVar = ParametersVar<F>;
+
+    #[tracing::instrument(target = "r1cs", skip(parameters, input))]
+    fn evaluate(
+        parameters: &Self::ParametersVar,
+        input: &Self::InputVar,
+    ) -> Result<Self::OutputVar, SynthesisError> {
+        let (key, flags) = parameters.key_words_and_flags();
+        hash(&key, flags, input)
+    }
+}
+
+pub struct TwoToOneCRHGadget<F: PrimeField> {
+    _field: PhantomData<F>,
+}
+
+impl<F: PrimeField> TwoToOneCRHSchemeGadget<TwoToOneCRH, F> for TwoToOneCRHGadget<F> {
+    type InputVar = [UInt8<F>];
+    type OutputVar = OutputVar<F>;
+    type ParametersVar = ParametersVar<F>;
+
+    #[tracing::instrument(target = "r1cs", skip(parameters))]
+    fn evaluate(
+        parameters: &Self::ParametersVar,
+        left_input: &Self::InputVar,
+        right_input: &Self::InputVar,
+    ) -> Result<Self::OutputVar, SynthesisError> {
+        let input: Vec<_> = left_input.iter().chain(right_input).cloned().collect();
+        CRHGadget::evaluate(parameters, &input)
+    }
+
+    #[tracing::instrument(target = "r1cs", skip(parameters))]
+    fn compress(
+        parameters: &Self::ParametersVar,
+        left_input: &Self::OutputVar,
+        right_input: &Self::OutputVar,
+    ) -> Result<Self::OutputVar, SynthesisError> {
+        Self::evaluate(parameters, &left_input.0, &right_input.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::crh::blake3::{
+        self,
+        constraints::{CRHGadget, ParametersVar, TwoToOneCRHGadget},
+        Parameters,
+    };
+    use crate::crh::{CRHScheme, CRHSchemeGadget, TwoToOneCRHScheme, TwoToOneCRHSchemeGadget};
+    use ark_ed_on_bls12_381::Fq as Fr;
+    use ark_r1cs_std::prelude::*;
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::rand::RngCore;
+    use ark_std::test_rng;
+
+    #[test]
+    fn test_blake3_crh_gadget() {
+        let rng = &mut test_rng();
+        let mut key = [0u8; 32];
+        rng.fill_bytes(&mut key);
+
+        // the lengths cover partial blocks, several blocks, and a tree of three chunks
+        for (len, key) in [
+            (0, None),
+            (1, Some(key)),
+            (64, None),
+            (65, Some(key)),
+            (2049, None),
+        ] {
+            let params = Parameters { key };
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let mut input = vec![0u8; len];
+            rng.fill_bytes(&mut input);
+
+            let params_var = ParametersVar::new_witness(cs.clone(), || Ok(&params)).unwrap();
+            let input_var = UInt8::new_witness_vec(cs.clone(), &input).unwrap();
+            let output_var = CRHGadget::evaluate(&params_var, &input_var).unwrap();
+
+            let output = blake3::CRH::evaluate(&params, input.as_slice()).unwrap();
+            assert_eq!(output_var.value().unwrap(), output);
+            assert!(cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_blake3_two_to_one_crh_gadget() {
+        let rng = &mut test_rng();
+        let params = blake3::TwoToOneCRH::setup(rng).unwrap();
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let params_var = ParametersVar::new_constant(cs.clone(), &params).unwrap();
+
+        let mut left = [0u8; 32];
+        let mut right = [0u8; 32];
+        rng.fill_bytes(&mut left);
+        rng.fill_bytes(&mut right);
+        let left_var = UInt8::new_witness_vec(cs.clone(), &left).unwrap();
+        let right_var = UInt8::new_witness_vec(cs.clone(), &right).unwrap();
+
+        let output =
+            blake3::TwoToOneCRH::evaluate(&params, left.as_slice(), right.as_slice()).unwrap();
+        let output_var = TwoToOneCRHGadget::evaluate(&params_var, &left_var, &right_var).unwrap();
+        assert_eq!(output_var.value().unwrap(), output);
+
+        let compressed = blake3::TwoToOneCRH::compress(&params, output, output).unwrap();
+        let compressed_var =
+            TwoToOneCRHGadget::compress(&params_var, &output_var, &output_var).unwrap();
+        assert_eq!(compressed_var.value().unwrap(), compressed);
+        assert!(cs.is_satisfied().unwrap());
+    }
+}
@@ -0,0 +1,352 @@
+use crate::crh::{CRHScheme, TwoToOneCRHScheme};
+use crate::Error;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::borrow::Borrow;
+use ark_std::rand::Rng;
+#[cfg(not(feature = "std"))]
+use ark_std::vec::Vec;
+
+#[cfg(feature = "r1cs")]
+pub mod constraints;
+
+// The BLAKE3 hash function, as specified in https://github.com/BLAKE3-team/BLAKE3-specs.
+// The input is split into chunks of 1024 bytes, which are compressed block by block into
+// chaining values. The chaining values are then merged pairwise by parent nodes, in a binary
+// tree whose left subtrees are complete.
+
+pub(crate) const BLOCK_LEN: usize = 64;
+pub(crate) const CHUNK_LEN: usize = 1024;
+
+pub(crate) const CHUNK_START: u32 = 1 << 0;
+pub(crate) const CHUNK_END: u32 = 1 << 1;
+pub(crate) const PARENT: u32 = 1 << 2;
+pub(crate) const ROOT: u32 = 1 << 3;
+pub(crate) const KEYED_HASH: u32 = 1 << 4;
+
+pub(crate) const IV: [u32; 8] = [
+    0x6A09E667, 0xBB67AE85, 0x3C6EF372, 0xA54FF53A, 0x510E527F, 0x9B05688C, 0x1F83D9AB, 0x5BE0CD19,
+];
+
+pub(crate) const MSG_PERMUTATION: [usize; 16] =
+    [2, 6, 3, 10, 7, 0, 4, 13, 1, 11, 12, 5, 9, 14, 15, 8];
+
+fn g(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize, mx: u32, my: u32) {
+    state[a] = state[a].wrapping_add(state[b]).wrapping_add(mx);
+    state[d] = (state[d] ^ state[a]).rotate_right(16);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_right(12);
+    state[a] = state[a].wrapping_add(state[b]).wrapping_add(my);
+    state[d] = (state[d] ^ state[a]).rotate_right(8);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_right(7);
+}
+
+/// The BLAKE3 compression function. Returns the 16 output words, whose first 8 words are the
+/// new chaining value.
+pub fn compress(
+    chaining_value: &[u32; 8],
+    block_words: &[u32; 16],
+    counter: u64,
+    block_len: u32,
+    flags: u32,
+) -> [u32; 16] {
+    let mut state = [
+        chaining_value[0],
+        chaining_value[1],
+        chaining_value[2],
+        chaining_value[3],
+        chaining_value[4],
+        chaining_value[5],
+        chaining_value[6],
+        chaining_value[7],
+        IV[0],
+        IV[1],
+        IV[2],
+        IV[3],
+        counter as u32,
+        (counter >> 32) as u32,
+        block_len,
+        flags,
+    ];
+    let mut m = *block_words;
+    for round in 0..7 {
+        g(&mut state, 0, 4, 8, 12, m[0], m[1]);
+        g(&mut state, 1, 5, 9, 13, m[2], m[3]);
+        g(&mut state, 2, 6, 10, 14, m[4], m[5]);
+        g(&mut state, 3, 7, 11, 15, m[6], m[7]);
+        g(&mut state, 0, 5, 10, 15, m[8], m[9]);
+        g(&mut state, 1, 6, 11, 12, m[10], m[11]);
+        g(&mut state, 2, 7, 8, 13, m[12], m[13]);
+        g(&mut state, 3, 4, 9, 14, m[14], m[15]);
+        if round < 6 {
+            m = MSG_PERMUTATION.map(|i| m[i]);
+        }
+    }
+    for i in 0..8 {
+        state[i] ^= state[i + 8];
+        state[i + 8] ^= chaining_value[i];
+    }
+    state
+}
+
+/// Reads little-endian words from at most 64 bytes, padded with zeros.
+fn block_words(block: &[u8]) -> [u32; 16] {
+    let mut words = [0u32; 16];
+    for (word, bytes) in words.iter_mut().zip(block.chunks(4)) {
+        let mut buf = [0u8; 4];
+        buf[..bytes.len()].copy_from_slice(bytes);
+        *word = u32::from_le_bytes(buf);
+    }
+    words
+}
+
+fn first_8_words(words: [u32; 16]) -> [u32; 8] {
+    let mut result = [0u32; 8];
+    result.copy_from_slice(&words[..8]);
+    result
+}
+
+/// Compresses a chunk of at most `CHUNK_LEN` bytes into its chaining value. If the chunk is
+/// the root node, `ROOT` is set on the compression of its last block.
+fn chunk_chaining_value(
+    key: &[u32; 8],
+    chunk: &[u8],
+    counter: u64,
+    flags: u32,
+    root: bool,
+) -> [u32; 8] {
+    let num_blocks = chunk.len().div_ceil(BLOCK_LEN).max(1);
+    let mut cv = *key;
+    for i in 0..num_blocks {
+        let block = &chunk[i * BLOCK_LEN..chunk.len().min((i + 1) * BLOCK_LEN)];
+        let mut block_flags = flags;
+        if i == 0 {
+            block_flags |= CHUNK_START;
+        }
+        if i == num_blocks - 1 {
+            block_flags |= CHUNK_END;
+            if root {
+                block_flags |= ROOT;
+            }
+        }
+        cv = first_8_words(compress(
+            &cv,
+            &block_words(block),
+            counter,
+            block.len() as u32,
+            block_flags,
+        ));
+    }
+    cv
+}
+
+fn parent_chaining_value(
+    key: &[u32; 8],
+    left: &[u32; 8],
+    right: &[u32; 8],
+    flags: u32,
+) -> [u32; 8] {
+    let mut block = [0u32; 16];
+    block[..8].copy_from_slice(left);
+    block[8..].copy_from_slice(right);
+    first_8_words(compress(key, &block, 0, BLOCK_LEN as u32, PARENT | flags))
+}
+
+/// Hashes `input` with the given key words and mode flags, into a 32-byte digest.
+fn hash(key: &[u32; 8], flags: u32, input: &[u8]) -> [u8; 32] {
+    let num_chunks = input.len().div_ceil(CHUNK_LEN).max(1);
+    let chunk = |i: usize| &input[i * CHUNK_LEN..input.len().min((i + 1) * CHUNK_LEN)];
+
+    // The chaining values of the complete subtrees on the left of the current chunk. After
+    // each chunk but the last, the subtrees of equal size are merged.
+    let mut stack: Vec<[u32; 8]> = Vec::new();
+    for i in 0..num_chunks - 1 {
+        let mut cv = chunk_chaining_value(key, chunk(i), i as u64, flags, false);
+        let mut total_chunks = i + 1;
+        while total_chunks % 2 == 0 {
+            cv = parent_chaining_value(key, &stack.pop().unwrap(), &cv, flags);
+            total_chunks >>= 1;
+        }
+        stack.push(cv);
+    }
+
+    // The root node is the last chunk if it is the only one, and a parent node otherwise.
+    let last = chunk(num_chunks - 1);
+    let root = match stack.pop() {
+        None => chunk_chaining_value(key, last, 0, flags, true),
+        Some(left) => {
+            let mut right = chunk_chaining_value(key, last, num_chunks as u64 - 1, flags, false);
+            let mut left = left;
+            while let Some(next_left) = stack.pop() {
+                right = parent_chaining_value(key, &left, &right, flags);
+                left = next_left;
+            }
+            parent_chaining_value(key, &left, &right, flags | ROOT)
+        }
+    };
+
+    let mut output = [0u8; 32];
+    for (bytes, word) in output.chunks_mut(4).zip(root) {
+        bytes.copy_from_slice(&word.to_le_bytes());
+    }
+    output
+}
+
+/// Returns the words of a 32-byte key.
+fn key_words(key: &[u8; 32]) -> [u32; 8] {
+    let mut words = [0u32; 8];
+    for (word, bytes) in words.iter_mut().zip(key.chunks(4)) {
+        *word = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    }
+    words
+}
+
+/// The parameters of the BLAKE3 CRH: a key for the keyed mode, or `None` for the default mode.
+#[derive(Clone, Debug, Default, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Parameters {
+    pub key: Option<[u8; 32]>,
+}
+
+impl Parameters {
+    /// Returns the initial chaining value and the flags of the mode.
+    pub(crate) fn key_words_and_flags(&self) -> ([u32; 8], u32) {
+        match &self.key {
+            None => (IV, 0),
+            Some(key) => (key_words(key), KEYED_HASH),
+        }
+    }
+}
+
+/// BLAKE3 with a 32-byte digest, in the default mode or in the keyed mode.
+pub struct CRH;
+
+impl CRHScheme for CRH {
+    type Input = [u8];
+    type Output = [u8; 32];
+    type Parameters = Parameters;
+
+    /// Returns the parameters of the default (unkeyed) mode.
+    fn setup<R: Rng>(_rng: &mut R) -> Result<Self::Parameters, Error> {
+        Ok(Parameters::default())
+    }
+
+    fn evaluate<T: Borrow<Self::Input>>(
+        parameters: &Self::Parameters,
+        input: T,
+    ) -> Result<Self::Output, Error> {
+        let (key, flags) = parameters.key_words_and_flags();
+        Ok(hash(&key, flags, input.borrow()))
+    }
+}
+
+/// BLAKE3 with a 32-byte digest of the concatenation of the two inputs.
+pub struct TwoToOneCRH;
+
+impl TwoToOneCRHScheme for TwoToOneCRH {
+    type Input = [u8];
+    type Output = [u8; 32];
+    type Parameters = Parameters;
+
+    fn setup<R: Rng>(rng: &mut R) -> Result<Self::Parameters, Error> {
+        CRH::setup(rng)
+    }
+
+    fn evaluate<T: Borrow<Self::Input>>(
+        parameters: &Self::Parameters,
+        left_input: T,
+        right_input: T,
+    ) -> Result<Self::Output, Error> {
+        let input: Vec<u8> = [left_input.borrow(), right_input.borrow()].concat();
+        CRH::evaluate(parameters, input)
+    }
+
+    fn compress<T: Borrow<Self::Output>>(
+        parameters: &Self::Parameters,
+        left_input: T,
+        right_input: T,
+    ) -> Result<Self::Output, Error> {
+        <Self as TwoToOneCRHScheme>::evaluate(
+            parameters,
+            left_input.borrow().as_slice(),
+            right_input.borrow().as_slice(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::crh::blake3::{self, Parameters};
+    use crate::crh::CRHScheme;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    #[test]
+    fn test_vectors() {
+        let params = Parameters::default();
+        let digest = blake3::CRH::evaluate(&params, b"".as_slice()).unwrap();
+        assert_eq!(
+            hex(&digest),
+            "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262"
+        );
+        let digest = blake3::CRH::evaluate(&params, b"abc".as_slice()).unwrap();
+        assert_eq!(
+            hex(&digest),
+            "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85"
+        );
+
+        let params = Parameters {
+            key: Some(*b"whats the Elvish word for friend"),
+        };
+        let digest = blake3::CRH::evaluate(&params, b"".as_slice()).unwrap();
+        assert_eq!(
+            hex(&digest),
+            "92b2b75604ed3c761f9d6f62392c8a9227ad0ea3f09573e783f1498a4ed60d26"
+        );
+    }
+
+    #[test]
+    fn official_test_vectors() {
+        // from `test_vectors.json` of the reference implementation: the input bytes are
+        // `i % 251`, and the keyed hashes use the key `whats the Elvish word for friend`
+        let vectors = [
+            (
+                65,
+                "de1e5fa0be70df6d2be8fffd0e99ceaa8eb6e8c93a63f2d8d1c30ecb6b263dee",
+                "c0a4edefa2d2accb9277c371ac12fcdbb52988a86edc54f0716e1591b4326e72",
+            ),
+            (
+                1024,
+                "42214739f095a406f3fc83deb889744ac00df831c10daa55189b5d121c855af7",
+                "75c46f6f3d9eb4f55ecaaee480db732e6c2105546f1e675003687c31719c7ba4",
+            ),
+            (
+                1025,
+                "d00278ae47eb27b34faecf67b4fe263f82d5412916c1ffd97c8cb7fb814b8444",
+                "357dc55de0c7e382c900fd6e320acc04146be01db6a8ce7210b7189bd664ea69",
+            ),
+            (
+                2049,
+                "5f4d72f40d7a5f82b15ca2b2e44b1de3c2ef86c426c95c1af0b6879522563030",
+                "9f29700902f7c86e514ddc4df1e3049f258b2472b6dd5267f61bf13983b78dd5",
+            ),
+            (
+                3073,
+                "7124b49501012f81cc7f11ca069ec9226cecb8a2c850cfe644e327d22d3e1cd3",
+                "68dede9bef00ba89e43f31a6825f4cf433389fedae75c04ee9f0cf16a427c95a",
+            ),
+        ];
+        let unkeyed = Parameters::default();
+        let keyed = Parameters {
+            key: Some(*b"whats the Elvish word for friend"),
+        };
+        for (len, hash, keyed_hash) in vectors {
+            let input: Vec<u8> = (0..len).map(|i| (i % 251) as u8).collect();
+            let digest = blake3::CRH::evaluate(&unkeyed, input.as_slice()).unwrap();
+            assert_eq!(hex(&digest), hash, "length {len}");
+            let digest = blake3::CRH::evaluate(&keyed, input.as_slice()).unwrap();
+            assert_eq!(hex(&digest), keyed_hash, "keyed, length {len}");
+        }
+    }
+}
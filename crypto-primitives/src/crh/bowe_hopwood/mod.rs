@@ -18,7 +18,9 @@ use ark_ec::{
     CurveGroup,
 };
 use ark_ff::fields::PrimeField;
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
+};
 use ark_std::borrow::Borrow;
 use ark_std::cfg_chunks;
 #[cfg(not(feature = "std"))]
@@ -36,49 +38,148 @@ pub struct Parameters<P: TECurveConfig> {
     pub generators: Vec<Vec<TEProjective<P>>>,
 }
 
-pub struct CRH<P: TECurveConfig, W: pedersen::Window> {
-    group: PhantomData<P>,
-    window: PhantomData<W>,
+/// The parameters of the CRH, extended with the precomputed tables of their segments, which
+/// speed up the native evaluation with [`CRH::evaluate_with_tables`].
+///
+/// When deserialized with validation, the tables are checked against the generators.
+#[derive(Derivative, CanonicalSerialize)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub struct PrecomputedParameters<P: TECurveConfig> {
+    parameters: Parameters<P>,
+    tables: pedersen::WindowTables<TEProjective<P>>,
 }
 
-impl<P: TECurveConfig, W: pedersen::Window> CRH<P, W> {
-    pub fn create_generators<R: Rng>(rng: &mut R) -> Vec<Vec<TEProjective<P>>> {
-        let mut generators = Vec::new();
-        for _ in 0..W::NUM_WINDOWS {
-            let mut generators_for_segment = Vec::new();
-            let mut base = TEProjective::rand(rng);
-            for _ in 0..W::WINDOW_SIZE {
-                generators_for_segment.push(base);
-                for _ in 0..4 {
-                    base.double_in_place();
-                }
-            }
-            generators.push(generators_for_segment);
-        }
-        generators
+impl<P: TECurveConfig> PrecomputedParameters<P> {
+    /// Precomputes the tables of parts of `chunks_per_table` 3-bit chunks of the segments, whose
+    /// entries are the sums of the encoded chunks times their generators. Evaluating the CRH
+    /// then costs one lookup and one addition per part, instead of one addition per chunk.
+    pub fn new(parameters: Parameters<P>, chunks_per_table: usize) -> Result<Self, Error> {
+        let table_bits = chunks_per_table * CHUNK_SIZE;
+        pedersen::check_table_bits(table_bits)?;
+        let tables = cfg_iter!(parameters.generators)
+            .map(|segment_generators| {
+                segment_generators
+                    .chunks(chunks_per_table)
+                    .map(|generators| {
+                        // the entry of `m` is that of its low chunks, plus its high chunk
+                        let mut table = vec![TEProjective::ZERO];
+                        for generator in generators {
+                            let encoded: Vec<_> = (0..1u8 << CHUNK_SIZE)
+                                .map(|chunk| {
+                                    encode_chunk(generator, &pedersen::bytes_to_bits(&[chunk]))
+                                })
+                                .collect();
+                            table = encoded
+                                .iter()
+                                .flat_map(|encoded| table.iter().map(move |low| *low + encoded))
+                                .collect();
+                        }
+                        TEProjective::normalize_batch(&table)
+                    })
+                    .collect()
+            })
+            .collect();
+        Ok(Self {
+            parameters,
+            tables: pedersen::WindowTables { table_bits, tables },
+        })
+    }
+
+    pub fn parameters(&self) -> &Parameters<P> {
+        &self.parameters
+    }
+
+    pub fn tables(&self) -> &pedersen::WindowTables<TEProjective<P>> {
+        &self.tables
     }
 }
 
-pub struct TwoToOneCRH<P: TECurveConfig, W: pedersen::Window> {
-    group: PhantomData<P>,
-    window: PhantomData<W>,
+impl<P: TECurveConfig> Valid for PrecomputedParameters<P> {
+    /// Checks that the tables are those of the generators: the entry of the zero chunks must be
+    /// the sum of the generators, and each entry in which a single chunk is not zero must differ
+    /// from it by the encoding of that chunk. The other entries are not recomputed.
+    fn check(&self) -> Result<(), SerializationError> {
+        self.parameters.check()?;
+        self.tables.check()?;
+        let generators = &self.parameters.generators;
+        let chunks_per_table = self.tables.table_bits / CHUNK_SIZE;
+        let consistent = self.tables.matches_shape(generators, CHUNK_SIZE)
+            && self
+                .tables
+                .tables
+                .iter()
+                .zip(generators)
+                .all(|(tables, generators)| {
+                    tables.iter().zip(generators.chunks(chunks_per_table)).all(
+                        |(table, generators)| {
+                            let zero_chunks = generators
+                                .iter()
+                                .fold(TEProjective::ZERO, |sum, generator| sum + generator);
+                            TEProjective::from(table[0]) == zero_chunks
+                                && generators.iter().enumerate().all(|(k, generator)| {
+                                    (1..1u8 << CHUNK_SIZE).all(|chunk| {
+                                        let encoded = encode_chunk(
+                                            generator,
+                                            &pedersen::bytes_to_bits(&[chunk]),
+                                        );
+                                        TEProjective::from(
+                                            table[(chunk as usize) << (CHUNK_SIZE * k)],
+                                        ) == zero_chunks - generator + encoded
+                                    })
+                                })
+                        },
+                    )
+                });
+        if !consistent {
+            return Err(SerializationError::InvalidData);
+        }
+        Ok(())
+    }
 }
 
-impl<P: TECurveConfig, W: pedersen::Window> TwoToOneCRH<P, W> {
-    const INPUT_SIZE_BITS: usize = pedersen::CRH::<TEProjective<P>, W>::INPUT_SIZE_BITS;
-    const HALF_INPUT_SIZE_BITS: usize = Self::INPUT_SIZE_BITS / 2;
-    pub fn create_generators<R: Rng>(rng: &mut R) -> Vec<Vec<TEProjective<P>>> {
-        CRH::<P, W>::create_generators(rng)
+impl<P: TECurveConfig> CanonicalDeserialize for PrecomputedParameters<P> {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let parameters = Parameters::deserialize_with_mode(&mut reader, compress, Validate::No)?;
+        let tables =
+            pedersen::WindowTables::deserialize_with_mode(&mut reader, compress, Validate::No)?;
+        let result = Self { parameters, tables };
+        if let Validate::Yes = validate {
+            result.check()?;
+        }
+        Ok(result)
     }
 }
 
-impl<P: TECurveConfig, W: pedersen::Window> CRHScheme for CRH<P, W> {
-    type Input = [u8];
+/// Returns `(1 - 2 * c_2) * (1 + c_0 + 2 * c_1) * generator` for the 3-bit chunk `c`.
+fn encode_chunk<P: TECurveConfig>(
+    generator: &TEProjective<P>,
+    chunk_bits: &[bool],
+) -> TEProjective<P> {
+    let mut encoded = *generator;
+    if chunk_bits[0] {
+        encoded += generator;
+    }
+    if chunk_bits[1] {
+        encoded += &generator.double();
+    }
+    if chunk_bits[2] {
+        encoded = -encoded;
+    }
+    encoded
+}
 
-    type Output = P::BaseField;
-    type Parameters = Parameters<P>;
+pub struct CRH<P: TECurveConfig, W: pedersen::Window> {
+    group: PhantomData<P>,
+    window: PhantomData<W>,
+}
 
-    fn setup<R: Rng>(rng: &mut R) -> Result<Self::Parameters, Error> {
+impl<P: TECurveConfig, W: pedersen::Window> CRH<P, W> {
+    /// Panics if the window size results in scalars which are not smaller than `(p-1)/2`.
+    fn check_window_size() {
         fn calculate_num_chunks_in_segment<F: PrimeField>() -> usize {
             let upper_limit = F::MODULUS_MINUS_ONE_DIV_TWO;
             let mut c = 0;
@@ -99,23 +200,35 @@ impl<P: TECurveConfig, W: pedersen::Window> CRHScheme for CRH<P, W> {
                 maximum_num_chunks_in_segment
             );
         }
+    }
 
-        let time = start_timer!(|| format!(
-            "Bowe-Hopwood-PedersenCRH::Setup: {} segments of {} 3-bit chunks; {{0,1}}^{{{}}} -> P",
-            W::NUM_WINDOWS,
-            W::WINDOW_SIZE,
-            W::WINDOW_SIZE * W::NUM_WINDOWS * CHUNK_SIZE
-        ));
-        let generators = Self::create_generators(rng);
-        end_timer!(time);
-        Ok(Self::Parameters { generators })
+    pub fn create_generators<R: Rng>(rng: &mut R) -> Vec<Vec<TEProjective<P>>> {
+        let mut generators = Vec::new();
+        for _ in 0..W::NUM_WINDOWS {
+            generators.push(Self::segment_generators(TEProjective::rand(rng)));
+        }
+        generators
     }
 
-    fn evaluate<T: Borrow<Self::Input>>(
-        parameters: &Self::Parameters,
-        input: T,
-    ) -> Result<Self::Output, Error> {
-        let input = input.borrow();
+    /// Returns the generators of a segment: `2^(4 * j) * base` for the `j`-th chunk.
+    fn segment_generators(mut base: TEProjective<P>) -> Vec<TEProjective<P>> {
+        let mut generators_for_segment = Vec::new();
+        for _ in 0..W::WINDOW_SIZE {
+            generators_for_segment.push(base);
+            for _ in 0..4 {
+                base.double_in_place();
+            }
+        }
+        generators_for_segment
+    }
+
+    /// Evaluates the CRH without normalizing the output, with the `tables` of the generators of
+    /// the parameters if they are given.
+    fn evaluate_projective(
+        parameters: &Parameters<P>,
+        tables: Option<&pedersen::WindowTables<TEProjective<P>>>,
+        input: &[u8],
+    ) -> TEProjective<P> {
         let eval_time = start_timer!(|| "BoweHopwoodPedersenCRH::Eval");
 
         if (input.len() * 8) > W::WINDOW_SIZE * W::NUM_WINDOWS * CHUNK_SIZE {
@@ -158,54 +271,80 @@ impl<P: TECurveConfig, W: pedersen::Window> CRHScheme for CRH<P, W> {
         // for all i. Described in section 5.4.1.7 in the Zcash protocol
         // specification.
 
-        let result = cfg_chunks!(padded_input, W::WINDOW_SIZE * CHUNK_SIZE)
-            .zip(&parameters.generators)
-            .map(|(segment_bits, segment_generators)| {
-                cfg_chunks!(segment_bits, CHUNK_SIZE)
-                    .zip(segment_generators)
-                    .map(|(chunk_bits, generator)| {
-                        let mut encoded = *generator;
-                        if chunk_bits[0] {
-                            encoded += generator;
-                        }
-                        if chunk_bits[1] {
-                            encoded += &generator.double();
-                        }
-                        if chunk_bits[2] {
-                            encoded = -encoded;
-                        }
-                        encoded
+        let result = match tables {
+            Some(tables) => {
+                let chunks_per_table = tables.table_bits / CHUNK_SIZE;
+                cfg_chunks!(padded_input, W::WINDOW_SIZE * CHUNK_SIZE)
+                    .zip(&tables.tables)
+                    .zip(&parameters.generators)
+                    .map(|((segment_bits, segment_tables), segment_generators)| {
+                        segment_bits
+                            .chunks(tables.table_bits)
+                            .zip(segment_tables)
+                            .zip(segment_generators.chunks(chunks_per_table))
+                            .fold(TEProjective::ZERO, |sum, ((bits, table), generators)| {
+                                if bits.len() == generators.len() * CHUNK_SIZE {
+                                    sum + table[pedersen::to_index(bits)]
+                                } else {
+                                    // a short part cannot be looked up, as the missing
+                                    // chunks would be encoded as zero chunks
+                                    bits.chunks(CHUNK_SIZE).zip(generators).fold(
+                                        sum,
+                                        |sum, (chunk_bits, generator)| {
+                                            sum + encode_chunk(generator, chunk_bits)
+                                        },
+                                    )
+                                }
+                            })
                     })
                     .sum::<TEProjective<P>>()
-            })
-            .sum::<TEProjective<P>>();
+            }
+            None => cfg_chunks!(padded_input, W::WINDOW_SIZE * CHUNK_SIZE)
+                .zip(&parameters.generators)
+                .map(|(segment_bits, segment_generators)| {
+                    cfg_chunks!(segment_bits, CHUNK_SIZE)
+                        .zip(segment_generators)
+                        .map(|(chunk_bits, generator)| encode_chunk(generator, chunk_bits))
+                        .sum::<TEProjective<P>>()
+                })
+                .sum::<TEProjective<P>>(),
+        };
 
         end_timer!(eval_time);
 
-        Ok(result.into_affine().x)
+        result
     }
-}
 
-impl<P: TECurveConfig, W: pedersen::Window> TwoToOneCRHScheme for TwoToOneCRH<P, W> {
-    type Input = [u8];
+    /// Evaluates the CRH as [`CRHScheme::evaluate`], with the precomputed tables of the
+    /// parameters.
+    pub fn evaluate_with_tables(
+        parameters: &PrecomputedParameters<P>,
+        input: &[u8],
+    ) -> Result<P::BaseField, Error> {
+        Ok(
+            Self::evaluate_projective(&parameters.parameters, Some(&parameters.tables), input)
+                .into_affine()
+                .x,
+        )
+    }
+}
 
-    type Output = P::BaseField;
-    type Parameters = Parameters<P>;
+pub struct TwoToOneCRH<P: TECurveConfig, W: pedersen::Window> {
+    group: PhantomData<P>,
+    window: PhantomData<W>,
+}
 
-    fn setup<R: Rng>(r: &mut R) -> Result<Self::Parameters, Error> {
-        CRH::<P, W>::setup(r)
+impl<P: TECurveConfig, W: pedersen::Window> TwoToOneCRH<P, W> {
+    const INPUT_SIZE_BITS: usize = pedersen::CRH::<TEProjective<P>, W>::INPUT_SIZE_BITS;
+    const HALF_INPUT_SIZE_BITS: usize = Self::INPUT_SIZE_BITS / 2;
+    pub fn create_generators<R: Rng>(rng: &mut R) -> Vec<Vec<TEProjective<P>>> {
+        CRH::<P, W>::create_generators(rng)
     }
 
-    /// A simple implementation method: just concat the left input and right input together
+    /// Concatenates `left_input` and `right_input` into an input of `CRH`.
     ///
-    /// `evaluate` requires that `left_input` and `right_input` are of equal length.
-    fn evaluate<T: Borrow<Self::Input>>(
-        parameters: &Self::Parameters,
-        left_input: T,
-        right_input: T,
-    ) -> Result<Self::Output, Error> {
-        let left_input = left_input.borrow();
-        let right_input = right_input.borrow();
+    /// `left_input` and `right_input` must be of equal length.
+    fn concat(left_input: &[u8], right_input: &[u8]) -> Vec<u8> {
         assert_eq!(
             left_input.len(),
             right_input.len(),
@@ -222,8 +361,100 @@ impl<P: TECurveConfig, W: pedersen::Window> TwoToOneCRHScheme for TwoToOneCRH<P,
             .iter_mut()
             .zip(left_input.iter().chain(right_input.iter()))
             .for_each(|(b, l_b)| *b = *l_b);
+        buffer
+    }
+}
+
+impl<P: TECurveConfig, W: pedersen::Window> CRH<P, W>
+where
+    P::BaseField: PrimeField,
+{
+    /// Derives the generators deterministically from `personalization`, which must be at most 8
+    /// bytes: the base of the `i`-th segment is `FindGroupHash(personalization, I2LEOSP_32(i))`.
+    ///
+    /// With the personalization `"Zcash_PH"` on Jubjub, these are the generators of the Sapling
+    /// Pedersen hash.
+    pub fn setup_from_domain(personalization: &[u8]) -> Result<Parameters<P>, Error> {
+        Self::check_window_size();
+        let bases = pedersen::find_group_hashes(personalization, 0..W::NUM_WINDOWS)?;
+        let generators = bases.into_iter().map(Self::segment_generators).collect();
+        Ok(Parameters { generators })
+    }
+}
+
+impl<P: TECurveConfig, W: pedersen::Window> CRHScheme for CRH<P, W> {
+    type Input = [u8];
+
+    type Output = P::BaseField;
+    type Parameters = Parameters<P>;
+
+    fn setup<R: Rng>(rng: &mut R) -> Result<Self::Parameters, Error> {
+        Self::check_window_size();
+        let time = start_timer!(|| format!(
+            "Bowe-Hopwood-PedersenCRH::Setup: {} segments of {} 3-bit chunks; {{0,1}}^{{{}}} -> P",
+            W::NUM_WINDOWS,
+            W::WINDOW_SIZE,
+            W::WINDOW_SIZE * W::NUM_WINDOWS * CHUNK_SIZE
+        ));
+        let generators = Self::create_generators(rng);
+        end_timer!(time);
+        Ok(Self::Parameters { generators })
+    }
+
+    fn evaluate<T: Borrow<Self::Input>>(
+        parameters: &Self::Parameters,
+        input: T,
+    ) -> Result<Self::Output, Error> {
+        Ok(Self::evaluate_projective(parameters, None, input.borrow())
+            .into_affine()
+            .x)
+    }
 
-        CRH::<P, W>::evaluate(parameters, buffer)
+    fn batch_evaluate<T: Borrow<Self::Input> + Sync>(
+        parameters: &Self::Parameters,
+        inputs: &[T],
+    ) -> Result<Vec<Self::Output>, Error> {
+        let results: Vec<_> = cfg_iter!(inputs)
+            .map(|input| Self::evaluate_projective(parameters, None, input.borrow()))
+            .collect();
+        Ok(TEProjective::normalize_batch(&results)
+            .into_iter()
+            .map(|point| point.x)
+            .collect())
+    }
+}
+
+impl<P: TECurveConfig, W: pedersen::Window> TwoToOneCRH<P, W>
+where
+    P::BaseField: PrimeField,
+{
+    pub fn setup_from_domain(personalization: &[u8]) -> Result<Parameters<P>, Error> {
+        CRH::<P, W>::setup_from_domain(personalization)
+    }
+}
+
+impl<P: TECurveConfig, W: pedersen::Window> TwoToOneCRHScheme for TwoToOneCRH<P, W> {
+    type Input = [u8];
+
+    type Output = P::BaseField;
+    type Parameters = Parameters<P>;
+
+    fn setup<R: Rng>(r: &mut R) -> Result<Self::Parameters, Error> {
+        CRH::<P, W>::setup(r)
+    }
+
+    /// A simple implementation method: just concat the left input and right input together
+    ///
+    /// `evaluate` requires that `left_input` and `right_input` are of equal length.
+    fn evaluate<T: Borrow<Self::Input>>(
+        parameters: &Self::Parameters,
+        left_input: T,
+        right_input: T,
+    ) -> Result<Self::Output, Error> {
+        CRH::<P, W>::evaluate(
+            parameters,
+            Self::concat(left_input.borrow(), right_input.borrow()),
+        )
     }
 
     fn compress<T: Borrow<Self::Output>>(
@@ -237,6 +468,31 @@ impl<P: TECurveConfig, W: pedersen::Window> TwoToOneCRHScheme for TwoToOneCRH<P,
             crate::to_uncompressed_bytes!(right_input)?,
         )
     }
+
+    fn batch_evaluate<T: Borrow<Self::Input> + Sync>(
+        parameters: &Self::Parameters,
+        inputs: &[(T, T)],
+    ) -> Result<Vec<Self::Output>, Error> {
+        let buffers: Vec<_> = cfg_iter!(inputs)
+            .map(|(left, right)| Self::concat(left.borrow(), right.borrow()))
+            .collect();
+        CRH::<P, W>::batch_evaluate(parameters, &buffers)
+    }
+
+    fn batch_compress<T: Borrow<Self::Output> + Sync>(
+        parameters: &Self::Parameters,
+        inputs: &[(T, T)],
+    ) -> Result<Vec<Self::Output>, Error> {
+        let inputs = cfg_iter!(inputs)
+            .map(|(left, right)| {
+                Ok((
+                    crate::to_uncompressed_bytes!(left)?,
+                    crate::to_uncompressed_bytes!(right)?,
+                ))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        Self::batch_evaluate(parameters, &inputs)
+    }
 }
 
 impl<P: TECurveConfig> Debug for Parameters<P> {
@@ -251,9 +507,10 @@ impl<P: TECurveConfig> Debug for Parameters<P> {
 
 #[cfg(test)]
 mod test {
-    use crate::crh::{bowe_hopwood, pedersen::Window, CRHScheme};
-    use ark_ed_on_bls12_381::EdwardsConfig;
-    use ark_std::test_rng;
+    use crate::crh::{bowe_hopwood, pedersen, pedersen::Window, CRHScheme, TwoToOneCRHScheme};
+    use ark_ed_on_bls12_381::{EdwardsConfig, EdwardsProjective};
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+    use ark_std::{test_rng, UniformRand};
 
     #[test]
     fn test_simple_bh() {
@@ -269,4 +526,130 @@ mod test {
         let _ =
             bowe_hopwood::CRH::<EdwardsConfig, TestWindow>::evaluate(&params, [1, 2, 3]).unwrap();
     }
+
+    #[test]
+    fn test_batch_evaluate() {
+        #[derive(Clone)]
+        struct TestWindow {}
+        impl Window for TestWindow {
+            const WINDOW_SIZE: usize = 63;
+            const NUM_WINDOWS: usize = 8;
+        }
+        type BHCRH = bowe_hopwood::CRH<EdwardsConfig, TestWindow>;
+        type BHTwoToOneCRH = bowe_hopwood::TwoToOneCRH<EdwardsConfig, TestWindow>;
+        type PedersenCRH = pedersen::CRH<EdwardsProjective, TestWindow>;
+        type PedersenTwoToOneCRH = pedersen::TwoToOneCRH<EdwardsProjective, TestWindow>;
+
+        let rng = &mut test_rng();
+        let inputs: Vec<Vec<u8>> = (0..5)
+            .map(|_| (0..31).map(|_| u8::rand(rng)).collect())
+            .collect();
+        let pairs: Vec<_> = inputs
+            .chunks(2)
+            .take(2)
+            .map(|p| (p[0].as_slice(), p[1].as_slice()))
+            .collect();
+
+        let params = BHCRH::setup(rng).unwrap();
+        let expected: Vec<_> = inputs
+            .iter()
+            .map(|input| BHCRH::evaluate(&params, input.as_slice()).unwrap())
+            .collect();
+        assert_eq!(BHCRH::batch_evaluate(&params, &inputs).unwrap(), expected);
+        let expected: Vec<_> = pairs
+            .iter()
+            .map(|(l, r)| BHTwoToOneCRH::evaluate(&params, *l, *r).unwrap())
+            .collect();
+        assert_eq!(
+            BHTwoToOneCRH::batch_evaluate(&params, &pairs).unwrap(),
+            expected
+        );
+
+        let params = PedersenCRH::setup(rng).unwrap();
+        let expected: Vec<_> = inputs
+            .iter()
+            .map(|input| PedersenCRH::evaluate(&params, input.as_slice()).unwrap())
+            .collect();
+        assert_eq!(
+            PedersenCRH::batch_evaluate(&params, &inputs).unwrap(),
+            expected
+        );
+        let expected: Vec<_> = pairs
+            .iter()
+            .map(|(l, r)| PedersenTwoToOneCRH::evaluate(&params, *l, *r).unwrap())
+            .collect();
+        assert_eq!(
+            PedersenTwoToOneCRH::batch_evaluate(&params, &pairs).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_setup_from_domain() {
+        #[derive(Clone)]
+        struct TestWindow {}
+        impl Window for TestWindow {
+            const WINDOW_SIZE: usize = 63;
+            const NUM_WINDOWS: usize = 8;
+        }
+        type BHCRH = bowe_hopwood::CRH<EdwardsConfig, TestWindow>;
+        type PedersenCRH = pedersen::CRH<EdwardsProjective, TestWindow>;
+
+        // both hashes derive the bases of their segments in the same way
+        let params = BHCRH::setup_from_domain(b"Zcash_PH").unwrap();
+        let pedersen_params = PedersenCRH::setup_from_domain(b"Zcash_PH").unwrap();
+        for (segment, window) in params.generators.iter().zip(&pedersen_params.generators) {
+            assert_eq!(segment[0], window[0]);
+            assert_eq!(segment[1], window[4]);
+        }
+        let _ = BHCRH::evaluate(&params, [1, 2, 3]).unwrap();
+    }
+
+    #[test]
+    fn test_tables() {
+        #[derive(Clone)]
+        struct TestWindow {}
+        impl Window for TestWindow {
+            const WINDOW_SIZE: usize = 63;
+            const NUM_WINDOWS: usize = 8;
+        }
+        type BHCRH = bowe_hopwood::CRH<EdwardsConfig, TestWindow>;
+
+        let rng = &mut test_rng();
+        let params = BHCRH::setup(rng).unwrap();
+        // parts of 2 chunks do not divide the segments of 63 chunks
+        let precomputed = bowe_hopwood::PrecomputedParameters::new(params.clone(), 2).unwrap();
+        for len in [0, 1, 24, 100, 189] {
+            let input: Vec<u8> = (0..len).map(|_| u8::rand(rng)).collect();
+            assert_eq!(
+                BHCRH::evaluate_with_tables(&precomputed, input.as_slice()).unwrap(),
+                BHCRH::evaluate(&params, input.as_slice()).unwrap()
+            );
+        }
+
+        let mut bytes = Vec::new();
+        precomputed.serialize_compressed(&mut bytes).unwrap();
+        let deserialized =
+            bowe_hopwood::PrecomputedParameters::<EdwardsConfig>::deserialize_compressed(&*bytes)
+                .unwrap();
+        assert_eq!(deserialized.tables(), precomputed.tables());
+
+        // tables which are not those of the generators are rejected
+        let mut wrong_entry = precomputed.clone();
+        wrong_entry.tables.tables[1][2][5 << 3] = wrong_entry.tables.tables[1][2][4 << 3];
+        let mut wrong_chunk_size = precomputed.clone();
+        wrong_chunk_size.tables.table_bits = 4;
+        let mut other_generators = precomputed.clone();
+        other_generators.parameters = BHCRH::setup(rng).unwrap();
+        for tables in [wrong_entry, wrong_chunk_size, other_generators] {
+            let mut bytes = Vec::new();
+            tables.serialize_compressed(&mut bytes).unwrap();
+            assert!(matches!(
+                bowe_hopwood::PrecomputedParameters::<EdwardsConfig>::deserialize_compressed(
+                    &*bytes
+                ),
+                Err(SerializationError::InvalidData)
+            ));
+        }
+    }
 }
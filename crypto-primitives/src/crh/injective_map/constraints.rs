@@ -1,22 +1,31 @@
 use crate::crh::{
     constraints,
-    injective_map::{InjectiveMap, PedersenCRHCompressor, TECompressor},
+    injective_map::{
+        InjectiveMap, PedersenCRHCompressor, SWCompressed, SWCompressor, TECompressor,
+    },
     pedersen::{constraints as ped_constraints, Window},
     TwoToOneCRHSchemeGadget,
 };
-use ark_std::{fmt::Debug, marker::PhantomData};
+#[cfg(not(feature = "std"))]
+use ark_std::vec::Vec;
+use ark_std::{borrow::Borrow, fmt::Debug, marker::PhantomData};
 
 use crate::crh::injective_map::PedersenTwoToOneCRHCompressor;
 use crate::crh::CRHSchemeGadget;
 use ark_ec::{
+    short_weierstrass::{Projective as SWProjective, SWCurveConfig},
     twisted_edwards::{Projective as TEProjective, TECurveConfig},
     CurveConfig, CurveGroup,
 };
 use ark_ff::fields::{Field, PrimeField};
 use ark_r1cs_std::{
-    fields::fp::FpVar, groups::curves::twisted_edwards::AffineVar as TEVar, prelude::*,
+    fields::fp::FpVar,
+    groups::curves::{
+        short_weierstrass::ProjectiveVar as SWVar, twisted_edwards::AffineVar as TEVar,
+    },
+    prelude::*,
 };
-use ark_relations::r1cs::SynthesisError;
+use ark_relations::r1cs::{ConstraintSystemRef, Namespace, SynthesisError};
 
 type ConstraintF<C> = <<C as CurveGroup>::BaseField as Field>::BasePrimeField;
 
@@ -51,6 +60,103 @@ where
     }
 }
 
+pub struct SWCompressorGadget;
+
+/// The gadget of [`SWCompressed`]: the x-coordinate of a point, and the parity of its
+/// y-coordinate.
+#[derive(Clone, Debug)]
+pub struct SWCompressedVar<F: PrimeField> {
+    pub x: FpVar<F>,
+    pub y_is_odd: Boolean<F>,
+}
+
+impl<F, P> InjectiveMapGadget<SWProjective<P>, SWCompressor, SWVar<P, FpVar<F>>>
+    for SWCompressorGadget
+where
+    F: PrimeField,
+    P: SWCurveConfig + CurveConfig<BaseField = F>,
+{
+    type OutputVar = SWCompressedVar<F>;
+
+    /// The constraints are unsatisfied if `ge` is the point at infinity.
+    fn evaluate(ge: &SWVar<P, FpVar<F>>) -> Result<Self::OutputVar, SynthesisError> {
+        let ge = ge.to_affine()?;
+        ge.infinity.enforce_equal(&Boolean::FALSE)?;
+        // the parity is the lowest bit of the canonical representation
+        let y_is_odd = ge.y.to_bits_le()?[0].clone();
+        Ok(SWCompressedVar { x: ge.x, y_is_odd })
+    }
+}
+
+impl<F: PrimeField> R1CSVar<F> for SWCompressedVar<F> {
+    type Value = SWCompressed<F>;
+
+    fn cs(&self) -> ConstraintSystemRef<F> {
+        self.x.cs().or(self.y_is_odd.cs())
+    }
+
+    fn value(&self) -> Result<Self::Value, SynthesisError> {
+        Ok(SWCompressed {
+            x: self.x.value()?,
+            y_is_odd: self.y_is_odd.value()?,
+        })
+    }
+}
+
+impl<F: PrimeField> AllocVar<SWCompressed<F>, F> for SWCompressedVar<F> {
+    fn new_variable<T: Borrow<SWCompressed<F>>>(
+        cs: impl Into<Namespace<F>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        let ns = cs.into();
+        let cs = ns.cs();
+        let value = f().map(|value| *value.borrow());
+        let x = FpVar::new_variable(cs.clone(), || value.map(|value| value.x), mode)?;
+        let y_is_odd = Boolean::new_variable(cs, || value.map(|value| value.y_is_odd), mode)?;
+        Ok(Self { x, y_is_odd })
+    }
+}
+
+impl<F: PrimeField> EqGadget<F> for SWCompressedVar<F> {
+    fn is_eq(&self, other: &Self) -> Result<Boolean<F>, SynthesisError> {
+        Ok(self.x.is_eq(&other.x)? & &self.y_is_odd.is_eq(&other.y_is_odd)?)
+    }
+}
+
+impl<F: PrimeField> CondSelectGadget<F> for SWCompressedVar<F> {
+    fn conditionally_select(
+        cond: &Boolean<F>,
+        true_value: &Self,
+        false_value: &Self,
+    ) -> Result<Self, SynthesisError> {
+        Ok(Self {
+            x: FpVar::conditionally_select(cond, &true_value.x, &false_value.x)?,
+            y_is_odd: Boolean::conditionally_select(
+                cond,
+                &true_value.y_is_odd,
+                &false_value.y_is_odd,
+            )?,
+        })
+    }
+}
+
+impl<F: PrimeField> ToBytesGadget<F> for SWCompressedVar<F> {
+    /// Returns the bytes of the x-coordinate, followed by the parity as a byte, as the
+    /// serialization of [`SWCompressed`].
+    fn to_bytes_le(&self) -> Result<Vec<UInt8<F>>, SynthesisError> {
+        let mut bytes = self.x.to_bytes_le()?;
+        bytes.extend(self.y_is_odd.to_bytes_le()?);
+        Ok(bytes)
+    }
+
+    fn to_non_unique_bytes_le(&self) -> Result<Vec<UInt8<F>>, SynthesisError> {
+        let mut bytes = self.x.to_non_unique_bytes_le()?;
+        bytes.extend(self.y_is_odd.to_bytes_le()?);
+        Ok(bytes)
+    }
+}
+
 pub struct PedersenCRHCompressorGadget<C, I, W, GG, IG>
 where
     C: CurveGroup,
@@ -158,3 +264,65 @@ where
         )
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::crh::{
+        injective_map::{
+            constraints::{PedersenTwoToOneCRHCompressorGadget, SWCompressorGadget},
+            PedersenTwoToOneCRHCompressor, SWCompressor,
+        },
+        pedersen, TwoToOneCRHScheme, TwoToOneCRHSchemeGadget,
+    };
+    use ark_bls12_377::{constraints::G1Var, Fq, G1Projective};
+    use ark_r1cs_std::prelude::*;
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::{test_rng, UniformRand};
+
+    #[derive(Clone, PartialEq, Eq, Hash)]
+    struct Window;
+
+    impl pedersen::Window for Window {
+        const WINDOW_SIZE: usize = 128;
+        const NUM_WINDOWS: usize = 8;
+    }
+
+    type TestCRH = PedersenTwoToOneCRHCompressor<G1Projective, SWCompressor, Window>;
+    type TestCRHGadget = PedersenTwoToOneCRHCompressorGadget<
+        G1Projective,
+        SWCompressor,
+        Window,
+        G1Var,
+        SWCompressorGadget,
+    >;
+
+    #[test]
+    fn test_sw_compressor_equality() {
+        let rng = &mut test_rng();
+        let cs = ConstraintSystem::<Fq>::new_ref();
+
+        let parameters = TestCRH::setup(rng).unwrap();
+        let left: Vec<u8> = (0..32).map(|_| u8::rand(rng)).collect();
+        let right: Vec<u8> = (0..32).map(|_| u8::rand(rng)).collect();
+        let primitive_result =
+            TestCRH::evaluate(&parameters, left.as_slice(), right.as_slice()).unwrap();
+
+        let parameters_var = pedersen::constraints::CRHParametersVar::new_constant(
+            ark_relations::ns!(cs, "CRH Parameters"),
+            &parameters,
+        )
+        .unwrap();
+        let left_var = UInt8::new_witness_vec(cs.clone(), &left).unwrap();
+        let right_var = UInt8::new_witness_vec(cs.clone(), &right).unwrap();
+        let result_var = TestCRHGadget::evaluate(&parameters_var, &left_var, &right_var).unwrap();
+        assert_eq!(primitive_result, result_var.value().unwrap());
+
+        // the compressed outputs are hashed as their serialization
+        let compressed =
+            TestCRH::compress(&parameters, primitive_result, primitive_result).unwrap();
+        let compressed_var =
+            TestCRHGadget::compress(&parameters_var, &result_var, &result_var).unwrap();
+        assert_eq!(compressed, compressed_var.value().unwrap());
+        assert!(cs.is_satisfied().unwrap());
+    }
+}
@@ -6,16 +6,25 @@ use ark_std::{fmt::Debug, hash::Hash, marker::PhantomData};
 
 use super::{pedersen, CRHScheme, TwoToOneCRHScheme};
 use ark_ec::{
+    short_weierstrass::{Affine as SWAffine, Projective as SWProjective, SWCurveConfig},
     twisted_edwards::{Affine as TEAffine, Projective as TEProjective, TECurveConfig},
     CurveConfig, CurveGroup,
 };
+use ark_ff::{BigInteger, PrimeField};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::borrow::Borrow;
 #[cfg(feature = "r1cs")]
 pub mod constraints;
 
 pub trait InjectiveMap<C: CurveGroup> {
-    type Output: Clone + Eq + Hash + Default + Debug + CanonicalSerialize + CanonicalDeserialize;
+    type Output: Clone
+        + Eq
+        + Hash
+        + Default
+        + Debug
+        + CanonicalSerialize
+        + CanonicalDeserialize
+        + Send;
 
     fn injective_map(ge: &C::Affine) -> Result<Self::Output, Error>;
 }
@@ -31,6 +40,36 @@ impl<P: TECurveConfig> InjectiveMap<TEProjective<P>> for TECompressor {
     }
 }
 
+pub struct SWCompressor;
+
+/// A point of a SW curve other than the point at infinity, compressed to its x-coordinate and
+/// the parity of its y-coordinate, which tells apart the two points of this x-coordinate.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Hash, CanonicalSerialize, CanonicalDeserialize,
+)]
+pub struct SWCompressed<F: PrimeField> {
+    pub x: F,
+    pub y_is_odd: bool,
+}
+
+impl<P: SWCurveConfig> InjectiveMap<SWProjective<P>> for SWCompressor
+where
+    P::BaseField: PrimeField,
+{
+    type Output = SWCompressed<P::BaseField>;
+
+    /// Returns [`Error::PointAtInfinity`] for the point at infinity, which has no coordinates.
+    fn injective_map(ge: &SWAffine<P>) -> Result<Self::Output, Error> {
+        if ge.infinity {
+            return Err(Error::PointAtInfinity);
+        }
+        Ok(SWCompressed {
+            x: ge.x,
+            y_is_odd: ge.y.into_bigint().is_odd(),
+        })
+    }
+}
+
 pub struct PedersenCRHCompressor<C: CurveGroup, I: InjectiveMap<C>, W: pedersen::Window> {
     _group: PhantomData<C>,
     _compressor: PhantomData<I>,
@@ -60,6 +99,16 @@ impl<C: CurveGroup, I: InjectiveMap<C>, W: pedersen::Window> CRHScheme
         end_timer!(eval_time);
         Ok(result)
     }
+
+    fn batch_evaluate<T: Borrow<Self::Input> + Sync>(
+        parameters: &Self::Parameters,
+        inputs: &[T],
+    ) -> Result<Vec<Self::Output>, Error> {
+        pedersen::CRH::<C, W>::batch_evaluate(parameters, inputs)?
+            .iter()
+            .map(I::injective_map)
+            .collect()
+    }
 }
 
 pub struct PedersenTwoToOneCRHCompressor<C: CurveGroup, I: InjectiveMap<C>, W: pedersen::Window> {
@@ -106,4 +155,59 @@ impl<C: CurveGroup, I: InjectiveMap<C>, W: pedersen::Window> TwoToOneCRHScheme
             crate::to_uncompressed_bytes!(right_input)?,
         )
     }
+    fn batch_evaluate<T: Borrow<Self::Input> + Sync>(
+        parameters: &Self::Parameters,
+        inputs: &[(T, T)],
+    ) -> Result<Vec<Self::Output>, Error> {
+        pedersen::TwoToOneCRH::<C, W>::batch_evaluate(parameters, inputs)?
+            .iter()
+            .map(I::injective_map)
+            .collect()
+    }
+
+    fn batch_compress<T: Borrow<Self::Output> + Sync>(
+        parameters: &Self::Parameters,
+        inputs: &[(T, T)],
+    ) -> Result<Vec<Self::Output>, Error> {
+        let inputs = inputs
+            .iter()
+            .map(|(left, right)| {
+                Ok((
+                    crate::to_uncompressed_bytes!(left)?,
+                    crate::to_uncompressed_bytes!(right)?,
+                ))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        Self::batch_evaluate(parameters, &inputs)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::crh::injective_map::{InjectiveMap, SWCompressed, SWCompressor};
+    use crate::Error;
+    use ark_bls12_377::{Fq, G1Affine, G1Projective};
+    use ark_ec::AffineRepr;
+    use ark_ff::{BigInteger, PrimeField};
+    use ark_std::{test_rng, UniformRand};
+
+    #[test]
+    fn sw_compressor() {
+        let mut rng = test_rng();
+        let point = G1Affine::rand(&mut rng);
+        let compressed: SWCompressed<Fq> =
+            <SWCompressor as InjectiveMap<G1Projective>>::injective_map(&point).unwrap();
+        assert_eq!(compressed.x, point.x);
+        assert_eq!(compressed.y_is_odd, point.y.into_bigint().is_odd());
+
+        // the points of the same x-coordinate are told apart by the parity
+        let negated = <SWCompressor as InjectiveMap<G1Projective>>::injective_map(&-point).unwrap();
+        assert_eq!(negated.x, compressed.x);
+        assert_ne!(negated.y_is_odd, compressed.y_is_odd);
+
+        assert!(matches!(
+            <SWCompressor as InjectiveMap<G1Projective>>::injective_map(&G1Affine::zero()),
+            Err(Error::PointAtInfinity)
+        ));
+    }
 }
@@ -0,0 +1,236 @@
+use crate::crh::mimc::{FeistelCRH, FeistelTwoToOneCRH, MiMCConfig, TwoToOneCRH, CRH};
+use crate::crh::{
+    CRHSchemeGadget as CRHGadgetTrait, TwoToOneCRHSchemeGadget as TwoToOneCRHGadgetTrait,
+};
+
+use ark_ff::PrimeField;
+use ark_r1cs_std::alloc::{AllocVar, AllocationMode};
+use ark_r1cs_std::fields::{fp::FpVar, FieldVar};
+use ark_relations::r1cs::{Namespace, SynthesisError};
+use ark_std::borrow::Borrow;
+use ark_std::marker::PhantomData;
+
+#[derive(Clone)]
+pub struct CRHParametersVar<F: PrimeField> {
+    pub parameters: MiMCConfig<F>,
+}
+
+impl<F: PrimeField> CRHParametersVar<F> {
+    /// Encrypts `x` under the key `k` with MiMC-p/p.
+    pub fn permute(&self, x: &FpVar<F>, k: &FpVar<F>) -> Result<FpVar<F>, SynthesisError> {
+        let mut x = x.clone();
+        for c in &self.parameters.round_constants {
+            x = (x + k + *c).pow_by_constant([self.parameters.exponent])?;
+        }
+        Ok(x + k)
+    }
+
+    /// Encrypts `(x_l, x_r)` under the key `k` with the MiMC-2p/p Feistel network.
+    /// The halves are not swapped after the last round.
+    pub fn feistel_permute(
+        &self,
+        x_l: &FpVar<F>,
+        x_r: &FpVar<F>,
+        k: &FpVar<F>,
+    ) -> Result<(FpVar<F>, FpVar<F>), SynthesisError> {
+        let num_rounds = self.parameters.num_rounds();
+        let (mut x_l, mut x_r) = (x_l.clone(), x_r.clone());
+        for (i, c) in self.parameters.round_constants.iter().enumerate() {
+            let t = (&x_l + k + *c).pow_by_constant([self.parameters.exponent])?;
+            if i < num_rounds - 1 {
+                (x_l, x_r) = (x_r + t, x_l);
+            } else {
+                x_r += t;
+            }
+        }
+        Ok((x_l, x_r))
+    }
+
+    fn hash(&self, input: &[FpVar<F>]) -> Result<FpVar<F>, SynthesisError> {
+        let mut r = FpVar::zero();
+        for x in input {
+            r = &r + x + self.permute(x, &r)?;
+        }
+        Ok(r)
+    }
+
+    fn feistel_hash(&self, input: &[FpVar<F>]) -> Result<FpVar<F>, SynthesisError> {
+        let (mut x_l, mut x_r) = (FpVar::zero(), FpVar::zero());
+        for x in input {
+            (x_l, x_r) = self.feistel_permute(&(x_l + x), &x_r, &FpVar::zero())?;
+        }
+        Ok(x_l)
+    }
+}
+
+pub struct CRHGadget<F: PrimeField> {
+    field_phantom: PhantomData<F>,
+}
+
+impl<F: PrimeField> CRHGadgetTrait<CRH<F>, F> for CRHGadget<F> {
+    type InputVar = [FpVar<F>];
+    type OutputVar = FpVar<F>;
+    type ParametersVar = CRHParametersVar<F>;
+
+    fn evaluate(
+        parameters: &Self::ParametersVar,
+        input: &Self::InputVar,
+    ) -> Result<Self::OutputVar, SynthesisError> {
+        parameters.hash(input)
+    }
+}
+
+pub struct TwoToOneCRHGadget<F: PrimeField> {
+    field_phantom: PhantomData<F>,
+}
+
+impl<F: PrimeField> TwoToOneCRHGadgetTrait<TwoToOneCRH<F>, F> for TwoToOneCRHGadget<F> {
+    type InputVar = FpVar<F>;
+    type OutputVar = FpVar<F>;
+    type ParametersVar = CRHParametersVar<F>;
+
+    fn evaluate(
+        parameters: &Self::ParametersVar,
+        left_input: &Self::InputVar,
+        right_input: &Self::InputVar,
+    ) -> Result<Self::OutputVar, SynthesisError> {
+        Self::compress(parameters, left_input, right_input)
+    }
+
+    fn compress(
+        parameters: &Self::ParametersVar,
+        left_input: &Self::OutputVar,
+        right_input: &Self::OutputVar,
+    ) -> Result<Self::OutputVar, SynthesisError> {
+        parameters.hash(&[left_input.clone(), right_input.clone()])
+    }
+}
+
+pub struct FeistelCRHGadget<F: PrimeField> {
+    field_phantom: PhantomData<F>,
+}
+
+impl<F: PrimeField> CRHGadgetTrait<FeistelCRH<F>, F> for FeistelCRHGadget<F> {
+    type InputVar = [FpVar<F>];
+    type OutputVar = FpVar<F>;
+    type ParametersVar = CRHParametersVar<F>;
+
+    fn evaluate(
+        parameters: &Self::ParametersVar,
+        input: &Self::InputVar,
+    ) -> Result<Self::OutputVar, SynthesisError> {
+        parameters.feistel_hash(input)
+    }
+}
+
+pub struct FeistelTwoToOneCRHGadget<F: PrimeField> {
+    field_phantom: PhantomData<F>,
+}
+
+impl<F: PrimeField> TwoToOneCRHGadgetTrait<FeistelTwoToOneCRH<F>, F>
+    for FeistelTwoToOneCRHGadget<F>
+{
+    type InputVar = FpVar<F>;
+    type OutputVar = FpVar<F>;
+    type ParametersVar = CRHParametersVar<F>;
+
+    fn evaluate(
+        parameters: &Self::ParametersVar,
+        left_input: &Self::InputVar,
+        right_input: &Self::InputVar,
+    ) -> Result<Self::OutputVar, SynthesisError> {
+        Self::compress(parameters, left_input, right_input)
+    }
+
+    fn compress(
+        parameters: &Self::ParametersVar,
+        left_input: &Self::OutputVar,
+        right_input: &Self::OutputVar,
+    ) -> Result<Self::OutputVar, SynthesisError> {
+        parameters.feistel_hash(&[left_input.clone(), right_input.clone()])
+    }
+}
+
+impl<F: PrimeField> AllocVar<MiMCConfig<F>, F> for CRHParametersVar<F> {
+    fn new_variable<T: Borrow<MiMCConfig<F>>>(
+        _cs: impl Into<Namespace<F>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        _mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        f().map(|param| Self {
+            parameters: param.borrow().clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::crh::mimc::constraints::{
+        CRHGadget, CRHParametersVar, FeistelCRHGadget, FeistelTwoToOneCRHGadget, TwoToOneCRHGadget,
+    };
+    use crate::crh::mimc::{FeistelCRH, FeistelTwoToOneCRH, MiMCConfig, TwoToOneCRH, CRH};
+    use crate::crh::{CRHScheme, CRHSchemeGadget};
+    use crate::crh::{TwoToOneCRHScheme, TwoToOneCRHSchemeGadget};
+    use ark_bn254::Fr;
+    use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar, R1CSVar};
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::UniformRand;
+
+    #[test]
+    fn test_consistency() {
+        let mut test_rng = ark_std::test_rng();
+        let input: Vec<_> = (0..3).map(|_| Fr::rand(&mut test_rng)).collect();
+        let (left, right) = (input[0], input[1]);
+
+        for params in [
+            MiMCConfig::<Fr>::circom_mimc7(),
+            MiMCConfig::<Fr>::circom_mimc_sponge(),
+        ] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let params_g = CRHParametersVar::<Fr>::new_constant(cs.clone(), &params).unwrap();
+            let input_g =
+                Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(input.as_slice())).unwrap();
+            let (left_g, right_g) = (&input_g[0], &input_g[1]);
+
+            let crh_g = CRHGadget::<Fr>::evaluate(&params_g, &input_g).unwrap();
+            assert_eq!(
+                CRH::<Fr>::evaluate(&params, input.as_slice()).unwrap(),
+                crh_g.value().unwrap()
+            );
+            let crh_g = TwoToOneCRHGadget::<Fr>::compress(&params_g, left_g, right_g).unwrap();
+            assert_eq!(
+                TwoToOneCRH::<Fr>::compress(&params, left, right).unwrap(),
+                crh_g.value().unwrap()
+            );
+            let crh_g = FeistelCRHGadget::<Fr>::evaluate(&params_g, &input_g).unwrap();
+            assert_eq!(
+                FeistelCRH::<Fr>::evaluate(&params, input.as_slice()).unwrap(),
+                crh_g.value().unwrap()
+            );
+            let crh_g =
+                FeistelTwoToOneCRHGadget::<Fr>::compress(&params_g, left_g, right_g).unwrap();
+            assert_eq!(
+                FeistelTwoToOneCRH::<Fr>::compress(&params, left, right).unwrap(),
+                crh_g.value().unwrap()
+            );
+            assert!(cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_feistel_constraints() {
+        let mut test_rng = ark_std::test_rng();
+        let params = MiMCConfig::<Fr>::circom_mimc_sponge();
+        let left = Fr::rand(&mut test_rng);
+        let right = Fr::rand(&mut test_rng);
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let params_g = CRHParametersVar::<Fr>::new_constant(cs.clone(), &params).unwrap();
+        let left_g = FpVar::new_witness(cs.clone(), || Ok(left)).unwrap();
+        let right_g = FpVar::new_witness(cs.clone(), || Ok(right)).unwrap();
+        FeistelTwoToOneCRHGadget::<Fr>::compress(&params_g, &left_g, &right_g).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+        // x^5 costs 3 constraints per round, and each input is absorbed with a permutation
+        assert_eq!(cs.num_constraints(), 2 * 220 * 3);
+    }
+}
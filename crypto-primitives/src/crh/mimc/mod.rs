@@ -0,0 +1,347 @@
+use crate::crh::{CRHScheme, TwoToOneCRHScheme};
+use crate::sponge::poseidon::is_valid_alpha;
+use crate::Error;
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::borrow::Borrow;
+use ark_std::marker::PhantomData;
+use ark_std::rand::Rng;
+#[cfg(not(feature = "std"))]
+use ark_std::{format, vec::Vec};
+use sha3::{Digest, Keccak256};
+
+#[cfg(feature = "r1cs")]
+pub mod constraints;
+
+/// The parameters of [MiMC](https://eprint.iacr.org/2016/492): the exponent `e` of the round
+/// function and one constant per round.
+///
+/// The `i`-th round of MiMC-p/p with key `k` maps `x` to `(x + k + c_i)^e`, and the `i`-th
+/// round of the MiMC-2p/p Feistel network maps `(x_L, x_R)` to `(x_R + (x_L + k + c_i)^e, x_L)`.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct MiMCConfig<F: PrimeField> {
+    /// Exponent of the round function.
+    pub exponent: u64,
+    /// The round constants `c_i`, whose number is the number of rounds.
+    pub round_constants: Vec<F>,
+}
+
+impl<F: PrimeField> MiMCConfig<F> {
+    /// Returns the parameters with the given exponent and round constants.
+    ///
+    /// Returns an error if `x^exponent` is not a permutation of `F`, i.e. if
+    /// `gcd(exponent, p - 1) != 1`, or if there are no rounds.
+    pub fn new(exponent: u64, round_constants: Vec<F>) -> Result<Self, Error> {
+        if exponent < 3 || !is_valid_alpha::<F>(exponent) {
+            return Err(Error::InvalidParameters(format!(
+                "x^{exponent} is not a permutation of the field: gcd(exponent, p - 1) != 1"
+            )));
+        }
+        if round_constants.is_empty() {
+            return Err(Error::InvalidParameters(
+                "MiMC needs at least one round".into(),
+            ));
+        }
+        Ok(Self {
+            exponent,
+            round_constants,
+        })
+    }
+
+    /// Derives the round constants of `num_rounds` rounds from `seed`, as in circomlib.
+    ///
+    /// The first constant is zero. The others are the big-endian integers of the Keccak-256
+    /// chain `h_1 = Keccak256(Keccak256(seed))`, `h_{i+1} = Keccak256(h_i)`, reduced modulo `p`.
+    pub fn from_seed(seed: &[u8], exponent: u64, num_rounds: usize) -> Result<Self, Error> {
+        let mut round_constants = Vec::with_capacity(num_rounds);
+        let mut hash = Keccak256::digest(seed);
+        for i in 0..num_rounds {
+            if i == 0 {
+                round_constants.push(F::zero());
+            } else {
+                hash = Keccak256::digest(hash);
+                round_constants.push(F::from_be_bytes_mod_order(&hash));
+            }
+        }
+        Self::new(exponent, round_constants)
+    }
+
+    /// The parameters of circomlib's `MiMC7`, with exponent 7 and 91 rounds.
+    ///
+    /// [`CRH`] with these parameters computes `MultiMiMC7` with a zero key over BN254 Fr.
+    pub fn circom_mimc7() -> Self {
+        Self::from_seed(b"mimc", 7, 91).expect("x^7 is not a permutation of the field")
+    }
+
+    /// The parameters of circomlib's `MiMCSponge`, with exponent 5 and 220 rounds.
+    /// The first and the last round constants are zero.
+    ///
+    /// [`FeistelCRH`] with these parameters computes `MiMCSponge` with a zero key and a single
+    /// output over BN254 Fr, which is the hash of the Merkle tree of Tornado Cash.
+    pub fn circom_mimc_sponge() -> Self {
+        let mut config =
+            Self::from_seed(b"mimcsponge", 5, 220).expect("x^5 is not a permutation of the field");
+        *config.round_constants.last_mut().unwrap() = F::zero();
+        config
+    }
+
+    /// The number of rounds.
+    pub fn num_rounds(&self) -> usize {
+        self.round_constants.len()
+    }
+
+    /// Encrypts `x` under the key `k` with MiMC-p/p.
+    pub fn permute(&self, x: F, k: F) -> F {
+        self.round_constants
+            .iter()
+            .fold(x, |x, c| (x + k + c).pow([self.exponent]))
+            + k
+    }
+
+    /// Encrypts `(x_l, x_r)` under the key `k` with the MiMC-2p/p Feistel network.
+    /// The halves are not swapped after the last round.
+    pub fn feistel_permute(&self, mut x_l: F, mut x_r: F, k: F) -> (F, F) {
+        let num_rounds = self.num_rounds();
+        for (i, c) in self.round_constants.iter().enumerate() {
+            let t = (x_l + k + c).pow([self.exponent]);
+            if i < num_rounds - 1 {
+                (x_l, x_r) = (x_r + t, x_l);
+            } else {
+                x_r += t;
+            }
+        }
+        (x_l, x_r)
+    }
+
+    /// Hashes `input` with MiMC-p/p in the Miyaguchi–Preneel mode: starting from `r = 0`, each
+    /// element `x` updates `r` to `r + x + E_r(x)`.
+    fn hash(&self, input: &[F]) -> F {
+        input
+            .iter()
+            .fold(F::zero(), |r, x| r + x + self.permute(*x, r))
+    }
+
+    /// Hashes `input` with a sponge of rate 1 and capacity 1 over the MiMC-2p/p Feistel
+    /// permutation, and squeezes the first element.
+    fn feistel_hash(&self, input: &[F]) -> F {
+        let (mut x_l, mut x_r) = (F::zero(), F::zero());
+        for x in input {
+            (x_l, x_r) = self.feistel_permute(x_l + x, x_r, F::zero());
+        }
+        x_l
+    }
+
+    /// Generates the parameters with the smallest valid exponent, and the number of rounds
+    /// recommended in the MiMC paper: `ceil(log_e(p))` rounds for MiMC-p/p, and twice as many
+    /// for MiMC-2p/p.
+    fn generate(seed: &[u8], feistel: bool) -> Result<Self, Error> {
+        let exponent = (3..).step_by(2).find(|e| is_valid_alpha::<F>(*e)).unwrap();
+
+        // smallest number of rounds such that `e^rounds >= 2^n >= p`
+        let mut num_rounds = 0;
+        let mut power = 1f64;
+        let bound = (0..F::MODULUS_BIT_SIZE).fold(1f64, |bound, _| bound * 2.0);
+        while power < bound {
+            power *= exponent as f64;
+            num_rounds += 1;
+        }
+        if feistel {
+            num_rounds *= 2;
+        }
+        Self::from_seed(seed, exponent, num_rounds)
+    }
+}
+
+/// The MiMC-p/p CRH in the Miyaguchi–Preneel mode, which is circomlib's `MultiMiMC7` with a zero
+/// key when used with [`MiMCConfig::circom_mimc7`].
+pub struct CRH<F: PrimeField> {
+    field_phantom: PhantomData<F>,
+}
+
+impl<F: PrimeField> CRHScheme for CRH<F> {
+    type Input = [F];
+    type Output = F;
+    type Parameters = MiMCConfig<F>;
+
+    fn setup<R: Rng>(_rng: &mut R) -> Result<Self::Parameters, Error> {
+        MiMCConfig::generate(b"mimc", false)
+    }
+
+    fn evaluate<T: Borrow<Self::Input>>(
+        parameters: &Self::Parameters,
+        input: T,
+    ) -> Result<Self::Output, Error> {
+        Ok(parameters.hash(input.borrow()))
+    }
+}
+
+/// The MiMC-p/p two-to-one CRH, which hashes `[left, right]` with [`CRH`].
+pub struct TwoToOneCRH<F: PrimeField> {
+    field_phantom: PhantomData<F>,
+}
+
+impl<F: PrimeField> TwoToOneCRHScheme for TwoToOneCRH<F> {
+    type Input = F;
+    type Output = F;
+    type Parameters = MiMCConfig<F>;
+
+    fn setup<R: Rng>(_rng: &mut R) -> Result<Self::Parameters, Error> {
+        MiMCConfig::generate(b"mimc", false)
+    }
+
+    fn evaluate<T: Borrow<Self::Input>>(
+        parameters: &Self::Parameters,
+        left_input: T,
+        right_input: T,
+    ) -> Result<Self::Output, Error> {
+        Self::compress(parameters, left_input, right_input)
+    }
+
+    fn compress<T: Borrow<Self::Output>>(
+        parameters: &Self::Parameters,
+        left_input: T,
+        right_input: T,
+    ) -> Result<Self::Output, Error> {
+        Ok(parameters.hash(&[*left_input.borrow(), *right_input.borrow()]))
+    }
+}
+
+/// The CRH of the sponge over the MiMC-2p/p Feistel permutation, which is circomlib's
+/// `MiMCSponge` with a zero key and a single output when used with
+/// [`MiMCConfig::circom_mimc_sponge`].
+pub struct FeistelCRH<F: PrimeField> {
+    field_phantom: PhantomData<F>,
+}
+
+impl<F: PrimeField> CRHScheme for FeistelCRH<F> {
+    type Input = [F];
+    type Output = F;
+    type Parameters = MiMCConfig<F>;
+
+    fn setup<R: Rng>(_rng: &mut R) -> Result<Self::Parameters, Error> {
+        MiMCConfig::generate(b"mimcsponge", true)
+    }
+
+    fn evaluate<T: Borrow<Self::Input>>(
+        parameters: &Self::Parameters,
+        input: T,
+    ) -> Result<Self::Output, Error> {
+        Ok(parameters.feistel_hash(input.borrow()))
+    }
+}
+
+/// The MiMC-2p/p Feistel two-to-one CRH, which hashes `[left, right]` with [`FeistelCRH`].
+pub struct FeistelTwoToOneCRH<F: PrimeField> {
+    field_phantom: PhantomData<F>,
+}
+
+impl<F: PrimeField> TwoToOneCRHScheme for FeistelTwoToOneCRH<F> {
+    type Input = F;
+    type Output = F;
+    type Parameters = MiMCConfig<F>;
+
+    fn setup<R: Rng>(_rng: &mut R) -> Result<Self::Parameters, Error> {
+        MiMCConfig::generate(b"mimcsponge", true)
+    }
+
+    fn evaluate<T: Borrow<Self::Input>>(
+        parameters: &Self::Parameters,
+        left_input: T,
+        right_input: T,
+    ) -> Result<Self::Output, Error> {
+        Self::compress(parameters, left_input, right_input)
+    }
+
+    fn compress<T: Borrow<Self::Output>>(
+        parameters: &Self::Parameters,
+        left_input: T,
+        right_input: T,
+    ) -> Result<Self::Output, Error> {
+        Ok(parameters.feistel_hash(&[*left_input.borrow(), *right_input.borrow()]))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::crh::mimc::{FeistelCRH, FeistelTwoToOneCRH, MiMCConfig, TwoToOneCRH, CRH};
+    use crate::crh::{CRHScheme, TwoToOneCRHScheme};
+    use ark_bn254::Fr;
+    use ark_ff::MontFp;
+    use ark_std::{test_rng, UniformRand};
+
+    #[test]
+    fn circomlib_constants() {
+        // the first non-zero constants of circomlib's `mimcsponge.circom` and `mimc7.circom`
+        let sponge = MiMCConfig::<Fr>::circom_mimc_sponge();
+        assert_eq!(sponge.num_rounds(), 220);
+        assert_eq!(sponge.round_constants[0], Fr::from(0u64));
+        assert_eq!(
+            sponge.round_constants[1],
+            MontFp!("7120861356467848435263064379192047478074060781135320967663101236819528304084")
+        );
+        assert_eq!(sponge.round_constants[219], Fr::from(0u64));
+
+        let mimc7 = MiMCConfig::<Fr>::circom_mimc7();
+        assert_eq!(mimc7.num_rounds(), 91);
+        assert_eq!(
+            mimc7.round_constants[1],
+            MontFp!(
+                "20888961410941983456478427210666206549300505294776164667214940546594746570981"
+            )
+        );
+    }
+
+    #[test]
+    fn tornado_cash_zeros() {
+        // `zeros(0)` and `zeros(1)` of Tornado Cash's `MerkleTreeWithHistory`
+        let zero: Fr = MontFp!(
+            "21663839004416932945382355908790599225266501822907911457504978515578255421292"
+        );
+        let expected: Fr = MontFp!(
+            "16923532097304556005972200564242292693309333953544141029519619077135960040221"
+        );
+
+        let parameters = MiMCConfig::circom_mimc_sponge();
+        assert_eq!(
+            FeistelTwoToOneCRH::<Fr>::compress(&parameters, zero, zero).unwrap(),
+            expected
+        );
+        assert_eq!(
+            FeistelCRH::<Fr>::evaluate(&parameters, [zero, zero].as_slice()).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn setup_generates_valid_parameters() {
+        let mut rng = test_rng();
+        // 3 divides p - 1 for the scalar field of BN254
+        let parameters = CRH::<Fr>::setup(&mut rng).unwrap();
+        assert_eq!(parameters.exponent, 5);
+        assert_eq!(parameters.num_rounds(), 110);
+        let parameters = FeistelCRH::<Fr>::setup(&mut rng).unwrap();
+        assert_eq!(parameters.exponent, 5);
+        assert_eq!(parameters.num_rounds(), 220);
+
+        assert!(MiMCConfig::<Fr>::new(3, vec![Fr::from(0u64)]).is_err());
+        assert!(MiMCConfig::<Fr>::new(7, vec![]).is_err());
+    }
+
+    #[test]
+    fn two_to_one_matches_crh() {
+        let mut rng = test_rng();
+        let parameters = MiMCConfig::<Fr>::circom_mimc7();
+        let left = Fr::rand(&mut rng);
+        let right = Fr::rand(&mut rng);
+
+        let hash = TwoToOneCRH::<Fr>::evaluate(&parameters, left, right).unwrap();
+        assert_eq!(
+            hash,
+            CRH::<Fr>::evaluate(&parameters, [left, right].as_slice()).unwrap()
+        );
+        assert_ne!(
+            hash,
+            TwoToOneCRH::<Fr>::evaluate(&parameters, right, left).unwrap()
+        );
+    }
+}
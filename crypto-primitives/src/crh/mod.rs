@@ -2,11 +2,18 @@
 
 use ark_std::hash::Hash;
 use ark_std::rand::Rng;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+pub mod blake2s;
+pub mod blake3;
 pub mod bowe_hopwood;
 pub mod injective_map;
+pub mod mimc;
 pub mod pedersen;
 pub mod poseidon;
 pub mod sha256;
+pub mod sha512;
+pub mod sinsemilla;
 
 use crate::Error;
 
@@ -15,6 +22,8 @@ pub mod constraints;
 
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::borrow::Borrow;
+#[cfg(not(feature = "std"))]
+use ark_std::vec::Vec;
 #[cfg(feature = "r1cs")]
 pub use constraints::*;
 
@@ -28,7 +37,8 @@ pub trait CRHScheme {
         + Hash
         + Default
         + CanonicalSerialize
-        + CanonicalDeserialize;
+        + CanonicalDeserialize
+        + Send;
     type Parameters: Clone + CanonicalSerialize + CanonicalDeserialize + Sync;
 
     fn setup<R: Rng>(r: &mut R) -> Result<Self::Parameters, Error>;
@@ -36,6 +46,18 @@ pub trait CRHScheme {
         parameters: &Self::Parameters,
         input: T,
     ) -> Result<Self::Output, Error>;
+
+    /// Evaluates the CRH on each of the `inputs`, in parallel with the `parallel` feature.
+    ///
+    /// Implementations may override it to share work between evaluations.
+    fn batch_evaluate<T: Borrow<Self::Input> + Sync>(
+        parameters: &Self::Parameters,
+        inputs: &[T],
+    ) -> Result<Vec<Self::Output>, Error> {
+        cfg_iter!(inputs)
+            .map(|input| Self::evaluate(parameters, input.borrow()))
+            .collect()
+    }
 }
 
 /// CRH used by merkle tree inner hash. Merkle tree will convert leaf output to bytes first.
@@ -49,7 +71,8 @@ pub trait TwoToOneCRHScheme {
         + Hash
         + Default
         + CanonicalSerialize
-        + CanonicalDeserialize;
+        + CanonicalDeserialize
+        + Send;
     type Parameters: Clone + CanonicalSerialize + CanonicalDeserialize + Sync;
 
     fn setup<R: Rng>(r: &mut R) -> Result<Self::Parameters, Error>;
@@ -65,4 +88,30 @@ pub trait TwoToOneCRHScheme {
         left_input: T,
         right_input: T,
     ) -> Result<Self::Output, Error>;
+
+    /// Evaluates the CRH on each pair of `(left_input, right_input)`, in parallel with the
+    /// `parallel` feature.
+    ///
+    /// Implementations may override it to share work between evaluations.
+    fn batch_evaluate<T: Borrow<Self::Input> + Sync>(
+        parameters: &Self::Parameters,
+        inputs: &[(T, T)],
+    ) -> Result<Vec<Self::Output>, Error> {
+        cfg_iter!(inputs)
+            .map(|(left, right)| Self::evaluate(parameters, left.borrow(), right.borrow()))
+            .collect()
+    }
+
+    /// Compresses each pair of `(left_input, right_input)`, in parallel with the `parallel`
+    /// feature.
+    ///
+    /// Implementations may override it to share work between evaluations.
+    fn batch_compress<T: Borrow<Self::Output> + Sync>(
+        parameters: &Self::Parameters,
+        inputs: &[(T, T)],
+    ) -> Result<Vec<Self::Output>, Error> {
+        cfg_iter!(inputs)
+            .map(|(left, right)| Self::compress(parameters, left.borrow(), right.borrow()))
+            .collect()
+    }
 }
@@ -9,8 +9,11 @@ use ark_relations::r1cs::{Namespace, SynthesisError};
 #[cfg(not(feature = "std"))]
 use ark_std::vec::Vec;
 
-use crate::crh::pedersen::{TwoToOneCRH, CRH};
-use crate::crh::{CRHSchemeGadget, TwoToOneCRHSchemeGadget};
+use crate::crh::pedersen::{
+    GroupHash, TwoToOneCRH, VariableLengthCRH, VariableLengthParameters, CRH, LENGTH_PREFIX_SIZE,
+};
+use crate::crh::{CRHScheme, CRHSchemeGadget, TwoToOneCRHSchemeGadget};
+use ark_r1cs_std::fields::fp::FpVar;
 use core::{borrow::Borrow, iter, marker::PhantomData};
 
 #[derive(Derivative)]
@@ -152,6 +155,151 @@ where
     }
 }
 
+#[derive(Derivative)]
+#[derivative(Clone(bound = "C: GroupHash, GG: CurveVar<C, ConstraintF<C>>"))]
+pub struct VariableLengthCRHParametersVar<C: GroupHash, GG: CurveVar<C, ConstraintF<C>>>
+where
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+    params: VariableLengthParameters<C>,
+    #[doc(hidden)]
+    _group: PhantomData<(C, GG)>,
+}
+
+impl<C, GG> VariableLengthCRHParametersVar<C, GG>
+where
+    C: GroupHash,
+    GG: CurveVar<C, ConstraintF<C>>,
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+    /// Computes the sum of the windows of the little-endian `bits` of a length-prefixed input,
+    /// with constant generators.
+    fn sum_of_windows(&self, bits: &[Boolean<ConstraintF<C>>]) -> Result<GG, SynthesisError> {
+        let window_size = self.params.window_size();
+        let num_windows = bits.len().div_ceil(window_size);
+        let generators = self
+            .params
+            .generators(num_windows)
+            .map_err(|_| SynthesisError::Unsatisfiable)?;
+        GG::precomputed_base_multiscalar_mul_le(&generators, bits.chunks(window_size))
+    }
+}
+
+pub struct VariableLengthCRHGadget<C: GroupHash, GG: CurveVar<C, ConstraintF<C>>>
+where
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+    #[doc(hidden)]
+    _group: PhantomData<*const C>,
+    #[doc(hidden)]
+    _group_var: PhantomData<*const GG>,
+}
+
+impl<C, GG> VariableLengthCRHGadget<C, GG>
+where
+    C: GroupHash,
+    GG: CurveVar<C, ConstraintF<C>>,
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+    /// Hashes the first `length` bytes of `input`, where `length` is at most `input.len()`.
+    ///
+    /// The cost only depends on `input.len()`, which is the maximum length.
+    /// The constraints are unsatisfied if `length` is larger than `input.len()`.
+    #[tracing::instrument(target = "r1cs", skip(parameters, input, length))]
+    pub fn evaluate_with_length(
+        parameters: &VariableLengthCRHParametersVar<C, GG>,
+        input: &[UInt8<ConstraintF<C>>],
+        length: &FpVar<ConstraintF<C>>,
+    ) -> Result<GG, SynthesisError> {
+        let cs = input.cs().or(length.cs());
+
+        if cs.is_none() {
+            let length = length.value()?;
+            let length = (0..=input.len())
+                .find(|i| ConstraintF::<C>::from(*i as u64) == length)
+                .ok_or(SynthesisError::Unsatisfiable)?;
+            let result =
+                VariableLengthCRH::<C>::evaluate(&parameters.params, input[..length].value()?)
+                    .map_err(|_| SynthesisError::Unsatisfiable)?;
+            return Ok(GG::constant(result.into()));
+        }
+
+        // `is_end[i]` is whether `i == length`, and exactly one of them is set
+        let is_end = (0..=input.len())
+            .map(|i| length.is_eq(&FpVar::Constant(ConstraintF::<C>::from(i as u64))))
+            .collect::<Result<Vec<_>, _>>()?;
+        is_end
+            .iter()
+            .fold(FpVar::zero(), |sum, is_end| {
+                sum + FpVar::from(is_end.clone())
+            })
+            .enforce_equal(&FpVar::one())?;
+
+        // as `length` is at most `input.len()`, its 64 lowest bits are its encoding
+        let mut bits = length.to_bits_le()?;
+        bits.truncate(LENGTH_PREFIX_SIZE * 8);
+
+        // the bytes from index `length` are replaced by zeros, as the windows of zeros are ignored
+        let mut masked = Vec::with_capacity(input.len() * 8);
+        // whether `i < length`, which is whether `is_end[j]` is set for some `j > i`
+        let mut is_in_input = Boolean::FALSE;
+        for i in (0..input.len()).rev() {
+            is_in_input = &is_in_input | &is_end[i + 1];
+            for bit in input[i].to_bits_le()?.into_iter().rev() {
+                masked.push(&bit & &is_in_input);
+            }
+        }
+        masked.reverse();
+        bits.extend(masked);
+
+        parameters.sum_of_windows(&bits)
+    }
+}
+
+impl<C, GG> CRHSchemeGadget<VariableLengthCRH<C>, ConstraintF<C>> for VariableLengthCRHGadget<C, GG>
+where
+    C: GroupHash,
+    GG: CurveVar<C, ConstraintF<C>>,
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+    type InputVar = [UInt8<ConstraintF<C>>];
+    type OutputVar = GG;
+    type ParametersVar = VariableLengthCRHParametersVar<C, GG>;
+
+    #[tracing::instrument(target = "r1cs", skip(parameters, input))]
+    fn evaluate(
+        parameters: &Self::ParametersVar,
+        input: &Self::InputVar,
+    ) -> Result<Self::OutputVar, SynthesisError> {
+        let mut bits: Vec<_> = UInt64::constant(input.len() as u64).to_bits_le()?;
+        for byte in input {
+            bits.extend(byte.to_bits_le()?);
+        }
+        parameters.sum_of_windows(&bits)
+    }
+}
+
+impl<C, GG> AllocVar<VariableLengthParameters<C>, ConstraintF<C>>
+    for VariableLengthCRHParametersVar<C, GG>
+where
+    C: GroupHash,
+    GG: CurveVar<C, ConstraintF<C>>,
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+    #[tracing::instrument(target = "r1cs", skip(_cs, f))]
+    fn new_variable<T: Borrow<VariableLengthParameters<C>>>(
+        _cs: impl Into<Namespace<ConstraintF<C>>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        _mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        let params = f()?.borrow().clone();
+        Ok(VariableLengthCRHParametersVar {
+            params,
+            _group: PhantomData,
+        })
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::crh::{
@@ -159,7 +307,7 @@ mod test {
     };
     use ark_ec::CurveGroup;
     use ark_ed_on_bls12_381::{constraints::EdwardsVar, EdwardsProjective as JubJub, Fq as Fr};
-    use ark_r1cs_std::prelude::*;
+    use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
     use ark_relations::r1cs::{ConstraintSystem, ConstraintSystemRef};
     use ark_std::rand::Rng;
     use ark_std::{test_rng, UniformRand};
@@ -251,4 +399,55 @@ mod test {
         assert_eq!(primitive_result, result_var.value().unwrap().into_affine());
         assert!(cs.is_satisfied().unwrap());
     }
+
+    #[test]
+    fn test_variable_length_equality() {
+        type TestVariableLengthCRH = pedersen::VariableLengthCRH<JubJub>;
+        type TestVariableLengthCRHGadget =
+            pedersen::constraints::VariableLengthCRHGadget<JubJub, EdwardsVar>;
+
+        let rng = &mut test_rng();
+        let cs = ConstraintSystem::<Fr>::new_ref();
+
+        let (input, input_var) = generate_u8_input(cs.clone(), 10, rng);
+        // the generators of the inputs longer than 5 bytes are derived on demand
+        let parameters =
+            pedersen::VariableLengthParameters::with_max_input_len(b"test", 64, 5).unwrap();
+        let parameters_var = pedersen::constraints::VariableLengthCRHParametersVar::new_constant(
+            ark_relations::ns!(cs, "CRH Parameters"),
+            &parameters,
+        )
+        .unwrap();
+
+        for length in [0, 1, 7, 8, 10] {
+            let primitive_result =
+                TestVariableLengthCRH::evaluate(&parameters, &input[..length]).unwrap();
+
+            let result_var =
+                TestVariableLengthCRHGadget::evaluate(&parameters_var, &input_var[..length])
+                    .unwrap();
+            assert_eq!(primitive_result, result_var.value().unwrap());
+
+            let length_var =
+                FpVar::new_witness(cs.clone(), || Ok(Fr::from(length as u64))).unwrap();
+            let result_var = TestVariableLengthCRHGadget::evaluate_with_length(
+                &parameters_var,
+                &input_var,
+                &length_var,
+            )
+            .unwrap();
+            assert_eq!(primitive_result, result_var.value().unwrap());
+        }
+        assert!(cs.is_satisfied().unwrap());
+
+        // the length cannot exceed the maximum length
+        let length_var = FpVar::new_witness(cs.clone(), || Ok(Fr::from(11u64))).unwrap();
+        let _ = TestVariableLengthCRHGadget::evaluate_with_length(
+            &parameters_var,
+            &input_var,
+            &length_var,
+        )
+        .unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
 }
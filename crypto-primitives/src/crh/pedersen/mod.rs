@@ -7,14 +7,21 @@ use ark_std::{
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
 
-use crate::crh::{CRHScheme, TwoToOneCRHScheme};
-use ark_ec::CurveGroup;
-use ark_ff::{Field, ToConstraintField};
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
-use ark_std::borrow::Borrow;
+use crate::crh::{blake2s, CRHScheme, TwoToOneCRHScheme};
+use ark_ec::{
+    short_weierstrass::{self as sw, SWCurveConfig},
+    twisted_edwards::{self as te, TECurveConfig},
+    AffineRepr, CurveGroup,
+};
+use ark_ff::{BigInteger, Field, PrimeField, ToConstraintField};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
+};
+use ark_std::borrow::{Borrow, Cow};
 use ark_std::cfg_chunks;
+use ark_std::ops::Range;
 #[cfg(not(feature = "std"))]
-use ark_std::vec::Vec;
+use ark_std::{format, vec::Vec};
 
 #[cfg(feature = "r1cs")]
 pub mod constraints;
@@ -24,11 +31,185 @@ pub trait Window: Clone {
     const NUM_WINDOWS: usize;
 }
 
+/// The largest number of bits looked up in a table of [`WindowTables`].
+pub const MAX_TABLE_BITS: usize = 16;
+
 #[derive(Clone, Default, CanonicalSerialize, CanonicalDeserialize)]
 pub struct Parameters<C: CurveGroup> {
     pub generators: Vec<Vec<C>>,
 }
 
+/// The parameters of the CRH, extended with the precomputed tables of their windows, which
+/// speed up the native evaluation with [`CRH::evaluate_with_tables`].
+///
+/// When deserialized with validation, the tables are checked against the generators.
+#[derive(Derivative, CanonicalSerialize)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub struct PrecomputedParameters<C: CurveGroup> {
+    parameters: Parameters<C>,
+    tables: WindowTables<C>,
+}
+
+impl<C: CurveGroup> PrecomputedParameters<C> {
+    /// Precomputes the tables of `table_bits`-bit parts of the windows, so that evaluating the
+    /// CRH costs one lookup and one addition per part, instead of one addition per bit.
+    pub fn new(parameters: Parameters<C>, table_bits: usize) -> Result<Self, Error> {
+        let tables = WindowTables::new(&parameters.generators, table_bits)?;
+        Ok(Self { parameters, tables })
+    }
+
+    pub fn parameters(&self) -> &Parameters<C> {
+        &self.parameters
+    }
+
+    pub fn tables(&self) -> &WindowTables<C> {
+        &self.tables
+    }
+}
+
+impl<C: CurveGroup> Valid for PrecomputedParameters<C> {
+    /// Checks that the tables are those of the generators: each entry of a single bit must be
+    /// the generator of that bit. The other entries are not recomputed.
+    fn check(&self) -> Result<(), SerializationError> {
+        self.parameters.check()?;
+        self.tables.check()?;
+        let generators = &self.parameters.generators;
+        let table_bits = self.tables.table_bits;
+        let consistent = self.tables.matches_shape(generators, 1)
+            && self
+                .tables
+                .tables
+                .iter()
+                .zip(generators)
+                .all(|(tables, generators)| {
+                    tables
+                        .iter()
+                        .zip(generators.chunks(table_bits))
+                        .all(|(table, generators)| {
+                            table[0].is_zero()
+                                && generators
+                                    .iter()
+                                    .enumerate()
+                                    .all(|(k, generator)| C::from(table[1 << k]) == *generator)
+                        })
+                });
+        if !consistent {
+            return Err(SerializationError::InvalidData);
+        }
+        Ok(())
+    }
+}
+
+impl<C: CurveGroup> CanonicalDeserialize for PrecomputedParameters<C> {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let parameters = Parameters::deserialize_with_mode(&mut reader, compress, Validate::No)?;
+        let tables = WindowTables::deserialize_with_mode(&mut reader, compress, Validate::No)?;
+        let result = Self { parameters, tables };
+        if let Validate::Yes = validate {
+            result.check()?;
+        }
+        Ok(result)
+    }
+}
+
+/// Precomputed fixed-base tables: each window is split into parts of `table_bits` bits, and the
+/// table of a part holds the sums of the generators selected by all its `2^table_bits` values,
+/// which are the multiples of the lowest generator of the part.
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(Clone(bound = ""), Debug(bound = ""), PartialEq(bound = ""))]
+pub struct WindowTables<C: CurveGroup> {
+    pub table_bits: usize,
+    /// The table of the `j`-th part of the `i`-th window is `tables[i][j]`, indexed by the
+    /// little-endian value of the bits of the part.
+    pub tables: Vec<Vec<Vec<C::Affine>>>,
+}
+
+impl<C: CurveGroup> WindowTables<C> {
+    /// Computes the tables of the `generators` of each window, in parallel across windows.
+    pub fn new(generators: &[Vec<C>], table_bits: usize) -> Result<Self, Error> {
+        check_table_bits(table_bits)?;
+        let tables = cfg_iter!(generators)
+            .map(|powers| {
+                powers
+                    .chunks(table_bits)
+                    .map(|powers| {
+                        // the sum of the generators of the bits of `m` is that of `m` without its
+                        // highest bit, plus the generator of its highest bit
+                        let mut table = Vec::with_capacity(1 << powers.len());
+                        table.push(C::zero());
+                        for (k, power) in powers.iter().enumerate() {
+                            for m in 0..1 << k {
+                                table.push(table[m] + power);
+                            }
+                        }
+                        C::normalize_batch(&table)
+                    })
+                    .collect()
+            })
+            .collect();
+        Ok(Self { table_bits, tables })
+    }
+
+    /// Returns whether there is a table of `2^table_bits` entries for each part of the windows
+    /// of `generators`, where each generator selects `bits_per_generator` bits; the last part
+    /// of a window may be shorter.
+    pub(crate) fn matches_shape(&self, generators: &[Vec<C>], bits_per_generator: usize) -> bool {
+        if check_table_bits(self.table_bits).is_err() || self.table_bits % bits_per_generator != 0 {
+            return false;
+        }
+        let generators_per_table = self.table_bits / bits_per_generator;
+        self.tables.len() == generators.len()
+            && self
+                .tables
+                .iter()
+                .zip(generators)
+                .all(|(tables, generators)| {
+                    tables.len() == generators.len().div_ceil(generators_per_table)
+                        && tables
+                            .iter()
+                            .zip(generators.chunks(generators_per_table))
+                            .all(|(table, generators)| {
+                                table.len() == 1 << (bits_per_generator * generators.len())
+                            })
+                })
+    }
+
+    /// Computes the sum of the windows of `bits`, each of `window_size` bits, with one lookup
+    /// per part.
+    fn sum_of_windows(&self, bits: &[bool], window_size: usize) -> C {
+        cfg_chunks!(bits, window_size)
+            .zip(&self.tables)
+            .map(|(bits, tables)| {
+                bits.chunks(self.table_bits)
+                    .zip(tables)
+                    .fold(C::zero(), |sum, (bits, table)| sum + table[to_index(bits)])
+            })
+            .sum::<C>()
+    }
+}
+
+/// Checks that tables of `table_bits` bits are not empty, and not larger than `MAX_TABLE_BITS`.
+pub(crate) fn check_table_bits(table_bits: usize) -> Result<(), Error> {
+    if table_bits == 0 || table_bits > MAX_TABLE_BITS {
+        return Err(Error::InvalidParameters(format!(
+            "tables of {} bits are not between 1 and {} bits",
+            table_bits, MAX_TABLE_BITS
+        )));
+    }
+    Ok(())
+}
+
+/// Returns the value of little-endian `bits`.
+pub(crate) fn to_index(bits: &[bool]) -> usize {
+    bits.iter()
+        .rev()
+        .fold(0, |index, bit| (index << 1) | *bit as usize)
+}
+
 pub struct CRH<C: CurveGroup, W: Window> {
     group: PhantomData<C>,
     window: PhantomData<W>,
@@ -45,39 +226,17 @@ impl<C: CurveGroup, W: Window> CRH<C, W> {
     }
 
     pub fn generator_powers<R: Rng>(num_powers: usize, rng: &mut R) -> Vec<C> {
-        let mut cur_gen_powers = Vec::with_capacity(num_powers);
-        let mut base = C::rand(rng);
-        for _ in 0..num_powers {
-            cur_gen_powers.push(base);
-            base.double_in_place();
-        }
-        cur_gen_powers
-    }
-}
-
-impl<C: CurveGroup, W: Window> CRHScheme for CRH<C, W> {
-    type Input = [u8];
-    type Output = C::Affine;
-    type Parameters = Parameters<C>;
-
-    fn setup<R: Rng>(rng: &mut R) -> Result<Self::Parameters, Error> {
-        let time = start_timer!(|| format!(
-            "PedersenCRH::Setup: {} {}-bit windows; {{0,1}}^{{{}}} -> C",
-            W::NUM_WINDOWS,
-            W::WINDOW_SIZE,
-            W::NUM_WINDOWS * W::WINDOW_SIZE
-        ));
-        let generators = Self::create_generators(rng);
-        end_timer!(time);
-        Ok(Self::Parameters { generators })
+        powers_of_two(C::rand(rng), num_powers)
     }
 
-    fn evaluate<T: Borrow<Self::Input>>(
-        parameters: &Self::Parameters,
-        input: T,
-    ) -> Result<Self::Output, Error> {
+    /// Evaluates the CRH without normalizing the output, with the `tables` of the generators of
+    /// the parameters if they are given.
+    fn evaluate_projective(
+        parameters: &Parameters<C>,
+        tables: Option<&WindowTables<C>>,
+        input: &[u8],
+    ) -> C {
         let eval_time = start_timer!(|| "PedersenCRH::Eval");
-        let input = input.borrow();
         if (input.len() * 8) > W::WINDOW_SIZE * W::NUM_WINDOWS {
             panic!(
                 "incorrect input length {:?} for window params {:?}✕{:?}",
@@ -109,22 +268,82 @@ impl<C: CurveGroup, W: Window> CRHScheme for CRH<C, W> {
 
         // Compute sum of h_i^{m_i} for all i.
         let bits = bytes_to_bits(input);
-        let result = cfg_chunks!(bits, W::WINDOW_SIZE)
-            .zip(&parameters.generators)
-            .map(|(bits, generator_powers)| {
-                let mut encoded = C::zero();
-                for (bit, base) in bits.iter().zip(generator_powers.iter()) {
-                    if *bit {
-                        encoded += base;
-                    }
-                }
-                encoded
-            })
-            .sum::<C>();
+        let result = match tables {
+            Some(tables) => tables.sum_of_windows(&bits, W::WINDOW_SIZE),
+            None => sum_of_windows(&bits, W::WINDOW_SIZE, &parameters.generators),
+        };
 
         end_timer!(eval_time);
 
-        Ok(result.into())
+        result
+    }
+
+    /// Evaluates the CRH as [`CRHScheme::evaluate`], with the precomputed tables of the
+    /// parameters.
+    pub fn evaluate_with_tables(
+        parameters: &PrecomputedParameters<C>,
+        input: &[u8],
+    ) -> Result<C::Affine, Error> {
+        Ok(
+            Self::evaluate_projective(&parameters.parameters, Some(&parameters.tables), input)
+                .into(),
+        )
+    }
+}
+
+impl<C: GroupHash, W: Window> CRH<C, W> {
+    /// Derives the generators deterministically from `personalization`, which must be at most 8
+    /// bytes: the base of the `i`-th window is `FindGroupHash(personalization, I2LEOSP_32(i))`,
+    /// as the generators of the Sapling Pedersen hash.
+    pub fn create_generators_from_domain(personalization: &[u8]) -> Result<Vec<Vec<C>>, Error> {
+        let bases = find_group_hashes::<C>(personalization, 0..W::NUM_WINDOWS)?;
+        Ok(bases
+            .into_iter()
+            .map(|base| powers_of_two(base, W::WINDOW_SIZE))
+            .collect())
+    }
+
+    /// Sets up the parameters with [`Self::create_generators_from_domain`], so that nobody knows
+    /// the discrete logarithms between the generators.
+    pub fn setup_from_domain(personalization: &[u8]) -> Result<Parameters<C>, Error> {
+        Ok(Parameters {
+            generators: Self::create_generators_from_domain(personalization)?,
+        })
+    }
+}
+
+impl<C: CurveGroup, W: Window> CRHScheme for CRH<C, W> {
+    type Input = [u8];
+    type Output = C::Affine;
+    type Parameters = Parameters<C>;
+
+    fn setup<R: Rng>(rng: &mut R) -> Result<Self::Parameters, Error> {
+        let time = start_timer!(|| format!(
+            "PedersenCRH::Setup: {} {}-bit windows; {{0,1}}^{{{}}} -> C",
+            W::NUM_WINDOWS,
+            W::WINDOW_SIZE,
+            W::NUM_WINDOWS * W::WINDOW_SIZE
+        ));
+        let generators = Self::create_generators(rng);
+        end_timer!(time);
+        Ok(Self::Parameters { generators })
+    }
+
+    fn evaluate<T: Borrow<Self::Input>>(
+        parameters: &Self::Parameters,
+        input: T,
+    ) -> Result<Self::Output, Error> {
+        Ok(Self::evaluate_projective(parameters, None, input.borrow()).into())
+    }
+
+    fn batch_evaluate<T: Borrow<Self::Input> + Sync>(
+        parameters: &Self::Parameters,
+        inputs: &[T],
+    ) -> Result<Vec<Self::Output>, Error> {
+        let results: Vec<C> = cfg_iter!(inputs)
+            .map(|input| Self::evaluate_projective(parameters, None, input.borrow()))
+            .collect();
+        Ok(C::normalize_batch(&results))
     }
 }
 
@@ -143,24 +362,11 @@ impl<C: CurveGroup, W: Window> TwoToOneCRH<C, W> {
     pub fn generator_powers<R: Rng>(num_powers: usize, rng: &mut R) -> Vec<C> {
         CRH::<C, W>::generator_powers(num_powers, rng)
     }
-}
-
-impl<C: CurveGroup, W: Window> TwoToOneCRHScheme for TwoToOneCRH<C, W> {
-    type Input = [u8];
-    type Output = C::Affine;
-    type Parameters = Parameters<C>;
-
-    fn setup<R: Rng>(r: &mut R) -> Result<Self::Parameters, Error> {
-        CRH::<C, W>::setup(r)
-    }
 
-    fn evaluate<T: Borrow<Self::Input>>(
-        parameters: &Self::Parameters,
-        left_input: T,
-        right_input: T,
-    ) -> Result<Self::Output, Error> {
-        let left_input = left_input.borrow();
-        let right_input = right_input.borrow();
+    /// Concatenates `left_input` and `right_input` into an input of `CRH`.
+    ///
+    /// `left_input` and `right_input` must be of equal length.
+    fn concat(left_input: &[u8], right_input: &[u8]) -> Vec<u8> {
         assert_eq!(
             left_input.len(),
             right_input.len(),
@@ -176,8 +382,34 @@ impl<C: CurveGroup, W: Window> TwoToOneCRHScheme for TwoToOneCRH<C, W> {
             .iter_mut()
             .zip(left_input.iter().chain(right_input.iter()))
             .for_each(|(b, l_b)| *b = *l_b);
+        buffer
+    }
+}
+
+impl<C: GroupHash, W: Window> TwoToOneCRH<C, W> {
+    pub fn setup_from_domain(personalization: &[u8]) -> Result<Parameters<C>, Error> {
+        CRH::<C, W>::setup_from_domain(personalization)
+    }
+}
+
+impl<C: CurveGroup, W: Window> TwoToOneCRHScheme for TwoToOneCRH<C, W> {
+    type Input = [u8];
+    type Output = C::Affine;
+    type Parameters = Parameters<C>;
 
-        CRH::<C, W>::evaluate(parameters, buffer.as_slice())
+    fn setup<R: Rng>(r: &mut R) -> Result<Self::Parameters, Error> {
+        CRH::<C, W>::setup(r)
+    }
+
+    fn evaluate<T: Borrow<Self::Input>>(
+        parameters: &Self::Parameters,
+        left_input: T,
+        right_input: T,
+    ) -> Result<Self::Output, Error> {
+        CRH::<C, W>::evaluate(
+            parameters,
+            Self::concat(left_input.borrow(), right_input.borrow()),
+        )
     }
 
     /// A simple implementation method: just concat the left input and right input together
@@ -194,6 +426,185 @@ impl<C: CurveGroup, W: Window> TwoToOneCRHScheme for TwoToOneCRH<C, W> {
             crate::to_uncompressed_bytes!(right_input)?,
         )
     }
+
+    fn batch_evaluate<T: Borrow<Self::Input> + Sync>(
+        parameters: &Self::Parameters,
+        inputs: &[(T, T)],
+    ) -> Result<Vec<Self::Output>, Error> {
+        let buffers: Vec<_> = cfg_iter!(inputs)
+            .map(|(left, right)| Self::concat(left.borrow(), right.borrow()))
+            .collect();
+        CRH::<C, W>::batch_evaluate(parameters, &buffers)
+    }
+
+    fn batch_compress<T: Borrow<Self::Output> + Sync>(
+        parameters: &Self::Parameters,
+        inputs: &[(T, T)],
+    ) -> Result<Vec<Self::Output>, Error> {
+        let inputs = cfg_iter!(inputs)
+            .map(|(left, right)| {
+                Ok((
+                    crate::to_uncompressed_bytes!(left)?,
+                    crate::to_uncompressed_bytes!(right)?,
+                ))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        Self::batch_evaluate(parameters, &inputs)
+    }
+}
+
+/// The number of bytes of the little-endian length that prefixes the inputs of
+/// [`VariableLengthCRH`].
+pub const LENGTH_PREFIX_SIZE: usize = 8;
+/// The personalization used by [`VariableLengthCRH::setup`].
+const VARIABLE_LENGTH_PERSONALIZATION: &[u8] = b"ark_PHVL";
+/// The window size used by [`VariableLengthCRH::setup`].
+const VARIABLE_LENGTH_WINDOW_SIZE: usize = 64;
+
+/// The parameters of [`VariableLengthCRH`]: the personalization from which the generators are
+/// derived, the number of bits of each window, and the generators of the first windows, which
+/// are precomputed so that they are not derived again on every evaluation.
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(
+    Clone(bound = ""),
+    Debug(bound = ""),
+    PartialEq(bound = ""),
+    Eq(bound = "")
+)]
+pub struct VariableLengthParameters<C: GroupHash> {
+    personalization: [u8; 8],
+    window_size: usize,
+    generators: Vec<Vec<C>>,
+}
+
+impl<C: GroupHash> VariableLengthParameters<C> {
+    /// Returns the parameters of a personalization of at most 8 bytes and a positive window
+    /// size, without precomputed generators.
+    pub fn new(personalization: &[u8], window_size: usize) -> Result<Self, Error> {
+        if window_size == 0 {
+            return Err(Error::InvalidParameters(
+                "the window size must be positive".into(),
+            ));
+        }
+        Ok(Self {
+            personalization: personalization_block(personalization)?,
+            window_size,
+            generators: Vec::new(),
+        })
+    }
+
+    /// Returns the parameters of [`new`](Self::new), with the generators of the inputs of up
+    /// to `max_input_len` bytes precomputed. The generators of longer inputs are still derived
+    /// on demand.
+    pub fn with_max_input_len(
+        personalization: &[u8],
+        window_size: usize,
+        max_input_len: usize,
+    ) -> Result<Self, Error> {
+        let mut parameters = Self::new(personalization, window_size)?;
+        parameters.generators =
+            parameters.derive_generators(0..parameters.num_windows(max_input_len))?;
+        Ok(parameters)
+    }
+
+    pub fn personalization(&self) -> &[u8; 8] {
+        &self.personalization
+    }
+
+    pub fn window_size(&self) -> usize {
+        self.window_size
+    }
+
+    /// Returns the number of windows of an input of `input_len` bytes, with its length prefix.
+    pub fn num_windows(&self, input_len: usize) -> usize {
+        ((LENGTH_PREFIX_SIZE + input_len) * 8).div_ceil(self.window_size)
+    }
+
+    /// Returns the generators of the first `num_windows` windows, which are those of
+    /// [`CRH::setup_from_domain`] with the same personalization and window size. Only the
+    /// generators which are not precomputed are derived.
+    pub fn generators(&self, num_windows: usize) -> Result<Cow<'_, [Vec<C>]>, Error> {
+        let num_precomputed = self.generators.len();
+        if num_windows <= num_precomputed {
+            return Ok(Cow::Borrowed(&self.generators[..num_windows]));
+        }
+        let mut generators = self.generators.clone();
+        generators.extend(self.derive_generators(num_precomputed..num_windows)?);
+        Ok(Cow::Owned(generators))
+    }
+
+    fn derive_generators(&self, windows: Range<usize>) -> Result<Vec<Vec<C>>, Error> {
+        let bases = find_group_hashes::<C>(&self.personalization, windows)?;
+        Ok(bases
+            .into_iter()
+            .map(|base| powers_of_two(base, self.window_size))
+            .collect())
+    }
+}
+
+/// A Pedersen CRH for inputs of any length, whose number of windows follows the length of the
+/// input. The generators which are not precomputed in the parameters are derived on demand from
+/// their personalization, as [`CRH::setup_from_domain`].
+///
+/// The input is prefixed with its length as a 64-bit little-endian integer, so that inputs that
+/// only differ by trailing zeros, such as `[a]` and `[a, 0]`, have different hashes.
+/// Its gadget can hash a variable number of bytes, up to a maximum length.
+pub struct VariableLengthCRH<C: GroupHash> {
+    group: PhantomData<C>,
+}
+
+impl<C: GroupHash> VariableLengthCRH<C> {
+    /// Returns the length-prefixed input.
+    pub fn encode(input: &[u8]) -> Vec<u8> {
+        [&(input.len() as u64).to_le_bytes()[..], input].concat()
+    }
+}
+
+impl<C: GroupHash> CRHScheme for VariableLengthCRH<C> {
+    type Input = [u8];
+    type Output = C::Affine;
+    type Parameters = VariableLengthParameters<C>;
+
+    fn setup<R: Rng>(_rng: &mut R) -> Result<Self::Parameters, Error> {
+        VariableLengthParameters::new(VARIABLE_LENGTH_PERSONALIZATION, VARIABLE_LENGTH_WINDOW_SIZE)
+    }
+
+    fn evaluate<T: Borrow<Self::Input>>(
+        parameters: &Self::Parameters,
+        input: T,
+    ) -> Result<Self::Output, Error> {
+        let input = input.borrow();
+        let generators = parameters.generators(parameters.num_windows(input.len()))?;
+        let bits = bytes_to_bits(&Self::encode(input));
+        Ok(sum_of_windows(&bits, parameters.window_size, &generators).into())
+    }
+}
+
+/// Computes the sum of `h_i^{m_i}`, where `m_i` is the `i`-th window of `bits` and `h_i` is the
+/// base of `generators[i]`.
+fn sum_of_windows<C: CurveGroup>(bits: &[bool], window_size: usize, generators: &[Vec<C>]) -> C {
+    cfg_chunks!(bits, window_size)
+        .zip(generators)
+        .map(|(bits, generator_powers)| {
+            let mut encoded = C::zero();
+            for (bit, base) in bits.iter().zip(generator_powers.iter()) {
+                if *bit {
+                    encoded += base;
+                }
+            }
+            encoded
+        })
+        .sum::<C>()
+}
+
+/// Returns `base`, `2 * base`, ..., `2^(num_powers - 1) * base`.
+pub(crate) fn powers_of_two<C: CurveGroup>(mut base: C, num_powers: usize) -> Vec<C> {
+    let mut cur_gen_powers = Vec::with_capacity(num_powers);
+    for _ in 0..num_powers {
+        cur_gen_powers.push(base);
+        base.double_in_place();
+    }
+    cur_gen_powers
 }
 
 pub fn bytes_to_bits(bytes: &[u8]) -> Vec<bool> {
@@ -207,6 +618,135 @@ pub fn bytes_to_bits(bytes: &[u8]) -> Vec<bool> {
     bits
 }
 
+/// The uniform random string of the group hash of the Sapling specification.
+pub const GROUP_HASH_URS: &[u8] =
+    b"096b36a5804bfacef1691e173c366a47ff5ba84a44f26ddd7e8d9f79d5b42df0";
+
+/// Groups with a hash to the group, whose outputs have unknown discrete logarithms.
+///
+/// See [Section 5.4.9.5 of the Zcash protocol specification](https://zips.z.cash/protocol/protocol.pdf#concretegrouphashjubjub)
+/// for the group hash of Jubjub, which is generalized to any Twisted Edwards (TE) or short
+/// Weierstrass (SW) curve over a prime field.
+pub trait GroupHash: CurveGroup {
+    /// Computes `GroupHash(D, M)`: the BLAKE2s-256 hash of `GROUP_HASH_URS || M`, personalized
+    /// by `D`, is decoded as a compressed point, which is multiplied by the cofactor.
+    ///
+    /// Returns `None` if the hash is not the encoding of a point, or if the result is zero.
+    fn group_hash(personalization: &[u8; 8], message: &[u8]) -> Option<Self>;
+
+    /// Computes `FindGroupHash(D, M)`: the first `GroupHash(D, M || [i])` which is not `None`,
+    /// for `i` in `0..256`.
+    fn find_group_hash(personalization: &[u8; 8], message: &[u8]) -> Option<Self> {
+        let mut input = [message, &[0]].concat();
+        for i in 0..=u8::MAX {
+            *input.last_mut().unwrap() = i;
+            if let Some(point) = Self::group_hash(personalization, &input) {
+                return Some(point);
+            }
+        }
+        None
+    }
+}
+
+/// Decodes a compressed point of 32 bytes: a coordinate in little-endian, followed by the
+/// parity of the other coordinate in the most significant bit.
+///
+/// Returns `None` if the coordinate is not reduced modulo the characteristic.
+fn decode_compressed<F: PrimeField>(mut bytes: [u8; 32]) -> Option<(F, bool)> {
+    let sign = bytes[31] >> 7 == 1;
+    bytes[31] &= 0x7f;
+    let coordinate = F::from_le_bytes_mod_order(&bytes);
+    let mut canonical = coordinate.into_bigint().to_bytes_le();
+    canonical.resize(32, 0);
+    (canonical == bytes).then_some((coordinate, sign))
+}
+
+/// Returns the square root of `square` whose parity is `sign`, if any.
+fn sqrt_with_parity<F: PrimeField>(square: F, sign: bool) -> Option<F> {
+    let root = square.sqrt()?;
+    if root.into_bigint().is_odd() == sign {
+        Some(root)
+    } else if root == F::ZERO {
+        None
+    } else {
+        Some(-root)
+    }
+}
+
+fn group_hash_bytes(personalization: &[u8; 8], message: &[u8]) -> [u8; 32] {
+    let parameters = blake2s::Parameters {
+        personalization: *personalization,
+    };
+    blake2s::CRH::evaluate(&parameters, [GROUP_HASH_URS, message].concat())
+        .expect("BLAKE2s does not fail")
+}
+
+impl<P: TECurveConfig> GroupHash for te::Projective<P>
+where
+    P::BaseField: PrimeField,
+{
+    /// Decodes the hash as the y-coordinate and the parity of the x-coordinate, as `abst_J`.
+    fn group_hash(personalization: &[u8; 8], message: &[u8]) -> Option<Self> {
+        let (y, sign) =
+            decode_compressed::<P::BaseField>(group_hash_bytes(personalization, message))?;
+        // a * x^2 + y^2 = 1 + d * x^2 * y^2
+        let y2 = y.square();
+        let x2 = (P::BaseField::ONE - y2) * (P::COEFF_A - P::COEFF_D * y2).inverse()?;
+        let x = sqrt_with_parity(x2, sign)?;
+        let point = te::Affine::<P>::new_unchecked(x, y).clear_cofactor();
+        (!point.is_zero()).then(|| point.into_group())
+    }
+}
+
+impl<P: SWCurveConfig> GroupHash for sw::Projective<P>
+where
+    P::BaseField: PrimeField,
+{
+    /// Decodes the hash as the x-coordinate and the parity of the y-coordinate.
+    fn group_hash(personalization: &[u8; 8], message: &[u8]) -> Option<Self> {
+        let (x, sign) =
+            decode_compressed::<P::BaseField>(group_hash_bytes(personalization, message))?;
+        // y^2 = x^3 + a * x + b
+        let y2 = x.square() * x + P::COEFF_A * x + P::COEFF_B;
+        let y = sqrt_with_parity(y2, sign)?;
+        let point = sw::Affine::<P>::new_unchecked(x, y).clear_cofactor();
+        (!point.is_zero()).then(|| point.into_group())
+    }
+}
+
+/// Pads a personalization of at most 8 bytes with zeros.
+pub(crate) fn personalization_block(personalization: &[u8]) -> Result<[u8; 8], Error> {
+    if personalization.len() > 8 {
+        return Err(Error::InvalidParameters(format!(
+            "personalization of {} bytes is longer than 8 bytes",
+            personalization.len()
+        )));
+    }
+    let mut block = [0u8; 8];
+    block[..personalization.len()].copy_from_slice(personalization);
+    Ok(block)
+}
+
+/// Computes `FindGroupHash(personalization, message)`, padding the personalization to 8 bytes.
+pub(crate) fn find_group_hash<C: GroupHash>(
+    personalization: &[u8],
+    message: &[u8],
+) -> Result<C, Error> {
+    C::find_group_hash(&personalization_block(personalization)?, message).ok_or_else(|| {
+        Error::InvalidParameters(format!("no group hash of the message {:?}", message))
+    })
+}
+
+/// Computes `FindGroupHash(personalization, I2LEOSP_32(i))` for `i` in `indices`.
+pub(crate) fn find_group_hashes<C: GroupHash>(
+    personalization: &[u8],
+    indices: Range<usize>,
+) -> Result<Vec<C>, Error> {
+    cfg_into_iter!(indices.start as u32..indices.end as u32)
+        .map(|i| find_group_hash(personalization, &i.to_le_bytes()))
+        .collect()
+}
+
 impl<C: CurveGroup> Debug for Parameters<C> {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
         writeln!(f, "Pedersen Hash Parameters {{")?;
@@ -225,3 +765,184 @@ impl<ConstraintF: Field, C: CurveGroup + ToConstraintField<ConstraintF>>
         Some(Vec::new())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::crh::pedersen::{
+        GroupHash, PrecomputedParameters, VariableLengthCRH, VariableLengthParameters, Window, CRH,
+    };
+    use crate::crh::CRHScheme;
+    use crate::Error;
+    use ark_ec::CurveGroup;
+    use ark_ed_on_bls12_381::{EdwardsAffine, EdwardsProjective, Fq};
+    use ark_ff::MontFp;
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+    use ark_std::{test_rng, UniformRand};
+
+    #[derive(Clone)]
+    struct TestWindow {}
+    impl Window for TestWindow {
+        const WINDOW_SIZE: usize = 64;
+        const NUM_WINDOWS: usize = 4;
+    }
+
+    type TestCRH = CRH<EdwardsProjective, TestWindow>;
+
+    #[test]
+    fn sapling_generators() {
+        // the first two generators of the Sapling Pedersen hash, and its randomness base
+        let expected = [
+            EdwardsAffine::new(
+                MontFp!(
+                    "52355368488200756720908213129543630848976972731871436319321443845291207170897"
+                ),
+                MontFp!(
+                    "18372611905088487385433946659983357101887954355879737496286092836680199584970"
+                ),
+            ),
+            EdwardsAffine::new(
+                MontFp!(
+                    "9787319019520772215561425571402619434275350335445140843695488791465664995454"
+                ),
+                MontFp!(
+                    "617599303620822769724880923839314378351145790385632133893219494436232173713"
+                ),
+            ),
+        ];
+        let generators = TestCRH::create_generators_from_domain(b"Zcash_PH").unwrap();
+        assert_eq!(generators[0][0].into_affine(), expected[0]);
+        assert_eq!(generators[1][0].into_affine(), expected[1]);
+        assert_eq!(generators[0][1], generators[0][0] + generators[0][0]);
+
+        let randomness_base: Fq = MontFp!(
+            "17604198421250097151573650471091947092640882385666301668182991308218746233954"
+        );
+        assert_eq!(
+            EdwardsProjective::find_group_hash(b"Zcash_PH", b"r")
+                .unwrap()
+                .into_affine()
+                .x,
+            randomness_base
+        );
+    }
+
+    #[test]
+    fn setup_from_domain() {
+        let parameters = TestCRH::setup_from_domain(b"test").unwrap();
+        assert_eq!(
+            parameters.generators,
+            TestCRH::setup_from_domain(b"test").unwrap().generators
+        );
+        assert_ne!(
+            parameters.generators,
+            TestCRH::setup_from_domain(b"other").unwrap().generators
+        );
+        TestCRH::evaluate(&parameters, [1u8, 2, 3].as_slice()).unwrap();
+
+        assert!(TestCRH::setup_from_domain(b"8 bytes!").is_ok());
+        assert!(matches!(
+            TestCRH::setup_from_domain(b"much too long"),
+            Err(Error::InvalidParameters(_))
+        ));
+    }
+
+    #[test]
+    fn short_weierstrass_group_hash() {
+        let point = ark_bn254::G1Projective::find_group_hash(b"test\0\0\0\0", b"")
+            .unwrap()
+            .into_affine();
+        assert!(point.is_on_curve());
+    }
+
+    #[test]
+    fn variable_length() {
+        let parameters = VariableLengthParameters::new(b"test", 64).unwrap();
+        let hash = |input: &[u8]| {
+            VariableLengthCRH::<EdwardsProjective>::evaluate(&parameters, input).unwrap()
+        };
+
+        // the length prefix separates inputs which only differ by trailing zeros
+        assert_ne!(hash(&[]), hash(&[0]));
+        assert_ne!(hash(&[1]), hash(&[1, 0]));
+
+        // the generators are those of the fixed-length hash of the same domain
+        let input = [7u8; 20];
+        let fixed = TestCRH::setup_from_domain(b"test").unwrap();
+        assert_eq!(
+            hash(&input),
+            TestCRH::evaluate(
+                &fixed,
+                VariableLengthCRH::<EdwardsProjective>::encode(&input)
+            )
+            .unwrap()
+        );
+
+        // inputs longer than the fixed-length hash are supported
+        assert_eq!(parameters.num_windows(100), 14);
+        hash(&[1u8; 100]);
+
+        // precomputing the generators of short inputs does not change the hashes of shorter or
+        // longer inputs
+        let precomputed = VariableLengthParameters::with_max_input_len(b"test", 64, 20).unwrap();
+        assert_eq!(precomputed.generators(4).unwrap().len(), 4);
+        for len in [0, 20, 100] {
+            let input = [3u8; 100];
+            assert_eq!(
+                VariableLengthCRH::<EdwardsProjective>::evaluate(&precomputed, &input[..len])
+                    .unwrap(),
+                hash(&input[..len])
+            );
+        }
+
+        assert!(matches!(
+            VariableLengthParameters::new(b"test", 0),
+            Err(Error::InvalidParameters(_))
+        ));
+    }
+
+    #[test]
+    fn window_tables() {
+        let mut rng = test_rng();
+        let parameters = TestCRH::setup(&mut rng).unwrap();
+        // the parts of 5 bits do not divide the windows of 64 bits
+        let precomputed = PrecomputedParameters::new(parameters.clone(), 5).unwrap();
+        for len in [0, 1, 17, 32] {
+            let input: Vec<_> = (0..len).map(|_| u8::rand(&mut rng)).collect();
+            assert_eq!(
+                TestCRH::evaluate_with_tables(&precomputed, input.as_slice()).unwrap(),
+                TestCRH::evaluate(&parameters, input.as_slice()).unwrap()
+            );
+        }
+
+        let mut bytes = Vec::new();
+        precomputed.serialize_compressed(&mut bytes).unwrap();
+        let deserialized =
+            PrecomputedParameters::<EdwardsProjective>::deserialize_compressed(&*bytes).unwrap();
+        assert_eq!(deserialized.tables(), precomputed.tables());
+
+        // tables which are not those of the generators are rejected
+        let mut wrong_entry = precomputed.clone();
+        wrong_entry.tables.tables[1][2][1 << 3] = wrong_entry.tables.tables[1][2][1 << 2];
+        let mut missing_window = precomputed.clone();
+        missing_window.tables.tables.pop();
+        let mut other_generators = precomputed.clone();
+        other_generators.parameters = TestCRH::setup(&mut rng).unwrap();
+        for tables in [wrong_entry, missing_window, other_generators] {
+            let mut bytes = Vec::new();
+            tables.serialize_compressed(&mut bytes).unwrap();
+            assert!(matches!(
+                PrecomputedParameters::<EdwardsProjective>::deserialize_compressed(&*bytes),
+                Err(SerializationError::InvalidData)
+            ));
+        }
+
+        assert!(matches!(
+            PrecomputedParameters::new(parameters.clone(), 0),
+            Err(Error::InvalidParameters(_))
+        ));
+        assert!(matches!(
+            PrecomputedParameters::new(parameters, 17),
+            Err(Error::InvalidParameters(_))
+        ));
+    }
+}
@@ -1,5 +1,7 @@
-use crate::crh::poseidon::{TwoToOneCRH, CRH};
-use crate::crh::CRHScheme;
+use crate::crh::poseidon::{
+    PoseidonCompression, TwoToOneCRH, VariableLengthPoseidonCRH, COMPRESSION_DOMAIN_TAG, CRH,
+};
+use crate::crh::{CRHScheme, TwoToOneCRHScheme};
 use crate::crh::{
     CRHSchemeGadget as CRHGadgetTrait, TwoToOneCRHSchemeGadget as TwoToOneCRHGadgetTrait,
 };
@@ -10,7 +12,8 @@ use crate::sponge::poseidon::PoseidonConfig;
 use crate::sponge::Absorb;
 use ark_ff::PrimeField;
 use ark_r1cs_std::alloc::{AllocVar, AllocationMode};
-use ark_r1cs_std::fields::fp::FpVar;
+use ark_r1cs_std::eq::EqGadget;
+use ark_r1cs_std::fields::{fp::FpVar, FieldVar};
 use ark_r1cs_std::R1CSVar;
 use ark_relations::r1cs::{Namespace, SynthesisError};
 use ark_std::borrow::Borrow;
@@ -97,6 +100,164 @@ impl<F: PrimeField + Absorb> TwoToOneCRHGadgetTrait<TwoToOneCRH<F>, F> for TwoTo
     }
 }
 
+pub struct PoseidonCompressionGadget<F: PrimeField + Absorb> {
+    field_phantom: PhantomData<F>,
+}
+
+impl<F: PrimeField + Absorb> TwoToOneCRHGadgetTrait<PoseidonCompression<F>, F>
+    for PoseidonCompressionGadget<F>
+{
+    type InputVar = FpVar<F>;
+    type OutputVar = FpVar<F>;
+    type ParametersVar = CRHParametersVar<F>;
+
+    fn evaluate(
+        parameters: &Self::ParametersVar,
+        left_input: &Self::InputVar,
+        right_input: &Self::InputVar,
+    ) -> Result<Self::OutputVar, SynthesisError> {
+        Self::compress(parameters, left_input, right_input)
+    }
+
+    fn compress(
+        parameters: &Self::ParametersVar,
+        left_input: &Self::OutputVar,
+        right_input: &Self::OutputVar,
+    ) -> Result<Self::OutputVar, SynthesisError> {
+        let cs = left_input.cs().or(right_input.cs());
+
+        if cs.is_none() {
+            Ok(FpVar::Constant(
+                PoseidonCompression::<F>::compress(
+                    &parameters.parameters,
+                    left_input.value()?,
+                    right_input.value()?,
+                )
+                .unwrap(),
+            ))
+        } else {
+            assert!(
+                parameters.parameters.rate + parameters.parameters.capacity >= 3,
+                "the Poseidon compression function requires a state of at least 3 elements"
+            );
+            let mut sponge = PoseidonSpongeVar::new(cs, &parameters.parameters);
+            sponge.state[0] = FpVar::Constant(F::from(COMPRESSION_DOMAIN_TAG));
+            sponge.state[1] = left_input.clone();
+            sponge.state[2] = right_input.clone();
+            sponge.permute()?;
+            Ok(&sponge.state[1] + left_input)
+        }
+    }
+}
+
+pub struct VariableLengthPoseidonCRHGadget<F: PrimeField + Absorb> {
+    field_phantom: PhantomData<F>,
+}
+
+impl<F: PrimeField + Absorb> VariableLengthPoseidonCRHGadget<F> {
+    /// Hashes the first `length` elements of `input`, where `length` is at most `input.len()`.
+    ///
+    /// The cost only depends on `input.len()`, which is the maximum length.
+    /// The constraints are unsatisfied if `length` is larger than `input.len()`.
+    pub fn evaluate_with_length(
+        parameters: &CRHParametersVar<F>,
+        input: &[FpVar<F>],
+        length: &FpVar<F>,
+    ) -> Result<FpVar<F>, SynthesisError> {
+        let cs = input.cs().or(length.cs());
+
+        if cs.is_none() {
+            let length = length.value()?;
+            let length = (0..=input.len())
+                .find(|i| F::from(*i as u64) == length)
+                .ok_or(SynthesisError::Unsatisfiable)?;
+            return Ok(FpVar::Constant(
+                VariableLengthPoseidonCRH::<F>::evaluate(
+                    &parameters.parameters,
+                    input[..length].value()?,
+                )
+                .unwrap(),
+            ));
+        }
+
+        // `is_end[i]` is whether `i == length`, and exactly one of them is set
+        let is_end = (0..=input.len())
+            .map(|i| length.is_eq(&FpVar::Constant(F::from(i as u64))))
+            .collect::<Result<Vec<_>, _>>()?;
+        is_end
+            .iter()
+            .fold(FpVar::zero(), |sum, is_end| {
+                sum + FpVar::from(is_end.clone())
+            })
+            .enforce_equal(&FpVar::one())?;
+
+        // the input is padded with a one at index `length` and zeros after it
+        let rate = parameters.parameters.rate;
+        let num_blocks = input.len() / rate + 1;
+        let mut padded = vec![FpVar::zero(); num_blocks * rate];
+        padded[input.len()] = FpVar::from(is_end[input.len()].clone());
+        // whether `i < length`, which is the sum of `is_end[j]` for `j > i`
+        let mut is_in_input = FpVar::zero();
+        for i in (0..input.len()).rev() {
+            is_in_input += FpVar::from(is_end[i + 1].clone());
+            padded[i] = &input[i] * &is_in_input + FpVar::from(is_end[i].clone());
+        }
+
+        // the hash is squeezed after the block containing index `length`
+        let capacity = parameters.parameters.capacity;
+        let mut sponge = PoseidonSpongeVar::new(cs, &parameters.parameters);
+        let mut output = FpVar::zero();
+        for (block, elements) in padded.chunks(rate).enumerate() {
+            for (j, element) in elements.iter().enumerate() {
+                sponge.state[capacity + j] += element;
+            }
+            sponge.permute()?;
+            let is_last_block = is_end
+                .iter()
+                .skip(block * rate)
+                .take(rate)
+                .fold(FpVar::zero(), |sum, is_end| {
+                    sum + FpVar::from(is_end.clone())
+                });
+            output += &sponge.state[capacity] * is_last_block;
+        }
+        Ok(output)
+    }
+}
+
+impl<F: PrimeField + Absorb> CRHGadgetTrait<VariableLengthPoseidonCRH<F>, F>
+    for VariableLengthPoseidonCRHGadget<F>
+{
+    type InputVar = [FpVar<F>];
+    type OutputVar = FpVar<F>;
+    type ParametersVar = CRHParametersVar<F>;
+
+    fn evaluate(
+        parameters: &Self::ParametersVar,
+        input: &Self::InputVar,
+    ) -> Result<Self::OutputVar, SynthesisError> {
+        let cs = input.cs();
+
+        if cs.is_none() {
+            Ok(FpVar::Constant(
+                VariableLengthPoseidonCRH::<F>::evaluate(&parameters.parameters, input.value()?)
+                    .unwrap(),
+            ))
+        } else {
+            let rate = parameters.parameters.rate;
+            let num_blocks = input.len() / rate + 1;
+            let mut padded = input.to_vec();
+            padded.push(FpVar::one());
+            padded.resize(num_blocks * rate, FpVar::zero());
+
+            let mut sponge = PoseidonSpongeVar::new(cs, &parameters.parameters);
+            sponge.absorb(&padded)?;
+            let res = sponge.squeeze_field_elements(1)?;
+            Ok(res[0].clone())
+        }
+    }
+}
+
 impl<F: PrimeField + Absorb> AllocVar<PoseidonConfig<F>, F> for CRHParametersVar<F> {
     fn new_variable<T: Borrow<PoseidonConfig<F>>>(
         _cs: impl Into<Namespace<F>>,
@@ -113,8 +274,11 @@ impl<F: PrimeField + Absorb> AllocVar<PoseidonConfig<F>, F> for CRHParametersVar
 
 #[cfg(test)]
 mod test {
-    use crate::crh::poseidon::constraints::{CRHGadget, CRHParametersVar, TwoToOneCRHGadget};
-    use crate::crh::poseidon::{TwoToOneCRH, CRH};
+    use crate::crh::poseidon::constraints::{
+        CRHGadget, CRHParametersVar, PoseidonCompressionGadget, TwoToOneCRHGadget,
+        VariableLengthPoseidonCRHGadget,
+    };
+    use crate::crh::poseidon::{PoseidonCompression, TwoToOneCRH, VariableLengthPoseidonCRH, CRH};
     use crate::crh::{CRHScheme, CRHSchemeGadget};
     use crate::crh::{TwoToOneCRHScheme, TwoToOneCRHSchemeGadget};
     use crate::sponge::poseidon::PoseidonConfig;
@@ -185,4 +349,84 @@ mod test {
         assert_eq!(crh_b, crh_b_g.value().unwrap());
         assert_eq!(crh, crh_g.value().unwrap());
     }
+
+    #[test]
+    fn test_compression_consistency() {
+        let mut test_rng = ark_std::test_rng();
+        let params = PoseidonCompression::<Fr>::setup(&mut test_rng).unwrap();
+        let left = Fr::rand(&mut test_rng);
+        let right = Fr::rand(&mut test_rng);
+        let compressed = PoseidonCompression::<Fr>::compress(&params, left, right).unwrap();
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let left_g = FpVar::new_witness(cs.clone(), || Ok(left)).unwrap();
+        let right_g = FpVar::new_witness(cs.clone(), || Ok(right)).unwrap();
+        let params_g = CRHParametersVar::<Fr>::new_constant(cs.clone(), &params).unwrap();
+
+        let num_constraints = cs.num_constraints();
+        let two_to_one_g = TwoToOneCRHGadget::<Fr>::compress(&params_g, &left_g, &right_g).unwrap();
+        let two_to_one_constraints = cs.num_constraints() - num_constraints;
+
+        let num_constraints = cs.num_constraints();
+        let compressed_g =
+            PoseidonCompressionGadget::<Fr>::compress(&params_g, &left_g, &right_g).unwrap();
+        assert_eq!(compressed, compressed_g.value().unwrap());
+        assert_ne!(two_to_one_g.value().unwrap(), compressed_g.value().unwrap());
+        assert!(cs.is_satisfied().unwrap());
+        // a single permutation whose first element is a constant, and a free feed-forward
+        assert_eq!(
+            cs.num_constraints() - num_constraints,
+            two_to_one_constraints
+        );
+
+        let left_c = FpVar::Constant(left);
+        let right_c = FpVar::Constant(right);
+        let compressed_c =
+            PoseidonCompressionGadget::<Fr>::compress(&params_g, &left_c, &right_c).unwrap();
+        assert_eq!(compressed, compressed_c.value().unwrap());
+    }
+
+    #[test]
+    fn test_variable_length_consistency() {
+        let mut test_rng = ark_std::test_rng();
+        let params = VariableLengthPoseidonCRH::<Fr>::setup(&mut test_rng).unwrap();
+        let input: Vec<_> = (0..7).map(|_| Fr::rand(&mut test_rng)).collect();
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let params_g = CRHParametersVar::<Fr>::new_constant(cs.clone(), &params).unwrap();
+        let input_g = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(input.as_slice())).unwrap();
+        for length in [0, 1, 2, 5, 6, 7] {
+            let expected =
+                VariableLengthPoseidonCRH::<Fr>::evaluate(&params, &input[..length]).unwrap();
+
+            let hash_g =
+                VariableLengthPoseidonCRHGadget::<Fr>::evaluate(&params_g, &input_g[..length])
+                    .unwrap();
+            assert_eq!(expected, hash_g.value().unwrap());
+
+            let length_g = FpVar::new_witness(cs.clone(), || Ok(Fr::from(length as u64))).unwrap();
+            let hash_g = VariableLengthPoseidonCRHGadget::<Fr>::evaluate_with_length(
+                &params_g, &input_g, &length_g,
+            )
+            .unwrap();
+            assert_eq!(expected, hash_g.value().unwrap());
+
+            let length_c = FpVar::Constant(Fr::from(length as u64));
+            let input_c: Vec<_> = input.iter().map(|x| FpVar::Constant(*x)).collect();
+            let hash_c = VariableLengthPoseidonCRHGadget::<Fr>::evaluate_with_length(
+                &params_g, &input_c, &length_c,
+            )
+            .unwrap();
+            assert_eq!(expected, hash_c.value().unwrap());
+        }
+        assert!(cs.is_satisfied().unwrap());
+
+        // the length cannot exceed the maximum length
+        let length_g = FpVar::new_witness(cs.clone(), || Ok(Fr::from(8u64))).unwrap();
+        let _ = VariableLengthPoseidonCRHGadget::<Fr>::evaluate_with_length(
+            &params_g, &input_g, &length_g,
+        )
+        .unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
 }
@@ -1,15 +1,44 @@
 use crate::crh::TwoToOneCRHScheme;
-use crate::sponge::poseidon::{PoseidonConfig, PoseidonSponge};
+use crate::sponge::poseidon::{secure_poseidon_config, PoseidonConfig, PoseidonSponge};
 use crate::sponge::{Absorb, CryptographicSponge};
 use crate::{crh::CRHScheme, Error};
 use ark_ff::PrimeField;
 use ark_std::borrow::Borrow;
 use ark_std::marker::PhantomData;
 use ark_std::rand::Rng;
+#[cfg(not(feature = "std"))]
+use ark_std::vec::Vec;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
 #[cfg(feature = "r1cs")]
 pub mod constraints;
 
+/// Applies `f` to each of the `inputs` with a sponge that is reset between inputs,
+/// instead of cloning the parameters into a new sponge for each input.
+fn batch_with_sponge<F: PrimeField, T: Sync, O: Send>(
+    parameters: &PoseidonConfig<F>,
+    inputs: &[T],
+    f: impl Fn(&mut PoseidonSponge<F>, &T) -> O + Sync + Send,
+) -> Vec<O> {
+    let f = |sponge: &mut PoseidonSponge<F>, input: &T| {
+        sponge.reset();
+        f(sponge, input)
+    };
+    #[cfg(not(feature = "parallel"))]
+    {
+        let mut sponge = PoseidonSponge::new(parameters);
+        inputs.iter().map(|input| f(&mut sponge, input)).collect()
+    }
+    #[cfg(feature = "parallel")]
+    {
+        inputs
+            .par_iter()
+            .map_init(|| PoseidonSponge::new(parameters), f)
+            .collect()
+    }
+}
+
 pub struct CRH<F: PrimeField + Absorb> {
     field_phantom: PhantomData<F>,
 }
@@ -20,9 +49,7 @@ impl<F: PrimeField + Absorb> CRHScheme for CRH<F> {
     type Parameters = PoseidonConfig<F>;
 
     fn setup<R: Rng>(_rng: &mut R) -> Result<Self::Parameters, Error> {
-        // automatic generation of parameters are not implemented yet
-        // therefore, the developers must specify the parameters themselves
-        unimplemented!()
+        secure_poseidon_config(2)
     }
 
     fn evaluate<T: Borrow<Self::Input>>(
@@ -36,6 +63,16 @@ impl<F: PrimeField + Absorb> CRHScheme for CRH<F> {
         let res = sponge.squeeze_field_elements::<F>(1);
         Ok(res[0])
     }
+
+    fn batch_evaluate<T: Borrow<Self::Input> + Sync>(
+        parameters: &Self::Parameters,
+        inputs: &[T],
+    ) -> Result<Vec<Self::Output>, Error> {
+        Ok(batch_with_sponge(parameters, inputs, |sponge, input| {
+            sponge.absorb(&input.borrow());
+            sponge.squeeze_field_elements::<F>(1)[0]
+        }))
+    }
 }
 
 pub struct TwoToOneCRH<F: PrimeField + Absorb> {
@@ -48,9 +85,7 @@ impl<F: PrimeField + Absorb> TwoToOneCRHScheme for TwoToOneCRH<F> {
     type Parameters = PoseidonConfig<F>;
 
     fn setup<R: Rng>(_rng: &mut R) -> Result<Self::Parameters, Error> {
-        // automatic generation of parameters are not implemented yet
-        // therefore, the developers must specify the parameters themselves
-        unimplemented!()
+        secure_poseidon_config(2)
     }
 
     fn evaluate<T: Borrow<Self::Input>>(
@@ -75,4 +110,279 @@ impl<F: PrimeField + Absorb> TwoToOneCRHScheme for TwoToOneCRH<F> {
         let res = sponge.squeeze_field_elements::<F>(1);
         Ok(res[0])
     }
+
+    fn batch_evaluate<T: Borrow<Self::Input> + Sync>(
+        parameters: &Self::Parameters,
+        inputs: &[(T, T)],
+    ) -> Result<Vec<Self::Output>, Error> {
+        Self::batch_compress(parameters, inputs)
+    }
+
+    fn batch_compress<T: Borrow<Self::Output> + Sync>(
+        parameters: &Self::Parameters,
+        inputs: &[(T, T)],
+    ) -> Result<Vec<Self::Output>, Error> {
+        Ok(batch_with_sponge(
+            parameters,
+            inputs,
+            |sponge, (left, right)| {
+                sponge.absorb(left.borrow());
+                sponge.absorb(right.borrow());
+                sponge.squeeze_field_elements::<F>(1)[0]
+            },
+        ))
+    }
+}
+
+/// The Poseidon paper's domain tag (capacity element) of a 2-to-1 Merkle tree compression,
+/// `2^arity - 1`. Sponges start with a zero capacity element.
+pub(crate) const COMPRESSION_DOMAIN_TAG: u64 = 3;
+
+/// A two-to-one compression function that applies a single Poseidon permutation.
+///
+/// The permutation is applied to the state `[3, left, right, 0, ...]`, where the first element is
+/// the domain tag of a 2-to-1 compression as described in the [Poseidon paper](https://eprint.iacr.org/2019/458),
+/// and the output is the second element of the permuted state, plus `left` (feed-forward).
+/// As its domain tag differs from the initial capacity element of `PoseidonSponge`,
+/// and the output is fed forward, it is separated from [`TwoToOneCRH`] with the same parameters.
+///
+/// The parameters must have a state of at least 3 elements.
+pub struct PoseidonCompression<F: PrimeField + Absorb> {
+    field_phantom: PhantomData<F>,
+}
+
+impl<F: PrimeField + Absorb> TwoToOneCRHScheme for PoseidonCompression<F> {
+    type Input = F;
+    type Output = F;
+    type Parameters = PoseidonConfig<F>;
+
+    fn setup<R: Rng>(_rng: &mut R) -> Result<Self::Parameters, Error> {
+        secure_poseidon_config(2)
+    }
+
+    fn evaluate<T: Borrow<Self::Input>>(
+        parameters: &Self::Parameters,
+        left_input: T,
+        right_input: T,
+    ) -> Result<Self::Output, Error> {
+        Self::compress(parameters, left_input, right_input)
+    }
+
+    fn compress<T: Borrow<Self::Output>>(
+        parameters: &Self::Parameters,
+        left_input: T,
+        right_input: T,
+    ) -> Result<Self::Output, Error> {
+        Self::check_parameters(parameters)?;
+        let mut sponge = PoseidonSponge::new(parameters);
+        Ok(Self::compress_with_sponge(
+            &mut sponge,
+            *left_input.borrow(),
+            *right_input.borrow(),
+        ))
+    }
+
+    fn batch_evaluate<T: Borrow<Self::Input> + Sync>(
+        parameters: &Self::Parameters,
+        inputs: &[(T, T)],
+    ) -> Result<Vec<Self::Output>, Error> {
+        Self::batch_compress(parameters, inputs)
+    }
+
+    fn batch_compress<T: Borrow<Self::Output> + Sync>(
+        parameters: &Self::Parameters,
+        inputs: &[(T, T)],
+    ) -> Result<Vec<Self::Output>, Error> {
+        Self::check_parameters(parameters)?;
+        Ok(batch_with_sponge(
+            parameters,
+            inputs,
+            |sponge, (left, right)| {
+                Self::compress_with_sponge(sponge, *left.borrow(), *right.borrow())
+            },
+        ))
+    }
+}
+
+impl<F: PrimeField + Absorb> PoseidonCompression<F> {
+    fn check_parameters(parameters: &PoseidonConfig<F>) -> Result<(), Error> {
+        if parameters.rate + parameters.capacity < 3 {
+            return Err(Error::InvalidParameters(
+                "the Poseidon compression function requires a state of at least 3 elements".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Compresses `left_input` and `right_input` with a sponge in its initial state.
+    fn compress_with_sponge(sponge: &mut PoseidonSponge<F>, left_input: F, right_input: F) -> F {
+        sponge.state[0] = F::from(COMPRESSION_DOMAIN_TAG);
+        sponge.state[1] = left_input;
+        sponge.state[2] = right_input;
+        sponge.permute();
+        sponge.state[1] + left_input
+    }
+}
+
+/// A CRH for inputs of variable length, which pads the input with a one followed by zeros
+/// (10* padding) to a multiple of the rate before absorbing it into a `PoseidonSponge`.
+///
+/// Unlike [`CRH`], inputs that only differ by trailing zeros, such as `[a]` and `[a, 0]`,
+/// have different hashes.
+/// Its gadget can hash a variable number of elements, up to a maximum length.
+pub struct VariableLengthPoseidonCRH<F: PrimeField + Absorb> {
+    field_phantom: PhantomData<F>,
+}
+
+impl<F: PrimeField + Absorb> CRHScheme for VariableLengthPoseidonCRH<F> {
+    type Input = [F];
+    type Output = F;
+    type Parameters = PoseidonConfig<F>;
+
+    fn setup<R: Rng>(_rng: &mut R) -> Result<Self::Parameters, Error> {
+        secure_poseidon_config(2)
+    }
+
+    fn evaluate<T: Borrow<Self::Input>>(
+        parameters: &Self::Parameters,
+        input: T,
+    ) -> Result<Self::Output, Error> {
+        let input = input.borrow();
+
+        // the padded input is a non-empty multiple of the rate
+        let num_blocks = input.len() / parameters.rate + 1;
+        let mut padded = Vec::with_capacity(num_blocks * parameters.rate);
+        padded.extend_from_slice(input);
+        padded.push(F::one());
+        padded.resize(num_blocks * parameters.rate, F::zero());
+
+        let mut sponge = PoseidonSponge::new(parameters);
+        sponge.absorb(&padded);
+        let res = sponge.squeeze_field_elements::<F>(1);
+        Ok(res[0])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::crh::poseidon::{PoseidonCompression, TwoToOneCRH, VariableLengthPoseidonCRH, CRH};
+    use crate::crh::{CRHScheme, TwoToOneCRHScheme};
+    use crate::sponge::poseidon::PoseidonSponge;
+    use crate::sponge::CryptographicSponge;
+    use ark_ed_on_bls12_381::Fq as Fr;
+    use ark_std::{test_rng, UniformRand};
+
+    #[test]
+    fn setup_generates_secure_parameters() {
+        let mut rng = test_rng();
+        let parameters = CRH::<Fr>::setup(&mut rng).unwrap();
+        assert_eq!(parameters.rate, 2);
+        assert_eq!(parameters.capacity, 1);
+        // 3 divides p - 1 for the scalar field of BLS12-381
+        assert_eq!(parameters.alpha, 5);
+        assert_eq!((parameters.full_rounds, parameters.partial_rounds), (8, 56));
+        assert!(parameters.optimized().is_some());
+
+        let two_to_one_parameters = TwoToOneCRH::<Fr>::setup(&mut rng).unwrap();
+        assert_eq!(two_to_one_parameters.ark, parameters.ark);
+        assert_eq!(two_to_one_parameters.mds, parameters.mds);
+
+        let input: Vec<_> = (0..5).map(|_| Fr::rand(&mut rng)).collect();
+        let left = CRH::<Fr>::evaluate(&parameters, input.as_slice()).unwrap();
+        let right = CRH::<Fr>::evaluate(&parameters, &input[1..]).unwrap();
+        assert_ne!(left, right);
+        TwoToOneCRH::<Fr>::evaluate(&two_to_one_parameters, left, right).unwrap();
+    }
+
+    #[test]
+    fn compression_is_domain_separated() {
+        let mut rng = test_rng();
+        let parameters = PoseidonCompression::<Fr>::setup(&mut rng).unwrap();
+        let left = Fr::rand(&mut rng);
+        let right = Fr::rand(&mut rng);
+
+        let compressed = PoseidonCompression::<Fr>::compress(&parameters, left, right).unwrap();
+        assert_eq!(
+            compressed,
+            PoseidonCompression::<Fr>::evaluate(&parameters, left, right).unwrap()
+        );
+        assert_ne!(
+            compressed,
+            PoseidonCompression::<Fr>::compress(&parameters, right, left).unwrap()
+        );
+        assert_ne!(
+            compressed,
+            TwoToOneCRH::<Fr>::compress(&parameters, left, right).unwrap()
+        );
+
+        let mut small_parameters = parameters.clone();
+        small_parameters.rate = 1;
+        assert!(PoseidonCompression::<Fr>::compress(&small_parameters, left, right).is_err());
+    }
+
+    #[test]
+    fn variable_length_is_padded() {
+        let mut rng = test_rng();
+        let parameters = VariableLengthPoseidonCRH::<Fr>::setup(&mut rng).unwrap();
+        let a = Fr::rand(&mut rng);
+        let zero = Fr::from(0u64);
+
+        let hashes: Vec<_> = [
+            vec![],
+            vec![zero],
+            vec![a],
+            vec![a, zero],
+            vec![a, zero, zero],
+        ]
+        .iter()
+        .map(|input| {
+            VariableLengthPoseidonCRH::<Fr>::evaluate(&parameters, input.as_slice()).unwrap()
+        })
+        .collect();
+        for i in 0..hashes.len() {
+            for j in 0..i {
+                assert_ne!(hashes[i], hashes[j]);
+            }
+        }
+
+        // [a, 0] is padded to [a, 0, 1, 0] with a rate of 2
+        let mut sponge = PoseidonSponge::new(&parameters);
+        sponge.absorb(&vec![a, zero, Fr::from(1u64), zero]);
+        assert_eq!(sponge.squeeze_field_elements::<Fr>(1)[0], hashes[3]);
+    }
+
+    #[test]
+    fn batch_evaluation_matches_evaluate() {
+        let mut rng = test_rng();
+        let parameters = CRH::<Fr>::setup(&mut rng).unwrap();
+        let inputs: Vec<Vec<Fr>> = (0..5)
+            .map(|len| (0..len).map(|_| Fr::rand(&mut rng)).collect())
+            .collect();
+        let expected: Vec<_> = inputs
+            .iter()
+            .map(|input| CRH::<Fr>::evaluate(&parameters, input.as_slice()).unwrap())
+            .collect();
+        assert_eq!(
+            CRH::<Fr>::batch_evaluate(&parameters, &inputs).unwrap(),
+            expected
+        );
+
+        let pairs: Vec<_> = expected.chunks(2).map(|p| (p[0], p[p.len() - 1])).collect();
+        let expected: Vec<_> = pairs
+            .iter()
+            .map(|(l, r)| TwoToOneCRH::<Fr>::compress(&parameters, l, r).unwrap())
+            .collect();
+        assert_eq!(
+            TwoToOneCRH::<Fr>::batch_compress(&parameters, &pairs).unwrap(),
+            expected
+        );
+        let expected: Vec<_> = pairs
+            .iter()
+            .map(|(l, r)| PoseidonCompression::<Fr>::compress(&parameters, l, r).unwrap())
+            .collect();
+        assert_eq!(
+            PoseidonCompression::<Fr>::batch_compress(&parameters, &pairs).unwrap(),
+            expected
+        );
+    }
 }
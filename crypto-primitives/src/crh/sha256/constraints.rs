@@ -13,8 +13,10 @@ use ark_r1cs_std::{
     boolean::Boolean,
     convert::ToBytesGadget,
     eq::EqGadget,
+    fields::fp::FpVar,
     select::CondSelectGadget,
     uint32::UInt32,
+    uint64::UInt64,
     uint8::UInt8,
     R1CSVar,
 };
@@ -213,6 +215,75 @@ impl<ConstraintF: PrimeField> Sha256Gadget<ConstraintF> {
         sha256_var.update(data)?;
         sha256_var.finalize()
     }
+
+    /// Computes the digest of the first `len` bytes of `data`, where `len` is a variable, so
+    /// that the length of the message can be kept secret. `data` holds the message followed by
+    /// arbitrary bytes up to the maximum length, and `len <= data.len()` is enforced.
+    ///
+    /// The padding is applied in-circuit, and all the blocks that a message of the maximum
+    /// length would have are processed. The digest is the state after the last block of the
+    /// padded message.
+    pub fn digest_var_len(
+        data: &[UInt8<ConstraintF>],
+        len: &FpVar<ConstraintF>,
+    ) -> Result<DigestVar<ConstraintF>, SynthesisError> {
+        let max_len = data.len();
+        // A message of length l ends with 9 bytes of padding and length, in block (l + 8) / 64
+        let num_blocks = (max_len + 8) / 64 + 1;
+
+        // `is_end[i]` is whether `len == i`. Exactly one of them is true.
+        let is_end = (0..=max_len)
+            .map(|i| len.is_eq(&FpVar::Constant(ConstraintF::from(i as u64))))
+            .collect::<Result<Vec<_>, _>>()?;
+        Boolean::kary_or(&is_end)?.enforce_equal(&Boolean::TRUE)?;
+
+        // Keep the bytes of the message, then append 0x80 and zeros
+        let mut padded = Vec::with_capacity(num_blocks * 64);
+        let mut ended = Boolean::FALSE;
+        for (i, byte) in data.iter().enumerate() {
+            ended |= &is_end[i];
+            let byte = UInt8::conditionally_select(&ended, &UInt8::constant(0), byte)?;
+            padded.push(UInt8::conditionally_select(
+                &is_end[i],
+                &UInt8::constant(0x80),
+                &byte,
+            )?);
+        }
+        padded.push(UInt8::conditionally_select(
+            &is_end[max_len],
+            &UInt8::constant(0x80),
+            &UInt8::constant(0),
+        )?);
+        padded.resize(num_blocks * 64, UInt8::constant(0));
+
+        // The number of bits of the message, as 8 big-endian bytes
+        let (bitlen, _) = UInt64::from_fp(&(len * ConstraintF::from(8u64)))?;
+        let encoded_bitlen = bitlen.to_bytes_be()?;
+
+        let mut state: Vec<UInt32<ConstraintF>> = H.iter().cloned().map(UInt32::constant).collect();
+        let mut digest_state = state.clone();
+        for (b, block) in padded.chunks_mut(64).enumerate() {
+            // The block is the last one iff 64 * b - 8 <= len <= 64 * b + 55, and then the
+            // length is written at its end
+            let is_last =
+                Boolean::kary_or(&is_end[(64 * b).saturating_sub(8)..=max_len.min(64 * b + 55)])?;
+            for (byte, len_byte) in block[56..].iter_mut().zip(&encoded_bitlen) {
+                *byte = UInt8::conditionally_select(&is_last, len_byte, byte)?;
+            }
+
+            Self::update_state(&mut state, block)?;
+            for (d, s) in digest_state.iter_mut().zip(&state) {
+                *d = UInt32::conditionally_select(&is_last, s, d)?;
+            }
+        }
+
+        // Collect the state into big-endian bytes
+        let mut bytes = Vec::with_capacity(32);
+        for word in &digest_state {
+            bytes.extend(word.to_bytes_be()?);
+        }
+        Ok(DigestVar(bytes))
+    }
 }
 
 // Now implement the CRH traits for SHA256
@@ -528,6 +599,38 @@ mod test {
         }
     }
 
+    /// Tests hashing prefixes of a witness buffer, whose lengths are witnesses
+    #[test]
+    fn var_len() {
+        let mut rng = ark_std::test_rng();
+        let max_len = 130;
+        let mut data = vec![0u8; max_len];
+        rng.fill_bytes(&mut data);
+
+        for len in [0, 1, 55, 56, 63, 64, 65, 119, 120, 128, 130] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let data_var = to_byte_vars(ns!(cs, "data"), &data);
+            let len_var = FpVar::new_witness(ns!(cs, "len"), || Ok(Fr::from(len as u64))).unwrap();
+
+            let digest_var = Sha256Gadget::digest_var_len(&data_var, &len_var).unwrap();
+            assert_eq!(
+                digest_var.value().unwrap().to_vec(),
+                Sha256::digest(&data[..len]).to_vec(),
+                "error at length {}",
+                len
+            );
+            assert!(cs.is_satisfied().unwrap());
+        }
+
+        // The length cannot exceed the size of the buffer
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let data_var = to_byte_vars(ns!(cs, "data"), &data);
+        let len_var =
+            FpVar::new_witness(ns!(cs, "len"), || Ok(Fr::from(max_len as u64 + 1))).unwrap();
+        Sha256Gadget::digest_var_len(&data_var, &len_var).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
     /// Tests the EqGadget impl of DigestVar
     #[test]
     fn digest_eq() {
@@ -0,0 +1,719 @@
+// The SHA-512 family gadget, which mirrors `Sha256Gadget` with 64-bit words.
+// See https://en.wikipedia.org/wiki/SHA-2#Pseudocode
+
+use crate::crh::{
+    sha512::{Sha384, Sha512, Sha512_256},
+    CRHScheme, CRHSchemeGadget, TwoToOneCRHScheme, TwoToOneCRHSchemeGadget,
+};
+
+use core::{borrow::Borrow, fmt, marker::PhantomData};
+
+use ark_ff::PrimeField;
+use ark_r1cs_std::{
+    alloc::{AllocVar, AllocationMode},
+    boolean::Boolean,
+    convert::ToBytesGadget,
+    eq::EqGadget,
+    select::CondSelectGadget,
+    uint64::UInt64,
+    uint8::UInt8,
+    R1CSVar,
+};
+use ark_relations::r1cs::{ConstraintSystemRef, Namespace, SynthesisError};
+#[cfg(not(feature = "std"))]
+use ark_std::vec::Vec;
+
+pub use crate::crh::sha256::constraints::UnitVar;
+
+const STATE_LEN: usize = 8;
+
+type State = [u64; STATE_LEN];
+
+const K: [u64; 80] = [
+    0x428a2f98d728ae22,
+    0x7137449123ef65cd,
+    0xb5c0fbcfec4d3b2f,
+    0xe9b5dba58189dbbc,
+    0x3956c25bf348b538,
+    0x59f111f1b605d019,
+    0x923f82a4af194f9b,
+    0xab1c5ed5da6d8118,
+    0xd807aa98a3030242,
+    0x12835b0145706fbe,
+    0x243185be4ee4b28c,
+    0x550c7dc3d5ffb4e2,
+    0x72be5d74f27b896f,
+    0x80deb1fe3b1696b1,
+    0x9bdc06a725c71235,
+    0xc19bf174cf692694,
+    0xe49b69c19ef14ad2,
+    0xefbe4786384f25e3,
+    0x0fc19dc68b8cd5b5,
+    0x240ca1cc77ac9c65,
+    0x2de92c6f592b0275,
+    0x4a7484aa6ea6e483,
+    0x5cb0a9dcbd41fbd4,
+    0x76f988da831153b5,
+    0x983e5152ee66dfab,
+    0xa831c66d2db43210,
+    0xb00327c898fb213f,
+    0xbf597fc7beef0ee4,
+    0xc6e00bf33da88fc2,
+    0xd5a79147930aa725,
+    0x06ca6351e003826f,
+    0x142929670a0e6e70,
+    0x27b70a8546d22ffc,
+    0x2e1b21385c26c926,
+    0x4d2c6dfc5ac42aed,
+    0x53380d139d95b3df,
+    0x650a73548baf63de,
+    0x766a0abb3c77b2a8,
+    0x81c2c92e47edaee6,
+    0x92722c851482353b,
+    0xa2bfe8a14cf10364,
+    0xa81a664bbc423001,
+    0xc24b8b70d0f89791,
+    0xc76c51a30654be30,
+    0xd192e819d6ef5218,
+    0xd69906245565a910,
+    0xf40e35855771202a,
+    0x106aa07032bbd1b8,
+    0x19a4c116b8d2d0c8,
+    0x1e376c085141ab53,
+    0x2748774cdf8eeb99,
+    0x34b0bcb5e19b48a8,
+    0x391c0cb3c5c95a63,
+    0x4ed8aa4ae3418acb,
+    0x5b9cca4f7763e373,
+    0x682e6ff3d6b2b8a3,
+    0x748f82ee5defb2fc,
+    0x78a5636f43172f60,
+    0x84c87814a1f0ab72,
+    0x8cc702081a6439ec,
+    0x90befffa23631e28,
+    0xa4506cebde82bde9,
+    0xbef9a3f7b2c67915,
+    0xc67178f2e372532b,
+    0xca273eceea26619c,
+    0xd186b8c721c0c207,
+    0xeada7dd6cde0eb1e,
+    0xf57d4f7fee6ed178,
+    0x06f067aa72176fba,
+    0x0a637dc5a2c898a6,
+    0x113f9804bef90dae,
+    0x1b710b35131c471b,
+    0x28db77f523047d84,
+    0x32caab7b40c72493,
+    0x3c9ebe0a15c9bebc,
+    0x431d67c49c100d4c,
+    0x4cc5d4becb3e42b6,
+    0x597f299cfc657e2a,
+    0x5fcb6fab3ad6faec,
+    0x6c44198c4a475817,
+];
+
+/// A hash function of the SHA-512 family. They share the compression function and differ by
+/// their initial state and the length of their digest.
+pub trait Sha512Variant {
+    /// The initial state
+    const IV: State;
+    /// The length of the digest in bytes, which is a prefix of the final state
+    const OUTPUT_SIZE: usize;
+}
+
+impl Sha512Variant for Sha512 {
+    const IV: State = [
+        0x6a09e667f3bcc908,
+        0xbb67ae8584caa73b,
+        0x3c6ef372fe94f82b,
+        0xa54ff53a5f1d36f1,
+        0x510e527fade682d1,
+        0x9b05688c2b3e6c1f,
+        0x1f83d9abfb41bd6b,
+        0x5be0cd19137e2179,
+    ];
+    const OUTPUT_SIZE: usize = 64;
+}
+
+impl Sha512Variant for Sha384 {
+    const IV: State = [
+        0xcbbb9d5dc1059ed8,
+        0x629a292a367cd507,
+        0x9159015a3070dd17,
+        0x152fecd8f70e5939,
+        0x67332667ffc00b31,
+        0x8eb44a8768581511,
+        0xdb0c2e0d64f98fa7,
+        0x47b5481dbefa4fa4,
+    ];
+    const OUTPUT_SIZE: usize = 48;
+}
+
+impl Sha512Variant for Sha512_256 {
+    const IV: State = [
+        0x22312194fc2bf72c,
+        0x9f555fa3c84c64c2,
+        0x2393b86b6f53b151,
+        0x963877195940eabd,
+        0x96283ee2a88effe3,
+        0xbe5e1e2553863992,
+        0x2b0199fc2c85b8aa,
+        0x0eb72ddc81c52ca2,
+    ];
+    const OUTPUT_SIZE: usize = 32;
+}
+
+/// Computes SHA-512, or another hash function of the SHA-512 family selected by `H`.
+pub struct Sha512Gadget<ConstraintF: PrimeField, H: Sha512Variant = Sha512> {
+    state: Vec<UInt64<ConstraintF>>,
+    completed_data_blocks: u64,
+    pending: Vec<UInt8<ConstraintF>>,
+    num_pending: usize,
+    _variant: PhantomData<H>,
+}
+
+/// Computes SHA-384
+pub type Sha384Gadget<ConstraintF> = Sha512Gadget<ConstraintF, Sha384>;
+
+/// Computes SHA-512/256
+pub type Sha512_256Gadget<ConstraintF> = Sha512Gadget<ConstraintF, Sha512_256>;
+
+impl<ConstraintF: PrimeField, H: Sha512Variant> Clone for Sha512Gadget<ConstraintF, H> {
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+            completed_data_blocks: self.completed_data_blocks,
+            pending: self.pending.clone(),
+            num_pending: self.num_pending,
+            _variant: PhantomData,
+        }
+    }
+}
+
+impl<ConstraintF: PrimeField, H: Sha512Variant> Default for Sha512Gadget<ConstraintF, H> {
+    fn default() -> Self {
+        Self {
+            state: H::IV.iter().cloned().map(UInt64::constant).collect(),
+            completed_data_blocks: 0,
+            pending: vec![UInt8::constant(0); 128],
+            num_pending: 0,
+            _variant: PhantomData,
+        }
+    }
+}
+
+impl<ConstraintF: PrimeField, H: Sha512Variant> Sha512Gadget<ConstraintF, H> {
+    fn update_state(
+        state: &mut [UInt64<ConstraintF>],
+        data: &[UInt8<ConstraintF>],
+    ) -> Result<(), SynthesisError> {
+        assert_eq!(data.len(), 128);
+
+        let mut w = vec![UInt64::constant(0); 80];
+        for (word, chunk) in w.iter_mut().zip(data.chunks(8)) {
+            *word = UInt64::from_bytes_be(chunk)?;
+        }
+
+        for i in 16..80 {
+            let s0 = {
+                let x1 = w[i - 15].rotate_right(1);
+                let x2 = w[i - 15].rotate_right(8);
+                let x3 = &w[i - 15] >> 7u8;
+                x1 ^ &x2 ^ &x3
+            };
+            let s1 = {
+                let x1 = w[i - 2].rotate_right(19);
+                let x2 = w[i - 2].rotate_right(61);
+                let x3 = &w[i - 2] >> 6u8;
+                x1 ^ &x2 ^ &x3
+            };
+            w[i] = UInt64::wrapping_add_many(&[w[i - 16].clone(), s0, w[i - 7].clone(), s1])?;
+        }
+
+        let mut h = state.to_vec();
+        for i in 0..80 {
+            let ch = {
+                let x1 = &h[4] & &h[5];
+                let x2 = (!&h[4]) & &h[6];
+                x1 ^ &x2
+            };
+            let ma = {
+                let x1 = &h[0] & &h[1];
+                let x2 = &h[0] & &h[2];
+                let x3 = &h[1] & &h[2];
+                x1 ^ &x2 ^ &x3
+            };
+            let s0 = {
+                let x1 = h[0].rotate_right(28);
+                let x2 = h[0].rotate_right(34);
+                let x3 = h[0].rotate_right(39);
+                x1 ^ &x2 ^ &x3
+            };
+            let s1 = {
+                let x1 = h[4].rotate_right(14);
+                let x2 = h[4].rotate_right(18);
+                let x3 = h[4].rotate_right(41);
+                x1 ^ &x2 ^ &x3
+            };
+            let t0 = UInt64::wrapping_add_many(&[
+                h[7].clone(),
+                s1,
+                ch,
+                UInt64::constant(K[i]),
+                w[i].clone(),
+            ])?;
+            let t1 = s0.wrapping_add(&ma);
+
+            h[7] = h[6].clone();
+            h[6] = h[5].clone();
+            h[5] = h[4].clone();
+            h[4] = h[3].wrapping_add(&t0);
+            h[3] = h[2].clone();
+            h[2] = h[1].clone();
+            h[1] = h[0].clone();
+            h[0] = t0.wrapping_add(&t1);
+        }
+
+        for (s, hi) in state.iter_mut().zip(h.iter()) {
+            *s = s.wrapping_add(hi);
+        }
+
+        Ok(())
+    }
+
+    /// Consumes the given data and updates the internal state
+    pub fn update(&mut self, data: &[UInt8<ConstraintF>]) -> Result<(), SynthesisError> {
+        let mut offset = 0;
+        if self.num_pending > 0 && self.num_pending + data.len() >= 128 {
+            offset = 128 - self.num_pending;
+            // If the inputted data pushes the pending buffer over the chunk size, process it all
+            self.pending[self.num_pending..].clone_from_slice(&data[..offset]);
+            Self::update_state(&mut self.state, &self.pending)?;
+
+            self.completed_data_blocks += 1;
+            self.num_pending = 0;
+        }
+
+        for chunk in data[offset..].chunks(128) {
+            let chunk_size = chunk.len();
+
+            if chunk_size == 128 {
+                // If it's a full chunk, process it
+                Self::update_state(&mut self.state, chunk)?;
+                self.completed_data_blocks += 1;
+            } else {
+                // Otherwise, add the bytes to the `pending` buffer
+                self.pending[self.num_pending..self.num_pending + chunk_size]
+                    .clone_from_slice(chunk);
+                self.num_pending += chunk_size;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Outputs the final digest of all the inputted data
+    pub fn finalize(mut self) -> Result<DigestVar<ConstraintF, H>, SynthesisError> {
+        // Encode the number of processed bits as a u128, then serialize it to 16 big-endian
+        // bytes
+        let data_bitlen = self.completed_data_blocks as u128 * 1024 + self.num_pending as u128 * 8;
+        let encoded_bitlen: Vec<UInt8<ConstraintF>> = {
+            let bytes = data_bitlen.to_be_bytes();
+            bytes.iter().map(|&b| UInt8::constant(b)).collect()
+        };
+
+        // Padding starts with a 1 followed by some number of zeros (0x80 = 0b10000000)
+        let mut pending = vec![UInt8::constant(0); 144];
+        pending[0] = UInt8::constant(0x80);
+
+        // We'll either append to the 112+16 = 128 byte boundary or the 240+16 = 256 byte
+        // boundary, depending on whether we have at least 112 unprocessed bytes
+        let offset = if self.num_pending < 112 {
+            112 - self.num_pending
+        } else {
+            240 - self.num_pending
+        };
+
+        // Write the bitlen to the end of the padding. Then process all the padding
+        pending[offset..offset + 16].clone_from_slice(&encoded_bitlen);
+        self.update(&pending[..offset + 16])?;
+
+        // Collect the state into big-endian bytes, and truncate them to the digest length
+        let mut bytes = Vec::with_capacity(STATE_LEN * 8);
+        for word in &self.state {
+            bytes.extend(word.to_bytes_be()?);
+        }
+        bytes.truncate(H::OUTPUT_SIZE);
+        Ok(DigestVar::new(bytes))
+    }
+
+    /// Computes the digest of the given data. This is a shortcut for `default()` followed by
+    /// `update()` followed by `finalize()`.
+    pub fn digest(
+        data: &[UInt8<ConstraintF>],
+    ) -> Result<DigestVar<ConstraintF, H>, SynthesisError> {
+        let mut sha512_var = Self::default();
+        sha512_var.update(data)?;
+        sha512_var.finalize()
+    }
+}
+
+// Now implement the CRH traits for the SHA-512 family
+
+/// Contains a digest of the SHA-512 family, whose length is `H::OUTPUT_SIZE`
+pub struct DigestVar<ConstraintF: PrimeField, H: Sha512Variant = Sha512> {
+    pub bytes: Vec<UInt8<ConstraintF>>,
+    _variant: PhantomData<H>,
+}
+
+impl<ConstraintF: PrimeField, H: Sha512Variant> DigestVar<ConstraintF, H> {
+    /// Wraps the bytes of a digest. Panics if there are not `H::OUTPUT_SIZE` of them.
+    pub fn new(bytes: Vec<UInt8<ConstraintF>>) -> Self {
+        assert_eq!(bytes.len(), H::OUTPUT_SIZE);
+        Self {
+            bytes,
+            _variant: PhantomData,
+        }
+    }
+}
+
+impl<ConstraintF: PrimeField, H: Sha512Variant> Clone for DigestVar<ConstraintF, H> {
+    fn clone(&self) -> Self {
+        Self::new(self.bytes.clone())
+    }
+}
+
+impl<ConstraintF: PrimeField, H: Sha512Variant> fmt::Debug for DigestVar<ConstraintF, H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("DigestVar").field(&self.bytes).finish()
+    }
+}
+
+impl<ConstraintF: PrimeField, H: Sha512Variant> EqGadget<ConstraintF>
+    for DigestVar<ConstraintF, H>
+{
+    fn is_eq(&self, other: &Self) -> Result<Boolean<ConstraintF>, SynthesisError> {
+        self.bytes.is_eq(&other.bytes)
+    }
+}
+
+impl<ConstraintF: PrimeField, H: Sha512Variant> ToBytesGadget<ConstraintF>
+    for DigestVar<ConstraintF, H>
+{
+    fn to_bytes_le(&self) -> Result<Vec<UInt8<ConstraintF>>, SynthesisError> {
+        Ok(self.bytes.clone())
+    }
+}
+
+impl<ConstraintF: PrimeField, H: Sha512Variant> CondSelectGadget<ConstraintF>
+    for DigestVar<ConstraintF, H>
+{
+    fn conditionally_select(
+        cond: &Boolean<ConstraintF>,
+        true_value: &Self,
+        false_value: &Self,
+    ) -> Result<Self, SynthesisError> {
+        let bytes: Result<Vec<_>, _> = true_value
+            .bytes
+            .iter()
+            .zip(false_value.bytes.iter())
+            .map(|(t, f)| UInt8::conditionally_select(cond, t, f))
+            .collect();
+        bytes.map(Self::new)
+    }
+}
+
+impl<ConstraintF: PrimeField, H: Sha512Variant> AllocVar<Vec<u8>, ConstraintF>
+    for DigestVar<ConstraintF, H>
+{
+    // Allocates H::OUTPUT_SIZE UInt8s
+    fn new_variable<T: Borrow<Vec<u8>>>(
+        cs: impl Into<Namespace<ConstraintF>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        let cs = cs.into().cs();
+        let native_bytes = f();
+
+        if native_bytes
+            .as_ref()
+            .map(|b| b.borrow().len())
+            .unwrap_or(H::OUTPUT_SIZE)
+            != H::OUTPUT_SIZE
+        {
+            panic!(
+                "DigestVar must be allocated with precisely {} bytes",
+                H::OUTPUT_SIZE
+            );
+        }
+
+        // For each i, allocate the i-th byte
+        let var_bytes: Result<Vec<_>, _> = (0..H::OUTPUT_SIZE)
+            .map(|i| {
+                UInt8::new_variable(
+                    cs.clone(),
+                    || native_bytes.as_ref().map(|v| v.borrow()[i]).map_err(|e| *e),
+                    mode,
+                )
+            })
+            .collect();
+
+        var_bytes.map(Self::new)
+    }
+}
+
+impl<ConstraintF: PrimeField, H: Sha512Variant> R1CSVar<ConstraintF> for DigestVar<ConstraintF, H> {
+    type Value = Vec<u8>;
+
+    fn cs(&self) -> ConstraintSystemRef<ConstraintF> {
+        let mut result = ConstraintSystemRef::None;
+        for var in &self.bytes {
+            result = var.cs().or(result);
+        }
+        result
+    }
+
+    fn value(&self) -> Result<Self::Value, SynthesisError> {
+        self.bytes.iter().map(|var| var.value()).collect()
+    }
+}
+
+impl<ConstraintF, H> CRHSchemeGadget<H, ConstraintF> for Sha512Gadget<ConstraintF, H>
+where
+    ConstraintF: PrimeField,
+    H: Sha512Variant + CRHScheme<Input = [u8], Output = Vec<u8>, Parameters = ()>,
+{
+    type InputVar = [UInt8<ConstraintF>];
+    type OutputVar = DigestVar<ConstraintF, H>;
+    type ParametersVar = UnitVar<ConstraintF>;
+
+    #[tracing::instrument(target = "r1cs", skip(_parameters))]
+    fn evaluate(
+        _parameters: &Self::ParametersVar,
+        input: &Self::InputVar,
+    ) -> Result<Self::OutputVar, SynthesisError> {
+        Self::digest(input)
+    }
+}
+
+impl<ConstraintF, H> TwoToOneCRHSchemeGadget<H, ConstraintF> for Sha512Gadget<ConstraintF, H>
+where
+    ConstraintF: PrimeField,
+    H: Sha512Variant + TwoToOneCRHScheme<Input = [u8], Output = Vec<u8>, Parameters = ()>,
+{
+    type InputVar = [UInt8<ConstraintF>];
+    type OutputVar = DigestVar<ConstraintF, H>;
+    type ParametersVar = UnitVar<ConstraintF>;
+
+    #[tracing::instrument(target = "r1cs", skip(_parameters))]
+    fn evaluate(
+        _parameters: &Self::ParametersVar,
+        left_input: &Self::InputVar,
+        right_input: &Self::InputVar,
+    ) -> Result<Self::OutputVar, SynthesisError> {
+        let mut h = Self::default();
+        h.update(left_input)?;
+        h.update(right_input)?;
+        h.finalize()
+    }
+
+    #[tracing::instrument(target = "r1cs", skip(parameters))]
+    fn compress(
+        parameters: &Self::ParametersVar,
+        left_input: &Self::OutputVar,
+        right_input: &Self::OutputVar,
+    ) -> Result<Self::OutputVar, SynthesisError> {
+        // Convert output to bytes
+        let left_input = left_input.to_bytes_le()?;
+        let right_input = right_input.to_bytes_le()?;
+        <Self as TwoToOneCRHSchemeGadget<H, ConstraintF>>::evaluate(
+            parameters,
+            &left_input,
+            &right_input,
+        )
+    }
+}
+
+// All the tests below test against the RustCrypto sha2 implementation
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::crh::{CRHScheme, TwoToOneCRHScheme};
+    use sha2::digest::Digest;
+
+    use ark_bls12_377::Fr;
+    use ark_relations::{ns, r1cs::ConstraintSystem};
+    use ark_std::rand::RngCore;
+
+    const TEST_LENGTHS: &[usize] = &[
+        0, 1, 2, 8, 20, 40, 111, 112, 113, 127, 128, 129, 200, 255, 256, 257,
+    ];
+
+    /// Witnesses bytes
+    fn to_byte_vars(cs: impl Into<Namespace<Fr>>, data: &[u8]) -> Vec<UInt8<Fr>> {
+        let cs = cs.into().cs();
+        UInt8::new_witness_vec(cs, data).unwrap()
+    }
+
+    /// Tests the gadget of `H` on random strings of varied lengths
+    fn varied_lengths<H: Sha512Variant + Digest>() {
+        let mut rng = ark_std::test_rng();
+        let cs = ConstraintSystem::<Fr>::new_ref();
+
+        for &len in TEST_LENGTHS {
+            let mut hash = H::new();
+            let mut hash_var = Sha512Gadget::<Fr, H>::default();
+
+            // Make a random string of the given length
+            let mut input_str = vec![0u8; len];
+            rng.fill_bytes(&mut input_str);
+
+            // Compute the hashes and assert consistency
+            hash_var
+                .update(&to_byte_vars(ns!(cs, "input"), &input_str))
+                .unwrap();
+            Digest::update(&mut hash, input_str);
+            assert_eq!(
+                hash_var.finalize().unwrap().value().unwrap(),
+                hash.finalize().to_vec(),
+                "error at length {}",
+                len
+            );
+        }
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn varied_lengths_sha512() {
+        varied_lengths::<Sha512>();
+    }
+
+    #[test]
+    fn varied_lengths_sha384() {
+        varied_lengths::<Sha384>();
+    }
+
+    #[test]
+    fn varied_lengths_sha512_256() {
+        varied_lengths::<Sha512_256>();
+    }
+
+    /// Calls `update()` many times
+    #[test]
+    fn many_updates() {
+        let mut rng = ark_std::test_rng();
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let mut sha512 = Sha512::default();
+        let mut sha512_var = Sha512Gadget::<Fr>::default();
+
+        // Append the same 15-byte string 20 times
+        for _ in 0..20 {
+            let mut input_str = vec![0u8; 15];
+            rng.fill_bytes(&mut input_str);
+
+            sha512_var
+                .update(&to_byte_vars(ns!(cs, "input"), &input_str))
+                .unwrap();
+            sha512.update(input_str);
+        }
+
+        // Make sure the result is consistent
+        assert_eq!(
+            sha512_var.finalize().unwrap().value().unwrap(),
+            sha512.finalize().to_vec()
+        );
+    }
+
+    /// Tests the CRHCheme trait
+    #[test]
+    fn crh() {
+        let mut rng = ark_std::test_rng();
+        let cs = ConstraintSystem::<Fr>::new_ref();
+
+        // CRH parameters are nothing
+        let unit = ();
+        let unit_var = UnitVar::default();
+
+        for &len in TEST_LENGTHS {
+            // Make a random string of the given length
+            let mut input_str = vec![0u8; len];
+            rng.fill_bytes(&mut input_str);
+
+            // Compute the hashes and assert consistency
+            let computed_output = <Sha384Gadget<Fr> as CRHSchemeGadget<Sha384, Fr>>::evaluate(
+                &unit_var,
+                &to_byte_vars(ns!(cs, "input"), &input_str),
+            )
+            .unwrap();
+            let expected_output = <Sha384 as CRHScheme>::evaluate(&unit, input_str).unwrap();
+            assert_eq!(
+                computed_output.value().unwrap(),
+                expected_output,
+                "CRH error at length {}",
+                len
+            )
+        }
+    }
+
+    /// Tests the TwoToOneCRHScheme trait
+    #[test]
+    fn to_to_one_crh() {
+        let mut rng = ark_std::test_rng();
+        let cs = ConstraintSystem::<Fr>::new_ref();
+
+        // CRH parameters are nothing
+        let unit = ();
+        let unit_var = UnitVar::default();
+
+        for &len in TEST_LENGTHS {
+            // Make random strings of the given length
+            let mut left_input = vec![0u8; len];
+            let mut right_input = vec![0u8; len];
+            rng.fill_bytes(&mut left_input);
+            rng.fill_bytes(&mut right_input);
+
+            // Compute the hashes and assert consistency
+            let computed_output =
+                <Sha512_256Gadget<Fr> as TwoToOneCRHSchemeGadget<Sha512_256, Fr>>::evaluate(
+                    &unit_var,
+                    &to_byte_vars(ns!(cs, "left input"), &left_input),
+                    &to_byte_vars(ns!(cs, "right input"), &right_input),
+                )
+                .unwrap();
+            let expected_output =
+                <Sha512_256 as TwoToOneCRHScheme>::evaluate(&unit, left_input, right_input)
+                    .unwrap();
+            assert_eq!(
+                computed_output.value().unwrap(),
+                expected_output,
+                "TwoToOneCRH error at length {}",
+                len
+            )
+        }
+    }
+
+    /// Tests the EqGadget impl of DigestVar
+    #[test]
+    fn digest_eq() {
+        let mut rng = ark_std::test_rng();
+        let cs = ConstraintSystem::<Fr>::new_ref();
+
+        // Make two distinct digests
+        let mut digest1 = [0u8; 64];
+        let mut digest2 = [0u8; 64];
+        rng.fill_bytes(&mut digest1);
+        rng.fill_bytes(&mut digest2);
+
+        // Witness them
+        let digest1_var =
+            DigestVar::<Fr>::new_witness(cs.clone(), || Ok(digest1.to_vec())).unwrap();
+        let digest2_var =
+            DigestVar::<Fr>::new_witness(cs.clone(), || Ok(digest2.to_vec())).unwrap();
+
+        // Assert that the distinct digests are distinct
+        assert!(!digest1_var.is_eq(&digest2_var).unwrap().value().unwrap());
+
+        // Now assert that a digest equals itself
+        assert!(digest1_var.is_eq(&digest1_var).unwrap().value().unwrap());
+    }
+}
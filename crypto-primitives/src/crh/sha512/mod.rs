@@ -0,0 +1,83 @@
+use crate::crh::{CRHScheme, TwoToOneCRHScheme};
+use crate::Error;
+
+use ark_std::rand::Rng;
+#[cfg(not(feature = "std"))]
+use ark_std::vec::Vec;
+
+// Re-export the RustCrypto SHA-512 family types
+pub use sha2::{Sha384, Sha512, Sha512_256};
+
+#[cfg(feature = "r1cs")]
+pub mod constraints;
+
+// Implement the CRH traits for the SHA-512 family. They differ from SHA-256 only by their
+// digest type, so the implementations are shared through a macro.
+
+use core::borrow::Borrow;
+use sha2::digest::Digest;
+
+macro_rules! impl_crh {
+    ($hash:ty, $name:literal) => {
+        impl CRHScheme for $hash {
+            type Input = [u8];
+            // This has a fixed length. It has to be a Vec to impl CanonicalSerialize
+            type Output = Vec<u8>;
+            // There are no parameters for the SHA-512 family
+            type Parameters = ();
+
+            fn setup<R: Rng>(_rng: &mut R) -> Result<Self::Parameters, Error> {
+                Ok(())
+            }
+
+            #[doc = concat!("Evaluates ", $name, "(input)")]
+            fn evaluate<T: Borrow<Self::Input>>(
+                _parameters: &Self::Parameters,
+                input: T,
+            ) -> Result<Self::Output, Error> {
+                Ok(<$hash>::digest(input.borrow()).to_vec())
+            }
+        }
+
+        impl TwoToOneCRHScheme for $hash {
+            type Input = [u8];
+            // This has a fixed length. It has to be a Vec to impl CanonicalSerialize
+            type Output = Vec<u8>;
+            // There are no parameters for the SHA-512 family
+            type Parameters = ();
+
+            fn setup<R: Rng>(_rng: &mut R) -> Result<Self::Parameters, Error> {
+                Ok(())
+            }
+
+            #[doc = concat!("Evaluates ", $name, "(left_input || right_input)")]
+            fn evaluate<T: Borrow<Self::Input>>(
+                _parameters: &Self::Parameters,
+                left_input: T,
+                right_input: T,
+            ) -> Result<Self::Output, Error> {
+                let mut h = <$hash>::default();
+                h.update(left_input.borrow());
+                h.update(right_input.borrow());
+                Ok(h.finalize().to_vec())
+            }
+
+            #[doc = concat!("Evaluates ", $name, "(left_input || right_input)")]
+            fn compress<T: Borrow<Self::Output>>(
+                parameters: &Self::Parameters,
+                left_input: T,
+                right_input: T,
+            ) -> Result<Self::Output, Error> {
+                <Self as TwoToOneCRHScheme>::evaluate(
+                    parameters,
+                    left_input.borrow().as_slice(),
+                    right_input.borrow().as_slice(),
+                )
+            }
+        }
+    };
+}
+
+impl_crh!(Sha512, "SHA-512");
+impl_crh!(Sha384, "SHA-384");
+impl_crh!(Sha512_256, "SHA-512/256");
@@ -0,0 +1,222 @@
+use crate::crh::sinsemilla::{Parameters, TwoToOneCRH, CRH, K};
+use crate::crh::{CRHSchemeGadget, TwoToOneCRHSchemeGadget};
+use ark_ec::short_weierstrass::SWCurveConfig;
+use ark_ec::{AffineRepr, CurveConfig};
+use ark_ff::PrimeField;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_r1cs_std::groups::curves::short_weierstrass::ProjectiveVar;
+use ark_r1cs_std::prelude::*;
+use ark_relations::r1cs::{Namespace, SynthesisError};
+#[cfg(not(feature = "std"))]
+use ark_std::vec::Vec;
+use core::{borrow::Borrow, marker::PhantomData};
+
+type ConstraintF<P> = <P as CurveConfig>::BaseField;
+
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""))]
+pub struct CRHParametersVar<P: SWCurveConfig>
+where
+    P::BaseField: PrimeField,
+{
+    params: Parameters<P>,
+}
+
+pub struct CRHGadget<P: SWCurveConfig>
+where
+    P::BaseField: PrimeField,
+{
+    #[doc(hidden)]
+    _curve: PhantomData<P>,
+}
+
+impl<P: SWCurveConfig> CRHGadget<P>
+where
+    P::BaseField: PrimeField,
+{
+    /// Computes `SinsemillaHashToPoint` of `bits`, as [`CRH::hash_to_point`].
+    ///
+    /// The generator of each chunk is selected among the `2^K` constant generators, and the
+    /// additions are complete, so they agree with the incomplete additions of the native hash
+    /// whenever these succeed.
+    ///
+    /// The circuit therefore accepts a superset of the native hash: for the inputs on which
+    /// the native hash returns [`Error::IncompleteAddition`](crate::Error::IncompleteAddition),
+    /// it still computes the sum of the points instead of being unsatisfiable. Finding such an
+    /// input is as hard as computing a discrete logarithm relation between the generators.
+    #[tracing::instrument(target = "r1cs", skip(parameters, bits))]
+    pub fn hash_to_point(
+        parameters: &CRHParametersVar<P>,
+        bits: &[Boolean<ConstraintF<P>>],
+    ) -> Result<ProjectiveVar<P, FpVar<ConstraintF<P>>>, SynthesisError> {
+        let (s_x, s_y): (Vec<_>, Vec<_>) = parameters
+            .params
+            .s
+            .iter()
+            .map(|s| (FpVar::Constant(s.x), FpVar::Constant(s.y)))
+            .unzip();
+
+        let mut acc = ProjectiveVar::constant(parameters.params.q.into_group());
+        for chunk in bits.chunks(K) {
+            // the big-endian bits of the chunk, padded with zeros
+            let mut position = chunk.to_vec();
+            position.resize(K, Boolean::FALSE);
+            position.reverse();
+
+            let s = ProjectiveVar::new(
+                FpVar::conditionally_select_power_of_two_vector(&position, &s_x)?,
+                FpVar::conditionally_select_power_of_two_vector(&position, &s_y)?,
+                FpVar::one(),
+            );
+            acc = (&acc + &s) + &acc;
+        }
+        Ok(acc)
+    }
+}
+
+impl<P: SWCurveConfig> CRHSchemeGadget<CRH<P>, ConstraintF<P>> for CRHGadget<P>
+where
+    P::BaseField: PrimeField,
+{
+    type InputVar = [UInt8<ConstraintF<P>>];
+    type OutputVar = FpVar<ConstraintF<P>>;
+    type ParametersVar = CRHParametersVar<P>;
+
+    #[tracing::instrument(target = "r1cs", skip(parameters, input))]
+    fn evaluate(
+        parameters: &Self::ParametersVar,
+        input: &Self::InputVar,
+    ) -> Result<Self::OutputVar, SynthesisError> {
+        let mut bits = Vec::with_capacity(input.len() * 8);
+        for byte in input {
+            bits.extend(byte.to_bits_le()?);
+        }
+        Ok(Self::hash_to_point(parameters, &bits)?.to_affine()?.x)
+    }
+}
+
+pub struct TwoToOneCRHGadget<P: SWCurveConfig>
+where
+    P::BaseField: PrimeField,
+{
+    #[doc(hidden)]
+    _curve: PhantomData<P>,
+}
+
+impl<P: SWCurveConfig> TwoToOneCRHSchemeGadget<TwoToOneCRH<P>, ConstraintF<P>>
+    for TwoToOneCRHGadget<P>
+where
+    P::BaseField: PrimeField,
+{
+    type InputVar = [UInt8<ConstraintF<P>>];
+    type OutputVar = FpVar<ConstraintF<P>>;
+    type ParametersVar = CRHParametersVar<P>;
+
+    #[tracing::instrument(target = "r1cs", skip(parameters))]
+    fn evaluate(
+        parameters: &Self::ParametersVar,
+        left_input: &Self::InputVar,
+        right_input: &Self::InputVar,
+    ) -> Result<Self::OutputVar, SynthesisError> {
+        // assume equality of left and right length
+        assert_eq!(left_input.len(), right_input.len());
+        let chained_input: Vec<_> = left_input.iter().chain(right_input).cloned().collect();
+        CRHGadget::<P>::evaluate(parameters, &chained_input)
+    }
+
+    #[tracing::instrument(target = "r1cs", skip(parameters))]
+    fn compress(
+        parameters: &Self::ParametersVar,
+        left_input: &Self::OutputVar,
+        right_input: &Self::OutputVar,
+    ) -> Result<Self::OutputVar, SynthesisError> {
+        let mut bits = left_input.to_bits_le()?;
+        bits.extend(right_input.to_bits_le()?);
+        Ok(CRHGadget::<P>::hash_to_point(parameters, &bits)?
+            .to_affine()?
+            .x)
+    }
+}
+
+impl<P: SWCurveConfig> AllocVar<Parameters<P>, ConstraintF<P>> for CRHParametersVar<P>
+where
+    P::BaseField: PrimeField,
+{
+    #[tracing::instrument(target = "r1cs", skip(_cs, f))]
+    fn new_variable<T: Borrow<Parameters<P>>>(
+        _cs: impl Into<Namespace<ConstraintF<P>>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        _mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        let params = f()?.borrow().clone();
+        Ok(CRHParametersVar { params })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::crh::{
+        sinsemilla, CRHScheme, CRHSchemeGadget, TwoToOneCRHScheme, TwoToOneCRHSchemeGadget,
+    };
+    use ark_bn254::{g1::Config, Fq};
+    use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::rand::RngCore;
+    use ark_std::{test_rng, UniformRand};
+
+    type TestCRH = sinsemilla::CRH<Config>;
+    type TestCRHGadget = sinsemilla::constraints::CRHGadget<Config>;
+    type TestTwoToOneCRH = sinsemilla::TwoToOneCRH<Config>;
+    type TestTwoToOneCRHGadget = sinsemilla::constraints::TwoToOneCRHGadget<Config>;
+
+    #[test]
+    fn test_native_equality() {
+        let rng = &mut test_rng();
+        let cs = ConstraintSystem::<Fq>::new_ref();
+
+        // 5 bytes are 4 chunks, the last of which is padded
+        let mut input = vec![0u8; 5];
+        rng.fill_bytes(&mut input);
+        let input_var = UInt8::new_witness_vec(cs.clone(), &input).unwrap();
+
+        let parameters = TestCRH::setup(rng).unwrap();
+        let primitive_result = TestCRH::evaluate(&parameters, input.as_slice()).unwrap();
+
+        let parameters_var = sinsemilla::constraints::CRHParametersVar::new_constant(
+            ark_relations::ns!(cs, "CRH Parameters"),
+            &parameters,
+        )
+        .unwrap();
+        let result_var = TestCRHGadget::evaluate(&parameters_var, &input_var).unwrap();
+
+        assert_eq!(primitive_result, result_var.value().unwrap());
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_two_to_one_equality() {
+        let rng = &mut test_rng();
+        let cs = ConstraintSystem::<Fq>::new_ref();
+
+        let left_input = Fq::rand(rng);
+        let right_input = Fq::rand(rng);
+        let left_input_var = FpVar::new_witness(cs.clone(), || Ok(left_input)).unwrap();
+        let right_input_var = FpVar::new_witness(cs.clone(), || Ok(right_input)).unwrap();
+
+        let parameters = TestTwoToOneCRH::setup(rng).unwrap();
+        let primitive_result =
+            TestTwoToOneCRH::compress(&parameters, left_input, right_input).unwrap();
+
+        let parameters_var = sinsemilla::constraints::CRHParametersVar::new_constant(
+            ark_relations::ns!(cs, "CRH Parameters"),
+            &parameters,
+        )
+        .unwrap();
+        let result_var =
+            TestTwoToOneCRHGadget::compress(&parameters_var, &left_input_var, &right_input_var)
+                .unwrap();
+
+        assert_eq!(primitive_result, result_var.value().unwrap());
+        assert!(cs.is_satisfied().unwrap());
+    }
+}
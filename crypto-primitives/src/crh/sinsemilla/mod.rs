@@ -0,0 +1,298 @@
+//! The [Sinsemilla] hash is a Pedersen-like hash of Orchard, which adds one of `2^K` generators
+//! per `K`-bit chunk of the input with incomplete additions. See [Section 5.4.1.9 of the Zcash
+//! protocol specification](https://zips.z.cash/protocol/protocol.pdf#concretesinsemillahash)
+//! for a formal description of this hash function, specialized for the Pallas curve.
+//! The implementation in this repository is generic across choice of short Weierstrass (SW)
+//! curves, and derives its generators with a try-and-increment hash to the curve, so it is not
+//! compatible with Orchard.
+//!
+//! [Sinsemilla]: https://zcash.github.io/halo2/design/gadgets/sinsemilla.html
+
+use crate::crh::{pedersen, CRHScheme, TwoToOneCRHScheme};
+use crate::Error;
+use ark_ec::short_weierstrass::{Affine, SWCurveConfig};
+use ark_ec::{AffineRepr, CurveConfig};
+use ark_ff::{BigInteger, Field, PrimeField};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::borrow::Borrow;
+use ark_std::marker::PhantomData;
+use ark_std::rand::Rng;
+#[cfg(not(feature = "std"))]
+use ark_std::vec::Vec;
+use blake2::{Blake2s256, Digest};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+#[cfg(feature = "r1cs")]
+pub mod constraints;
+
+/// The number of bits of each chunk of the input.
+pub const K: usize = 10;
+
+/// The domain of the generator `Q(D)` of the personalization `D`.
+const Q_DOMAIN: &[u8] = b"SinsemillaQ";
+/// The domain of the generators `S(j)` of the chunks.
+const S_DOMAIN: &[u8] = b"SinsemillaS";
+/// The personalization used by `setup`.
+const DEFAULT_PERSONALIZATION: &[u8] = b"ark-crypto-primitives:Sinsemilla";
+
+/// The generators of the Sinsemilla hash: the initial point `Q(D)` of a personalization `D`,
+/// and the `2^K` points `S(j)` added for the chunks of value `j`.
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(Clone(bound = ""), Debug(bound = ""), PartialEq(bound = ""))]
+pub struct Parameters<P: SWCurveConfig> {
+    pub q: Affine<P>,
+    pub s: Vec<Affine<P>>,
+}
+
+impl<P: SWCurveConfig> Parameters<P>
+where
+    P::BaseField: PrimeField,
+{
+    /// Derives the generators of the personalization `D`, which separates the domains of
+    /// different uses of the hash function.
+    ///
+    /// `Q(D)` is the hash to the curve of `D`, and `S(j)` is the hash to the curve of the 32-bit
+    /// little-endian encoding of `j`, so that only `Q(D)` depends on the personalization.
+    pub fn new(personalization: &[u8]) -> Self {
+        let q = hash_to_curve(Q_DOMAIN, personalization);
+        let s = cfg_into_iter!(0..1u32 << K)
+            .map(|j| hash_to_curve(S_DOMAIN, &j.to_le_bytes()))
+            .collect();
+        Self { q, s }
+    }
+}
+
+/// Hashes `message` to a point of the prime-order subgroup of a SW curve, whose x-coordinate is
+/// derived from the BLAKE2s hashes of `domain`, `message` and a counter, which is incremented
+/// until a point is found.
+pub fn hash_to_curve<P: SWCurveConfig>(domain: &[u8], message: &[u8]) -> Affine<P>
+where
+    P::BaseField: PrimeField,
+{
+    // enough 256-bit hashes for at least 128 bits more than the modulus, so that the
+    // x-coordinate is statistically close to uniform after the reduction
+    let num_hashes = P::BaseField::MODULUS_BIT_SIZE as usize / 256 + 2;
+    for counter in 0u32.. {
+        let mut bytes = Vec::with_capacity(32 * num_hashes);
+        for i in 0..num_hashes as u8 {
+            let mut h = Blake2s256::new();
+            h.update((domain.len() as u64).to_le_bytes());
+            h.update(domain);
+            h.update(message);
+            h.update(counter.to_le_bytes());
+            h.update([i]);
+            bytes.extend_from_slice(&h.finalize());
+        }
+        let x = P::BaseField::from_le_bytes_mod_order(&bytes);
+        if let Some(point) = Affine::<P>::get_point_from_x_unchecked(x, false) {
+            let point = point.clear_cofactor();
+            if !point.is_zero() {
+                return point;
+            }
+        }
+    }
+    unreachable!("no point found by hashing to the curve")
+}
+
+/// Adds two points with the incomplete addition formula, which fails when they have the same
+/// x-coordinate, or when one of them is the point at infinity.
+fn incomplete_add<P: SWCurveConfig>(a: &Affine<P>, b: &Affine<P>) -> Result<Affine<P>, Error> {
+    if a.is_zero() || b.is_zero() || a.x == b.x {
+        return Err(Error::IncompleteAddition);
+    }
+    let lambda = (b.y - a.y) / (b.x - a.x);
+    let x = lambda.square() - a.x - b.x;
+    let y = lambda * (a.x - x) - a.y;
+    Ok(Affine::new_unchecked(x, y))
+}
+
+pub struct CRH<P: SWCurveConfig> {
+    curve: PhantomData<P>,
+}
+
+impl<P: SWCurveConfig> CRH<P>
+where
+    P::BaseField: PrimeField,
+{
+    /// Computes `SinsemillaHashToPoint`: the input is split in chunks of `K` bits, the last of
+    /// which is padded with zeros, and each chunk `m` updates the accumulator `acc`, which starts
+    /// at `Q(D)`, to `(acc + S(m)) + acc`.
+    ///
+    /// Returns [`Error::IncompleteAddition`] if an addition hits an exceptional case, which only
+    /// happens with negligible probability.
+    pub fn hash_to_point(parameters: &Parameters<P>, bits: &[bool]) -> Result<Affine<P>, Error> {
+        let mut acc = parameters.q;
+        for chunk in bits.chunks(K) {
+            let index = chunk
+                .iter()
+                .rev()
+                .fold(0, |index, bit| (index << 1) | *bit as usize);
+            acc = incomplete_add(&incomplete_add(&acc, &parameters.s[index])?, &acc)?;
+        }
+        Ok(acc)
+    }
+}
+
+impl<P: SWCurveConfig> CRHScheme for CRH<P>
+where
+    P::BaseField: PrimeField,
+{
+    type Input = [u8];
+    type Output = <P as CurveConfig>::BaseField;
+    type Parameters = Parameters<P>;
+
+    fn setup<R: Rng>(_rng: &mut R) -> Result<Self::Parameters, Error> {
+        Ok(Parameters::new(DEFAULT_PERSONALIZATION))
+    }
+
+    /// Computes the x-coordinate of the hash of the little-endian bits of the input.
+    ///
+    /// As the input is padded with zero bits to a multiple of `K`, inputs of different lengths
+    /// may collide, so the input length should be fixed.
+    fn evaluate<T: Borrow<Self::Input>>(
+        parameters: &Self::Parameters,
+        input: T,
+    ) -> Result<Self::Output, Error> {
+        let bits = pedersen::bytes_to_bits(input.borrow());
+        Ok(Self::hash_to_point(parameters, &bits)?.x)
+    }
+}
+
+pub struct TwoToOneCRH<P: SWCurveConfig> {
+    curve: PhantomData<P>,
+}
+
+impl<P: SWCurveConfig> TwoToOneCRHScheme for TwoToOneCRH<P>
+where
+    P::BaseField: PrimeField,
+{
+    type Input = [u8];
+    type Output = <P as CurveConfig>::BaseField;
+    type Parameters = Parameters<P>;
+
+    fn setup<R: Rng>(r: &mut R) -> Result<Self::Parameters, Error> {
+        CRH::<P>::setup(r)
+    }
+
+    /// Hashes the concatenation of `left_input` and `right_input` with [`CRH`].
+    ///
+    /// `left_input` and `right_input` must be of equal length.
+    fn evaluate<T: Borrow<Self::Input>>(
+        parameters: &Self::Parameters,
+        left_input: T,
+        right_input: T,
+    ) -> Result<Self::Output, Error> {
+        let (left_input, right_input) = (left_input.borrow(), right_input.borrow());
+        assert_eq!(
+            left_input.len(),
+            right_input.len(),
+            "left and right input should be of equal length"
+        );
+        CRH::<P>::evaluate(parameters, [left_input, right_input].concat())
+    }
+
+    /// Hashes the little-endian bits of `left_input` followed by those of `right_input`, with
+    /// `MODULUS_BIT_SIZE` bits each.
+    fn compress<T: Borrow<Self::Output>>(
+        parameters: &Self::Parameters,
+        left_input: T,
+        right_input: T,
+    ) -> Result<Self::Output, Error> {
+        let num_bits = P::BaseField::MODULUS_BIT_SIZE as usize;
+        let mut bits = left_input.borrow().into_bigint().to_bits_le();
+        bits.truncate(num_bits);
+        bits.extend(
+            right_input
+                .borrow()
+                .into_bigint()
+                .to_bits_le()
+                .into_iter()
+                .take(num_bits),
+        );
+        Ok(CRH::<P>::hash_to_point(parameters, &bits)?.x)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::crh::sinsemilla::{hash_to_curve, Parameters, TwoToOneCRH, CRH, K};
+    use crate::crh::{CRHScheme, TwoToOneCRHScheme};
+    use crate::Error;
+    use ark_bn254::{g1::Config, Fq};
+    use ark_ec::{short_weierstrass::Affine, AffineRepr};
+    use ark_std::{test_rng, UniformRand};
+
+    #[test]
+    fn generators_are_deterministic() {
+        let parameters = Parameters::<Config>::new(b"test");
+        assert_eq!(parameters.s.len(), 1 << K);
+        assert!(parameters.s.iter().all(|s| s.is_on_curve()));
+        assert_eq!(parameters, Parameters::<Config>::new(b"test"));
+
+        let other = Parameters::<Config>::new(b"other");
+        assert_ne!(parameters.q, other.q);
+        assert_eq!(parameters.s, other.s);
+        assert_ne!(
+            hash_to_curve::<Config>(b"test", b""),
+            hash_to_curve::<Config>(b"tes", b"t")
+        );
+    }
+
+    #[test]
+    fn hash_follows_the_specification() {
+        let mut rng = test_rng();
+        let parameters = CRH::<Config>::setup(&mut rng).unwrap();
+
+        // 2 bytes are 2 chunks, the last of which is padded with zeros
+        let input = [0b1010_0001u8, 0b0000_0111];
+        let (q, s) = (parameters.q, &parameters.s);
+        let acc: Affine<Config> = ((q + s[0b11_1010_0001]) + q).into();
+        let expected: Affine<Config> = ((acc + s[0b00_0001]) + acc).into();
+        assert_eq!(
+            CRH::<Config>::evaluate(&parameters, input.as_slice()).unwrap(),
+            expected.x
+        );
+
+        let empty = CRH::<Config>::evaluate(&parameters, [0u8; 0].as_slice()).unwrap();
+        assert_eq!(empty, parameters.q.x);
+    }
+
+    #[test]
+    fn exceptional_cases_are_rejected() {
+        let mut parameters = Parameters::<Config>::new(b"test");
+        // acc + S(0) = acc + acc is an exceptional case
+        parameters.s[0] = parameters.q;
+        assert!(matches!(
+            CRH::<Config>::hash_to_point(&parameters, &[false; K]),
+            Err(Error::IncompleteAddition)
+        ));
+        parameters.s[0] = Affine::zero();
+        assert!(matches!(
+            CRH::<Config>::hash_to_point(&parameters, &[false; K]),
+            Err(Error::IncompleteAddition)
+        ));
+    }
+
+    #[test]
+    fn two_to_one() {
+        let mut rng = test_rng();
+        let parameters = TwoToOneCRH::<Config>::setup(&mut rng).unwrap();
+        let left = Fq::rand(&mut rng);
+        let right = Fq::rand(&mut rng);
+
+        let compressed = TwoToOneCRH::<Config>::compress(&parameters, left, right).unwrap();
+        assert_ne!(
+            compressed,
+            TwoToOneCRH::<Config>::compress(&parameters, right, left).unwrap()
+        );
+
+        let evaluated =
+            TwoToOneCRH::<Config>::evaluate(&parameters, [1u8, 2].as_slice(), [3u8, 4].as_slice())
+                .unwrap();
+        assert_eq!(
+            evaluated,
+            CRH::<Config>::evaluate(&parameters, [1u8, 2, 3, 4].as_slice()).unwrap()
+        );
+    }
+}
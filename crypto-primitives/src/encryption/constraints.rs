@@ -1,4 +1,4 @@
-use crate::encryption::AsymmetricEncryptionScheme;
+use crate::encryption::{AsymmetricEncryptionScheme, SymmetricEncryptionScheme};
 
 use ark_r1cs_std::prelude::*;
 use ark_relations::r1cs::SynthesisError;
@@ -24,3 +24,22 @@ pub trait AsymmetricEncryptionGadget<C: AsymmetricEncryptionScheme, ConstraintF:
         public_key: &Self::PublicKeyVar,
     ) -> Result<Self::OutputVar, SynthesisError>;
 }
+
+pub trait SymmetricEncryptionGadget<C: SymmetricEncryptionScheme, ConstraintF: Field> {
+    type OutputVar: AllocVar<C::Ciphertext, ConstraintF>
+        + EqGadget<ConstraintF>
+        + Clone
+        + Sized
+        + Debug;
+    type ParametersVar: AllocVar<C::Parameters, ConstraintF> + Clone;
+    type PlaintextVar: AllocVar<C::Plaintext, ConstraintF> + Clone;
+    type KeyVar: AllocVar<C::Key, ConstraintF> + Clone;
+    type NonceVar: AllocVar<C::Nonce, ConstraintF> + Clone;
+
+    fn encrypt(
+        parameters: &Self::ParametersVar,
+        message: &Self::PlaintextVar,
+        nonce: &Self::NonceVar,
+        key: &Self::KeyVar,
+    ) -> Result<Self::OutputVar, SynthesisError>;
+}
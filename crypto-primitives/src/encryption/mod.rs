@@ -4,6 +4,7 @@ pub mod constraints;
 pub use constraints::*;
 
 pub mod elgamal;
+pub mod poseidon;
 
 use crate::Error;
 use ark_std::rand::Rng;
@@ -36,3 +37,29 @@ pub trait AsymmetricEncryptionScheme {
         ciphertext: &Self::Ciphertext,
     ) -> Result<Self::Plaintext, Error>;
 }
+
+pub trait SymmetricEncryptionScheme {
+    type Parameters;
+    type Key;
+    type Nonce;
+    type Plaintext;
+    type Ciphertext;
+
+    fn setup<R: Rng>(rng: &mut R) -> Result<Self::Parameters, Error>;
+
+    fn keygen<R: Rng>(pp: &Self::Parameters, rng: &mut R) -> Result<Self::Key, Error>;
+
+    fn encrypt(
+        pp: &Self::Parameters,
+        key: &Self::Key,
+        message: &Self::Plaintext,
+        nonce: &Self::Nonce,
+    ) -> Result<Self::Ciphertext, Error>;
+
+    fn decrypt(
+        pp: &Self::Parameters,
+        key: &Self::Key,
+        ciphertext: &Self::Ciphertext,
+        nonce: &Self::Nonce,
+    ) -> Result<Self::Plaintext, Error>;
+}
@@ -0,0 +1,300 @@
+use crate::encryption::poseidon::{Ciphertext, Plaintext, PoseidonAE};
+use crate::encryption::{SymmetricEncryptionGadget, SymmetricEncryptionScheme};
+use crate::sponge::constraints::CryptographicSpongeVar;
+use crate::sponge::poseidon::constraints::PoseidonSpongeVar;
+use crate::sponge::poseidon::PoseidonConfig;
+use crate::sponge::Absorb;
+use ark_ff::PrimeField;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_r1cs_std::prelude::*;
+use ark_relations::r1cs::{Namespace, SynthesisError};
+#[cfg(not(feature = "std"))]
+use ark_std::vec::Vec;
+use ark_std::{borrow::Borrow, marker::PhantomData};
+
+#[derive(Clone)]
+pub struct ParametersVar<F: PrimeField> {
+    pub parameters: PoseidonConfig<F>,
+}
+
+impl<F: PrimeField> AllocVar<PoseidonConfig<F>, F> for ParametersVar<F> {
+    fn new_variable<T: Borrow<PoseidonConfig<F>>>(
+        _cs: impl Into<Namespace<F>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        _mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        f().map(|param| Self {
+            parameters: param.borrow().clone(),
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PlaintextVar<F: PrimeField> {
+    pub plaintext: Vec<FpVar<F>>,
+}
+
+impl<F: PrimeField> AllocVar<Plaintext<F>, F> for PlaintextVar<F> {
+    fn new_variable<T: Borrow<Plaintext<F>>>(
+        cs: impl Into<Namespace<F>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        let plaintext = Vec::new_variable(cs, || f().map(|m| m.borrow().clone()), mode)?;
+        Ok(Self { plaintext })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct OutputVar<F: PrimeField> {
+    pub elements: Vec<FpVar<F>>,
+    pub tag: FpVar<F>,
+}
+
+impl<F: PrimeField> AllocVar<Ciphertext<F>, F> for OutputVar<F> {
+    fn new_variable<T: Borrow<Ciphertext<F>>>(
+        cs: impl Into<Namespace<F>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        let ns = cs.into();
+        let cs = ns.cs();
+        let prep = f().map(|c| c.borrow().clone());
+        let elements = Vec::new_variable(
+            cs.clone(),
+            || prep.as_ref().map(|c| c.elements.clone()).map_err(|e| *e),
+            mode,
+        )?;
+        let tag = FpVar::new_variable(cs, || prep.map(|c| c.tag), mode)?;
+        Ok(Self { elements, tag })
+    }
+}
+
+impl<F: PrimeField> EqGadget<F> for OutputVar<F> {
+    #[inline]
+    fn is_eq(&self, other: &Self) -> Result<Boolean<F>, SynthesisError> {
+        if self.elements.len() != other.elements.len() {
+            return Ok(Boolean::FALSE);
+        }
+        Ok(self.elements.is_eq(&other.elements)? & &self.tag.is_eq(&other.tag)?)
+    }
+}
+
+pub struct PoseidonAEGadget<F: PrimeField + Absorb> {
+    _field: PhantomData<F>,
+}
+
+impl<F: PrimeField + Absorb> SymmetricEncryptionGadget<PoseidonAE<F>, F> for PoseidonAEGadget<F> {
+    type OutputVar = OutputVar<F>;
+    type ParametersVar = ParametersVar<F>;
+    type PlaintextVar = PlaintextVar<F>;
+    type KeyVar = FpVar<F>;
+    type NonceVar = FpVar<F>;
+
+    fn encrypt(
+        parameters: &Self::ParametersVar,
+        message: &Self::PlaintextVar,
+        nonce: &Self::NonceVar,
+        key: &Self::KeyVar,
+    ) -> Result<Self::OutputVar, SynthesisError> {
+        let cs = key.cs().or(nonce.cs()).or(message.plaintext.cs());
+
+        if cs.is_none() {
+            let ciphertext = PoseidonAE::<F>::encrypt(
+                &parameters.parameters,
+                &key.value()?,
+                &message.plaintext.value()?,
+                &nonce.value()?,
+            )
+            .unwrap();
+            return Ok(OutputVar {
+                elements: ciphertext
+                    .elements
+                    .into_iter()
+                    .map(FpVar::Constant)
+                    .collect(),
+                tag: FpVar::Constant(ciphertext.tag),
+            });
+        }
+
+        // same as `PoseidonAE::init_sponge`
+        let mut sponge = PoseidonSpongeVar::new(cs, &parameters.parameters);
+        sponge.absorb(key)?;
+        sponge.absorb(nonce)?;
+        sponge.absorb(&FpVar::Constant(F::from(message.plaintext.len() as u64)))?;
+
+        let mut elements = Vec::with_capacity(message.plaintext.len());
+        for chunk in message.plaintext.chunks(parameters.parameters.rate) {
+            let keystream = sponge.squeeze_field_elements(chunk.len())?;
+            elements.extend(chunk.iter().zip(keystream).map(|(m, k)| m + k));
+            sponge.absorb(&chunk)?;
+        }
+        let tag = sponge.squeeze_field_elements(1)?.remove(0);
+
+        Ok(OutputVar { elements, tag })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::encryption::constraints::SymmetricEncryptionGadget;
+    use ark_std::{test_rng, UniformRand};
+
+    use ark_ed_on_bls12_381::Fq as Fr;
+
+    use crate::encryption::poseidon::{constraints::PoseidonAEGadget, PoseidonAE};
+    use crate::encryption::SymmetricEncryptionScheme;
+    use ark_r1cs_std::prelude::*;
+    use ark_relations::r1cs::ConstraintSystem;
+
+    #[test]
+    fn test_poseidon_ae_gadget() {
+        let rng = &mut test_rng();
+
+        type MyEnc = PoseidonAE<Fr>;
+        type MyGadget = PoseidonAEGadget<Fr>;
+
+        // compute primitive result
+        let parameters = MyEnc::setup(rng).unwrap();
+        let key = MyEnc::keygen(&parameters, rng).unwrap();
+        let nonce = Fr::rand(rng);
+        let msg: Vec<Fr> = (0..5).map(|_| Fr::rand(rng)).collect();
+        let primitive_result = MyEnc::encrypt(&parameters, &key, &msg, &nonce).unwrap();
+
+        // construct constraint system
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let parameters_var =
+            <MyGadget as SymmetricEncryptionGadget<MyEnc, Fr>>::ParametersVar::new_constant(
+                ark_relations::ns!(cs, "gadget_parameters"),
+                &parameters,
+            )
+            .unwrap();
+        let msg_var =
+            <MyGadget as SymmetricEncryptionGadget<MyEnc, Fr>>::PlaintextVar::new_witness(
+                ark_relations::ns!(cs, "gadget_message"),
+                || Ok(&msg),
+            )
+            .unwrap();
+        let nonce_var = <MyGadget as SymmetricEncryptionGadget<MyEnc, Fr>>::NonceVar::new_input(
+            ark_relations::ns!(cs, "gadget_nonce"),
+            || Ok(nonce),
+        )
+        .unwrap();
+        let key_var = <MyGadget as SymmetricEncryptionGadget<MyEnc, Fr>>::KeyVar::new_witness(
+            ark_relations::ns!(cs, "gadget_key"),
+            || Ok(key),
+        )
+        .unwrap();
+
+        // use gadget
+        let result_var =
+            MyGadget::encrypt(&parameters_var, &msg_var, &nonce_var, &key_var).unwrap();
+
+        // check that result equals expected ciphertext in the constraint system
+        let expected_var =
+            <MyGadget as SymmetricEncryptionGadget<MyEnc, Fr>>::OutputVar::new_input(
+                ark_relations::ns!(cs, "gadget_expected"),
+                || Ok(&primitive_result),
+            )
+            .unwrap();
+        expected_var.enforce_equal(&result_var).unwrap();
+
+        assert_eq!(
+            primitive_result.elements,
+            result_var.elements.value().unwrap()
+        );
+        assert_eq!(primitive_result.tag, result_var.tag.value().unwrap());
+        assert!(cs.is_satisfied().unwrap());
+
+        // a ciphertext of another plaintext does not verify
+        let mut other_msg = msg.clone();
+        other_msg[0] += Fr::from(1u64);
+        let other_result = MyEnc::encrypt(&parameters, &key, &other_msg, &nonce).unwrap();
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let msg_var =
+            <MyGadget as SymmetricEncryptionGadget<MyEnc, Fr>>::PlaintextVar::new_witness(
+                ark_relations::ns!(cs, "gadget_message"),
+                || Ok(&msg),
+            )
+            .unwrap();
+        let key_var = <MyGadget as SymmetricEncryptionGadget<MyEnc, Fr>>::KeyVar::new_witness(
+            ark_relations::ns!(cs, "gadget_key"),
+            || Ok(key),
+        )
+        .unwrap();
+        let nonce_var = <MyGadget as SymmetricEncryptionGadget<MyEnc, Fr>>::NonceVar::new_input(
+            ark_relations::ns!(cs, "gadget_nonce"),
+            || Ok(nonce),
+        )
+        .unwrap();
+        let result_var =
+            MyGadget::encrypt(&parameters_var, &msg_var, &nonce_var, &key_var).unwrap();
+        let expected_var =
+            <MyGadget as SymmetricEncryptionGadget<MyEnc, Fr>>::OutputVar::new_input(
+                ark_relations::ns!(cs, "gadget_expected"),
+                || Ok(&other_result),
+            )
+            .unwrap();
+        expected_var.enforce_equal(&result_var).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_poseidon_ae_gadget_permutations() {
+        let rng = &mut test_rng();
+
+        type MyEnc = PoseidonAE<Fr>;
+        type MyGadget = PoseidonAEGadget<Fr>;
+
+        let parameters = MyEnc::setup(rng).unwrap();
+        let key = MyEnc::keygen(&parameters, rng).unwrap();
+        let nonce = Fr::rand(rng);
+
+        let mut num_constraints = |len: usize| {
+            let msg: Vec<Fr> = (0..len).map(|_| Fr::rand(rng)).collect();
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let parameters_var =
+                <MyGadget as SymmetricEncryptionGadget<MyEnc, Fr>>::ParametersVar::new_constant(
+                    ark_relations::ns!(cs, "gadget_parameters"),
+                    &parameters,
+                )
+                .unwrap();
+            let msg_var =
+                <MyGadget as SymmetricEncryptionGadget<MyEnc, Fr>>::PlaintextVar::new_witness(
+                    ark_relations::ns!(cs, "gadget_message"),
+                    || Ok(&msg),
+                )
+                .unwrap();
+            let nonce_var =
+                <MyGadget as SymmetricEncryptionGadget<MyEnc, Fr>>::NonceVar::new_input(
+                    ark_relations::ns!(cs, "gadget_nonce"),
+                    || Ok(nonce),
+                )
+                .unwrap();
+            let key_var = <MyGadget as SymmetricEncryptionGadget<MyEnc, Fr>>::KeyVar::new_witness(
+                ark_relations::ns!(cs, "gadget_key"),
+                || Ok(key),
+            )
+            .unwrap();
+            MyGadget::encrypt(&parameters_var, &msg_var, &nonce_var, &key_var).unwrap();
+            cs.num_constraints()
+        };
+
+        // x^alpha by square-and-multiply, and a permutation of a state without constants
+        let alpha = parameters.alpha;
+        let s_box = (63 - alpha.leading_zeros() + alpha.count_ones() - 1) as usize;
+        let width = parameters.rate + parameters.capacity;
+        let permutation = (parameters.full_rounds * width + parameters.partial_rounds) * s_box;
+
+        // With a rate of 2, absorbing the key, the nonce and the length takes one permutation,
+        // in which the capacity element is a constant in the first S-box layer.
+        assert_eq!(parameters.rate, 2);
+        for (len, num_chunks) in [(0, 0), (1, 1), (2, 1), (3, 2), (5, 3), (6, 3)] {
+            assert_eq!(
+                num_constraints(len),
+                (1 + num_chunks + 1) * permutation - s_box,
+                "{len} elements"
+            );
+        }
+    }
+}
@@ -0,0 +1,173 @@
+#[cfg(feature = "r1cs")]
+pub mod constraints;
+
+use crate::encryption::SymmetricEncryptionScheme;
+use crate::sponge::poseidon::{secure_poseidon_config, PoseidonConfig, PoseidonSponge};
+use crate::sponge::{Absorb, CryptographicSponge, FieldBasedCryptographicSponge};
+use crate::Error;
+use ark_ff::{BigInteger, PrimeField};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::marker::PhantomData;
+use ark_std::rand::Rng;
+#[cfg(not(feature = "std"))]
+use ark_std::vec::Vec;
+
+/// Authenticated encryption of field elements with the Poseidon duplex sponge.
+///
+/// The sponge first absorbs the key, the nonce and the length of the message. Each chunk of
+/// `rate` message elements is then encrypted by adding the elements squeezed from the sponge,
+/// after which the plaintext chunk is absorbed. The tag is squeezed at the end.
+///
+/// Absorbing right after squeezing does not permute the state, so on top of the permutations
+/// needed to absorb the key, the nonce and the length, every chunk costs a single permutation,
+/// plus one more for the tag.
+///
+/// A nonce must never be reused with the same key.
+pub struct PoseidonAE<F: PrimeField + Absorb> {
+    _field: PhantomData<F>,
+}
+
+pub type Key<F> = F;
+
+pub type Nonce<F> = F;
+
+pub type Plaintext<F> = Vec<F>;
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Ciphertext<F: PrimeField> {
+    pub elements: Vec<F>,
+    pub tag: F,
+}
+
+impl<F: PrimeField + Absorb> PoseidonAE<F> {
+    fn init_sponge(
+        pp: &PoseidonConfig<F>,
+        key: &Key<F>,
+        nonce: &Nonce<F>,
+        len: usize,
+    ) -> PoseidonSponge<F> {
+        let mut sponge = PoseidonSponge::new(pp);
+        sponge.absorb(key);
+        sponge.absorb(nonce);
+        sponge.absorb(&(len as u64));
+        sponge
+    }
+}
+
+impl<F: PrimeField + Absorb> SymmetricEncryptionScheme for PoseidonAE<F> {
+    type Parameters = PoseidonConfig<F>;
+    type Key = Key<F>;
+    type Nonce = Nonce<F>;
+    type Plaintext = Plaintext<F>;
+    type Ciphertext = Ciphertext<F>;
+
+    fn setup<R: Rng>(_rng: &mut R) -> Result<Self::Parameters, Error> {
+        secure_poseidon_config(2)
+    }
+
+    fn keygen<R: Rng>(_pp: &Self::Parameters, rng: &mut R) -> Result<Self::Key, Error> {
+        Ok(F::rand(rng))
+    }
+
+    fn encrypt(
+        pp: &Self::Parameters,
+        key: &Self::Key,
+        message: &Self::Plaintext,
+        nonce: &Self::Nonce,
+    ) -> Result<Self::Ciphertext, Error> {
+        let mut sponge = Self::init_sponge(pp, key, nonce, message.len());
+
+        let mut elements = Vec::with_capacity(message.len());
+        for chunk in message.chunks(pp.rate) {
+            let keystream = sponge.squeeze_native_field_elements(chunk.len());
+            elements.extend(chunk.iter().zip(keystream).map(|(m, k)| *m + k));
+            sponge.absorb(&chunk);
+        }
+        let tag = sponge.squeeze_native_field_elements(1)[0];
+
+        Ok(Ciphertext { elements, tag })
+    }
+
+    fn decrypt(
+        pp: &Self::Parameters,
+        key: &Self::Key,
+        ciphertext: &Self::Ciphertext,
+        nonce: &Self::Nonce,
+    ) -> Result<Self::Plaintext, Error> {
+        let mut sponge = Self::init_sponge(pp, key, nonce, ciphertext.elements.len());
+
+        let mut message = Vec::with_capacity(ciphertext.elements.len());
+        for chunk in ciphertext.elements.chunks(pp.rate) {
+            let keystream = sponge.squeeze_native_field_elements(chunk.len());
+            let plaintext: Vec<F> = chunk.iter().zip(keystream).map(|(c, k)| *c - k).collect();
+            sponge.absorb(&plaintext);
+            message.extend(plaintext);
+        }
+        // Accumulate the difference of every byte so that the comparison does not stop at
+        // the first mismatch.
+        let expected = sponge.squeeze_native_field_elements(1)[0].into_bigint();
+        let difference = expected
+            .to_bytes_le()
+            .iter()
+            .zip(ciphertext.tag.into_bigint().to_bytes_le())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b));
+        if difference != 0 {
+            return Err(Error::AuthenticationFailure);
+        }
+
+        Ok(message)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ark_ed_on_bls12_381::Fq as Fr;
+    use ark_std::{test_rng, UniformRand};
+
+    use crate::encryption::poseidon::PoseidonAE;
+    use crate::encryption::SymmetricEncryptionScheme;
+    use crate::Error;
+
+    #[test]
+    fn test_poseidon_encryption() {
+        let rng = &mut test_rng();
+
+        let parameters = PoseidonAE::<Fr>::setup(rng).unwrap();
+        let key = PoseidonAE::<Fr>::keygen(&parameters, rng).unwrap();
+        let nonce = Fr::rand(rng);
+
+        for len in [0, 1, 2, 5] {
+            let msg: Vec<Fr> = (0..len).map(|_| Fr::rand(rng)).collect();
+            let cipher = PoseidonAE::<Fr>::encrypt(&parameters, &key, &msg, &nonce).unwrap();
+            let check_msg = PoseidonAE::<Fr>::decrypt(&parameters, &key, &cipher, &nonce).unwrap();
+            assert_eq!(msg, check_msg);
+        }
+    }
+
+    #[test]
+    fn test_poseidon_authentication() {
+        let rng = &mut test_rng();
+
+        let parameters = PoseidonAE::<Fr>::setup(rng).unwrap();
+        let key = PoseidonAE::<Fr>::keygen(&parameters, rng).unwrap();
+        let nonce = Fr::rand(rng);
+        let msg: Vec<Fr> = (0..3).map(|_| Fr::rand(rng)).collect();
+        let cipher = PoseidonAE::<Fr>::encrypt(&parameters, &key, &msg, &nonce).unwrap();
+
+        let mut tampered = cipher.clone();
+        tampered.elements[1] += Fr::from(1u64);
+        let mut truncated = cipher.clone();
+        truncated.elements.pop();
+        for (key, cipher, nonce) in [
+            (key, &tampered, nonce),
+            (key, &truncated, nonce),
+            (key + Fr::from(1u64), &cipher, nonce),
+            (key, &cipher, nonce + Fr::from(1u64)),
+        ] {
+            assert!(matches!(
+                PoseidonAE::<Fr>::decrypt(&parameters, &key, cipher, &nonce),
+                Err(Error::AuthenticationFailure)
+            ));
+        }
+    }
+}
@@ -16,7 +16,7 @@ extern crate ark_std;
 extern crate derivative;
 
 #[allow(unused_imports)]
-pub(crate) use ark_std::{borrow::ToOwned, boxed::Box, vec::Vec};
+pub(crate) use ark_std::{borrow::ToOwned, boxed::Box, string::String, vec::Vec};
 mod macros;
 
 #[cfg(feature = "commitment")]
@@ -47,6 +47,10 @@ pub mod sponge;
 pub enum Error {
     IncorrectInputLength(usize),
     NotPrimeOrder,
+    InvalidParameters(String),
+    AuthenticationFailure,
+    IncompleteAddition,
+    PointAtInfinity,
     GenericError(Box<dyn ark_std::error::Error + Send>),
     SerializationError(ark_serialize::SerializationError),
 }
@@ -56,6 +60,10 @@ impl core::fmt::Display for Error {
         match self {
             Self::IncorrectInputLength(len) => write!(f, "incorrect input length: {len}"),
             Self::NotPrimeOrder => write!(f, "element is not prime order"),
+            Self::InvalidParameters(msg) => write!(f, "invalid parameters: {msg}"),
+            Self::AuthenticationFailure => write!(f, "authentication failed"),
+            Self::IncompleteAddition => write!(f, "exceptional case of an incomplete addition"),
+            Self::PointAtInfinity => write!(f, "the point at infinity has no affine coordinates"),
             Self::GenericError(e) => write!(f, "{e}"),
             Self::SerializationError(e) => write!(f, "{e}"),
         }
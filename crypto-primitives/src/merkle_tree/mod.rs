@@ -44,7 +44,7 @@ type DefaultHasher = fnv::FnvHasher;
 /// Convert the hash digest in different layers by converting previous layer's output to
 /// `TargetType`, which is a `Borrow` to next layer's input.
 pub trait DigestConverter<From, To: ?Sized> {
-    type TargetType: Borrow<To>;
+    type TargetType: Borrow<To> + Send + Sync;
     fn convert(item: From) -> Result<Self::TargetType, Error>;
 }
 
@@ -55,7 +55,7 @@ pub struct IdentityDigestConverter<T> {
 }
 
 #[cfg(test)]
-impl<T> DigestConverter<T, T> for IdentityDigestConverter<T> {
+impl<T: Send + Sync> DigestConverter<T, T> for IdentityDigestConverter<T> {
     type TargetType = T;
     fn convert(item: T) -> Result<T, Error> {
         Ok(item)
@@ -455,31 +455,25 @@ impl<P: Config> MerkleTree<P> {
             let start_index = level_indices.pop().unwrap();
             let upper_bound = left_child(start_index);
 
-            cfg_iter_mut!(non_leaf_nodes[start_index..upper_bound])
-                .enumerate()
-                .try_for_each(|(i, n)| {
+            let inputs = cfg_into_iter!(start_index..upper_bound)
+                .map(|current_index| {
                     // `left_child(current_index)` and `right_child(current_index) returns the position of
                     // leaf in the whole tree (represented as a list in level order). We need to shift it
                     // by `-upper_bound` to get the index in `leaf_nodes` list.
-
-                    // similarly, we need to rescale i by start_index
-                    // to get the index outside the slice and in the level-ordered list of nodes
-
-                    let current_index = i + start_index;
                     let left_leaf_index = left_child(current_index) - upper_bound;
                     let right_leaf_index = right_child(current_index) - upper_bound;
-
-                    *n = P::TwoToOneHash::evaluate(
-                        two_to_one_hash_param,
+                    Ok((
                         P::LeafInnerDigestConverter::convert(
                             leaf_digests[left_leaf_index].clone(),
                         )?,
                         P::LeafInnerDigestConverter::convert(
                             leaf_digests[right_leaf_index].clone(),
                         )?,
-                    )?;
-                    Ok::<(), crate::Error>(())
-                })?;
+                    ))
+                })
+                .collect::<Result<Vec<_>, crate::Error>>()?;
+            let digests = P::TwoToOneHash::batch_evaluate(two_to_one_hash_param, &inputs)?;
+            non_leaf_nodes[start_index..upper_bound].clone_from_slice(&digests);
         }
 
         // compute the hash values for nodes in every other layer in the tree
@@ -490,28 +484,20 @@ impl<P: Config> MerkleTree<P> {
 
             let (nodes_at_level, nodes_at_prev_level) =
                 non_leaf_nodes[..].split_at_mut(upper_bound);
-            // Iterate over the nodes at the current level, and compute the hash of each node
-            cfg_iter_mut!(nodes_at_level[start_index..])
-                .enumerate()
-                .try_for_each(|(i, n)| {
-                    // `left_child(current_index)` and `right_child(current_index) returns the position of
-                    // leaf in the whole tree (represented as a list in level order). We need to shift it
-                    // by `-upper_bound` to get the index in `leaf_nodes` list.
-
-                    // similarly, we need to rescale i by start_index
-                    // to get the index outside the slice and in the level-ordered list of nodes
-                    let current_index = i + start_index;
+            let inputs: Vec<_> = (start_index..upper_bound)
+                .map(|current_index| {
+                    // the children are shifted by `-upper_bound` to get their index in
+                    // `nodes_at_prev_level`
                     let left_leaf_index = left_child(current_index) - upper_bound;
                     let right_leaf_index = right_child(current_index) - upper_bound;
-
-                    // need for unwrap as Box<Error> does not implement trait Send
-                    *n = P::TwoToOneHash::compress(
-                        two_to_one_hash_param,
-                        nodes_at_prev_level[left_leaf_index].clone(),
-                        nodes_at_prev_level[right_leaf_index].clone(),
-                    )?;
-                    Ok::<_, crate::Error>(())
-                })?;
+                    (
+                        &nodes_at_prev_level[left_leaf_index],
+                        &nodes_at_prev_level[right_leaf_index],
+                    )
+                })
+                .collect();
+            let digests = P::TwoToOneHash::batch_compress(two_to_one_hash_param, &inputs)?;
+            nodes_at_level[start_index..].clone_from_slice(&digests);
         }
         Ok(MerkleTree {
             leaf_nodes: leaf_digests,
@@ -78,7 +78,8 @@ const SIGMA: [[usize; 16]; 10] = [
 // END FUNCTION.
 //
 
-fn mixing_g<ConstraintF: PrimeField>(
+/// The mixing function G, which BLAKE3 shares with BLAKE2s.
+pub(crate) fn mixing_g<ConstraintF: PrimeField>(
     v: &mut [UInt32<ConstraintF>],
     a: usize,
     b: usize,
@@ -327,6 +328,22 @@ impl<ConstraintF: PrimeField> EqGadget<ConstraintF> for OutputVar<ConstraintF> {
     }
 }
 
+impl<ConstraintF: PrimeField> CondSelectGadget<ConstraintF> for OutputVar<ConstraintF> {
+    fn conditionally_select(
+        cond: &Boolean<ConstraintF>,
+        true_value: &Self,
+        false_value: &Self,
+    ) -> Result<Self, SynthesisError> {
+        let bytes: Result<Vec<_>, _> = true_value
+            .0
+            .iter()
+            .zip(false_value.0.iter())
+            .map(|(t, f)| UInt8::conditionally_select(cond, t, f))
+            .collect();
+        bytes.map(OutputVar)
+    }
+}
+
 impl<ConstraintF: PrimeField> ToBytesGadget<ConstraintF> for OutputVar<ConstraintF> {
     #[inline]
     fn to_bytes_le(&self) -> Result<Vec<UInt8<ConstraintF>>, SynthesisError> {
@@ -48,4 +48,21 @@ impl Blake2sWithParameterBlock {
         end_timer!(eval_time);
         h.finalize_fixed().into_iter().collect()
     }
+
+    /// Returns the parameter block as little-endian words, as expected by
+    /// `constraints::evaluate_blake2s_with_parameters`.
+    pub fn parameters(&self) -> [u32; 8] {
+        let word = |bytes: &[u8]| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        [
+            // digest length, key length, fanout = 1, depth = 1
+            u32::from_le_bytes([self.output_size, self.key_size, 1, 1]),
+            0,
+            0,
+            0,
+            word(&self.salt[..4]),
+            word(&self.salt[4..]),
+            word(&self.personalization[..4]),
+            word(&self.personalization[4..]),
+        ]
+    }
 }
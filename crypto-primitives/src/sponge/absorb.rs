@@ -1,11 +1,11 @@
-use ark_ec::short_weierstrass::Affine as SWAffine;
-use ark_ec::twisted_edwards::Affine as TEAffine;
+use ark_ec::short_weierstrass::{Affine as SWAffine, Projective as SWProjective};
+use ark_ec::twisted_edwards::{Affine as TEAffine, Projective as TEProjective};
 use ark_ec::{
     short_weierstrass::SWCurveConfig as SWModelParameters,
-    twisted_edwards::TECurveConfig as TEModelParameters,
+    twisted_edwards::TECurveConfig as TEModelParameters, CurveGroup,
 };
-use ark_ff::models::{Fp, FpConfig};
-use ark_ff::{BigInteger, Field, PrimeField, ToConstraintField};
+use ark_ff::models::{CubicExtConfig, CubicExtField, Fp, FpConfig, QuadExtConfig, QuadExtField};
+use ark_ff::{BigInt, BigInteger, Field, PrimeField, ToConstraintField};
 use ark_serialize::CanonicalSerialize;
 #[cfg(not(feature = "std"))]
 use ark_std::{string::String, vec::Vec};
@@ -167,6 +167,34 @@ impl<P: FpConfig<N>, const N: usize> Absorb for Fp<P, N> {
     }
 }
 
+macro_rules! impl_absorbable_extension_field {
+    ($field:ident, $config:ident) => {
+        impl<P: $config> Absorb for $field<P> {
+            fn to_sponge_bytes(&self, dest: &mut Vec<u8>) {
+                self.serialize_compressed(dest).unwrap()
+            }
+
+            fn to_sponge_field_elements<F: PrimeField>(&self, dest: &mut Vec<F>) {
+                let elems: Vec<_> = self.to_base_prime_field_elements().collect();
+                field_cast(&elems, dest).unwrap();
+            }
+        }
+    };
+}
+
+impl_absorbable_extension_field!(QuadExtField, QuadExtConfig);
+impl_absorbable_extension_field!(CubicExtField, CubicExtConfig);
+
+impl<const N: usize> Absorb for BigInt<N> {
+    fn to_sponge_bytes(&self, dest: &mut Vec<u8>) {
+        self.0.to_sponge_bytes(dest)
+    }
+
+    fn to_sponge_field_elements<F: PrimeField>(&self, dest: &mut Vec<F>) {
+        self.0.to_sponge_field_elements(dest)
+    }
+}
+
 macro_rules! impl_absorbable_unsigned {
     ($t:ident) => {
         impl Absorb for $t {
@@ -282,6 +310,48 @@ where
     }
 }
 
+impl<P: TEModelParameters> Absorb for TEProjective<P>
+where
+    P::BaseField: ToConstraintField<<P::BaseField as Field>::BasePrimeField>,
+{
+    fn to_sponge_bytes(&self, dest: &mut Vec<u8>) {
+        self.into_affine().to_sponge_bytes(dest)
+    }
+
+    fn to_sponge_field_elements<F: PrimeField>(&self, dest: &mut Vec<F>) {
+        self.into_affine().to_sponge_field_elements(dest)
+    }
+
+    fn batch_to_sponge_bytes(batch: &[Self], dest: &mut Vec<u8>) {
+        TEAffine::batch_to_sponge_bytes(&Self::normalize_batch(batch), dest)
+    }
+
+    fn batch_to_sponge_field_elements<F: PrimeField>(batch: &[Self], dest: &mut Vec<F>) {
+        TEAffine::batch_to_sponge_field_elements(&Self::normalize_batch(batch), dest)
+    }
+}
+
+impl<P: SWModelParameters> Absorb for SWProjective<P>
+where
+    P::BaseField: ToConstraintField<<P::BaseField as Field>::BasePrimeField>,
+{
+    fn to_sponge_bytes(&self, dest: &mut Vec<u8>) {
+        self.into_affine().to_sponge_bytes(dest)
+    }
+
+    fn to_sponge_field_elements<F: PrimeField>(&self, dest: &mut Vec<F>) {
+        self.into_affine().to_sponge_field_elements(dest)
+    }
+
+    fn batch_to_sponge_bytes(batch: &[Self], dest: &mut Vec<u8>) {
+        SWAffine::batch_to_sponge_bytes(&Self::normalize_batch(batch), dest)
+    }
+
+    fn batch_to_sponge_field_elements<F: PrimeField>(batch: &[Self], dest: &mut Vec<F>) {
+        SWAffine::batch_to_sponge_field_elements(&Self::normalize_batch(batch), dest)
+    }
+}
+
 impl<A: Absorb> Absorb for &[A] {
     fn to_sponge_bytes(&self, dest: &mut Vec<u8>) {
         A::batch_to_sponge_bytes(self, dest)
@@ -330,6 +400,37 @@ impl<A: Absorb> Absorb for Option<A> {
     }
 }
 
+impl<A: Absorb, const N: usize> Absorb for [A; N] {
+    fn to_sponge_bytes(&self, dest: &mut Vec<u8>) {
+        self.as_slice().to_sponge_bytes(dest)
+    }
+
+    fn to_sponge_field_elements<F: PrimeField>(&self, dest: &mut Vec<F>) {
+        self.as_slice().to_sponge_field_elements(dest)
+    }
+}
+
+macro_rules! impl_absorbable_tuple {
+    ($($ty:ident : $idx:tt),+) => {
+        impl<$($ty: Absorb),+> Absorb for ($($ty,)+) {
+            fn to_sponge_bytes(&self, dest: &mut Vec<u8>) {
+                $(self.$idx.to_sponge_bytes(dest);)+
+            }
+
+            fn to_sponge_field_elements<F: PrimeField>(&self, dest: &mut Vec<F>) {
+                $(self.$idx.to_sponge_field_elements(dest);)+
+            }
+        }
+    };
+}
+
+impl_absorbable_tuple!(A: 0);
+impl_absorbable_tuple!(A: 0, B: 1);
+impl_absorbable_tuple!(A: 0, B: 1, C: 2);
+impl_absorbable_tuple!(A: 0, B: 1, C: 2, D: 3);
+impl_absorbable_tuple!(A: 0, B: 1, C: 2, D: 3, E: 4);
+impl_absorbable_tuple!(A: 0, B: 1, C: 2, D: 3, E: 4, G: 5);
+
 // TODO: add more for common data structures, treemap?
 
 impl<A: Absorb> Absorb for &A {
@@ -386,9 +487,9 @@ macro_rules! collect_sponge_field_elements {
 mod tests {
     use crate::sponge::poseidon::{poseidon_parameters_for_test, PoseidonSponge};
     use crate::sponge::test::Fr;
-    use crate::sponge::Absorb;
     use crate::sponge::{field_cast, CryptographicSponge};
-    use ark_ff::PrimeField;
+    use crate::sponge::{Absorb, AbsorbWithLength};
+    use ark_ff::{BigInteger, PrimeField};
     use ark_std::{test_rng, UniformRand};
 
     #[test]
@@ -495,4 +596,76 @@ mod tests {
 
         assert_ne!(dest1, dest2);
     }
+
+    #[test]
+    fn test_bigint_and_tuple_absorb() {
+        let x = Fr::from(123456789u64).into_bigint();
+        assert_eq!(x.to_sponge_bytes_as_vec(), x.to_bytes_le());
+        assert_eq!(
+            x.to_sponge_field_elements_as_vec::<Fr>(),
+            x.0.iter().map(|limb| Fr::from(*limb)).collect::<Vec<_>>()
+        );
+
+        // tuples are absorbed as the concatenation of their elements
+        let tuple = (1u8, 2u32, Fr::from(3));
+        let mut expected = Vec::new();
+        1u8.to_sponge_bytes(&mut expected);
+        2u32.to_sponge_bytes(&mut expected);
+        Fr::from(3).to_sponge_bytes(&mut expected);
+        assert_eq!(tuple.to_sponge_bytes_as_vec(), expected);
+    }
+
+    #[derive(Absorb)]
+    struct StructWithAttributes {
+        a: u8,
+        #[absorb(skip)]
+        #[allow(dead_code)]
+        cache: u64,
+        #[absorb(with_length)]
+        b: Vec<u16>,
+    }
+
+    #[derive(Absorb)]
+    enum TestEnum {
+        Unit,
+        Unnamed(u8, Fr),
+        Named {
+            a: u16,
+            #[absorb(skip)]
+            #[allow(dead_code)]
+            b: u32,
+        },
+    }
+
+    #[test]
+    fn test_absorb_derive_attributes() {
+        let a = StructWithAttributes {
+            a: 1,
+            cache: 2,
+            b: vec![3, 4],
+        };
+        let mut expected = Vec::new();
+        a.a.to_sponge_bytes(&mut expected);
+        a.b.to_sponge_bytes_with_length(&mut expected);
+        assert_eq!(a.to_sponge_bytes_as_vec(), expected);
+
+        let mut expected: Vec<Fr> = Vec::new();
+        a.a.to_sponge_field_elements(&mut expected);
+        a.b.to_sponge_field_elements_with_length(&mut expected);
+        assert_eq!(a.to_sponge_field_elements_as_vec::<Fr>(), expected);
+    }
+
+    #[test]
+    fn test_absorb_derive_enum() {
+        // the fields of a variant are prefixed with its index
+        assert_eq!(TestEnum::Unit.to_sponge_bytes_as_vec(), vec![0]);
+        assert_eq!(
+            TestEnum::Unnamed(5, Fr::from(6)).to_sponge_field_elements_as_vec::<Fr>(),
+            vec![Fr::from(1), Fr::from(5), Fr::from(6)]
+        );
+        assert_eq!(
+            TestEnum::Named { a: 7, b: 8 }.to_sponge_bytes_as_vec(),
+            vec![2, 7, 0]
+        );
+    }
 }
@@ -5,7 +5,9 @@ use ark_ec::{
 use ark_ff::{Field, PrimeField};
 use ark_r1cs_std::boolean::Boolean;
 use ark_r1cs_std::convert::{ToBytesGadget, ToConstraintFieldGadget};
+use ark_r1cs_std::fields::cubic_extension::{CubicExtVar, CubicExtVarConfig};
 use ark_r1cs_std::fields::fp::FpVar;
+use ark_r1cs_std::fields::quadratic_extension::{QuadExtVar, QuadExtVarConfig};
 use ark_r1cs_std::fields::{FieldOpsBounds, FieldVar};
 use ark_r1cs_std::groups::curves::short_weierstrass::{
     AffineVar as SWAffineVar, ProjectiveVar as SWProjectiveVar,
@@ -16,6 +18,8 @@ use ark_relations::r1cs::SynthesisError;
 #[cfg(not(feature = "std"))]
 use ark_std::vec::Vec;
 
+pub use ark_crypto_primitives_macros::AbsorbGadget;
+
 /// An interface for objects that can be absorbed by a `CryptographicSpongeVar` whose constraint field
 /// is `CF`.
 pub trait AbsorbGadget<F: PrimeField> {
@@ -94,6 +98,31 @@ impl<F: PrimeField> AbsorbGadget<F> for FpVar<F> {
     }
 }
 
+macro_rules! impl_absorbable_extension_field_var {
+    ($var:ident, $config:ident) => {
+        impl<BF, P> AbsorbGadget<P::BasePrimeField> for $var<BF, P>
+        where
+            BF: FieldVar<P::BaseField, P::BasePrimeField>
+                + ToConstraintFieldGadget<P::BasePrimeField>,
+            for<'a> &'a BF: FieldOpsBounds<'a, P::BaseField, BF>,
+            P: $config<BF>,
+        {
+            fn to_sponge_bytes(&self) -> Result<Vec<UInt8<P::BasePrimeField>>, SynthesisError> {
+                self.to_bytes_le()
+            }
+
+            fn to_sponge_field_elements(
+                &self,
+            ) -> Result<Vec<FpVar<P::BasePrimeField>>, SynthesisError> {
+                self.to_constraint_field()
+            }
+        }
+    };
+}
+
+impl_absorbable_extension_field_var!(QuadExtVar, QuadExtVarConfig);
+impl_absorbable_extension_field_var!(CubicExtVar, CubicExtVarConfig);
+
 impl<P, F> AbsorbGadget<<P::BaseField as Field>::BasePrimeField> for TEAffineVar<P, F>
 where
     P: TEModelParameters,
@@ -184,6 +213,76 @@ impl<F: PrimeField, A: AbsorbGadget<F>> AbsorbGadget<F> for Vec<A> {
     }
 }
 
+/// An extension to `AbsorbGadget` that is specific to items with variable length, such as a list.
+/// The length is absorbed as a constant, with the same encoding as `AbsorbWithLength`.
+pub trait AbsorbGadgetWithLength<F: PrimeField>: AbsorbGadget<F> {
+    /// The length of the `self` being absorbed.
+    fn absorb_length(&self) -> usize;
+
+    /// Converts the object into a list of bytes along with its length information
+    /// that can be absorbed by a `CryptographicSpongeVar`.
+    fn to_sponge_bytes_with_length(&self) -> Result<Vec<UInt8<F>>, SynthesisError> {
+        let mut output = UInt8::constant_vec(&(self.absorb_length() as u64).to_le_bytes());
+        output.append(&mut self.to_sponge_bytes()?);
+        Ok(output)
+    }
+
+    /// Converts the object into field elements along with its length information
+    /// that can be absorbed by a `CryptographicSpongeVar`.
+    fn to_sponge_field_elements_with_length(&self) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        let mut output = vec![FpVar::Constant(F::from(self.absorb_length() as u64))];
+        output.append(&mut self.to_sponge_field_elements()?);
+        Ok(output)
+    }
+}
+
+impl<F: PrimeField, A: AbsorbGadget<F>> AbsorbGadgetWithLength<F> for &[A] {
+    fn absorb_length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<F: PrimeField, A: AbsorbGadget<F>> AbsorbGadgetWithLength<F> for Vec<A> {
+    fn absorb_length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<F: PrimeField, A: AbsorbGadget<F>, const N: usize> AbsorbGadget<F> for [A; N] {
+    fn to_sponge_bytes(&self) -> Result<Vec<UInt8<F>>, SynthesisError> {
+        self.as_slice().to_sponge_bytes()
+    }
+
+    fn to_sponge_field_elements(&self) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        self.as_slice().to_sponge_field_elements()
+    }
+}
+
+macro_rules! impl_absorbable_tuple_var {
+    ($($ty:ident : $idx:tt),+) => {
+        impl<F: PrimeField, $($ty: AbsorbGadget<F>),+> AbsorbGadget<F> for ($($ty,)+) {
+            fn to_sponge_bytes(&self) -> Result<Vec<UInt8<F>>, SynthesisError> {
+                let mut output = Vec::new();
+                $(output.append(&mut self.$idx.to_sponge_bytes()?);)+
+                Ok(output)
+            }
+
+            fn to_sponge_field_elements(&self) -> Result<Vec<FpVar<F>>, SynthesisError> {
+                let mut output = Vec::new();
+                $(output.append(&mut self.$idx.to_sponge_field_elements()?);)+
+                Ok(output)
+            }
+        }
+    };
+}
+
+impl_absorbable_tuple_var!(A: 0);
+impl_absorbable_tuple_var!(A: 0, B: 1);
+impl_absorbable_tuple_var!(A: 0, B: 1, C: 2);
+impl_absorbable_tuple_var!(A: 0, B: 1, C: 2, D: 3);
+impl_absorbable_tuple_var!(A: 0, B: 1, C: 2, D: 3, E: 4);
+impl_absorbable_tuple_var!(A: 0, B: 1, C: 2, D: 3, E: 4, G: 5);
+
 impl<F: PrimeField, A: AbsorbGadget<F>> AbsorbGadget<F> for Option<A> {
     fn to_sponge_bytes(&self) -> Result<Vec<UInt8<F>>, SynthesisError> {
         let mut output = Vec::new();
@@ -242,9 +341,9 @@ macro_rules! collect_sponge_field_elements_gadget {
 
 #[cfg(test)]
 mod tests {
-    use crate::sponge::constraints::AbsorbGadget;
-    use crate::sponge::Absorb;
-    use ark_bls12_377::{Fq, G1Projective as G};
+    use crate::sponge::constraints::{AbsorbGadget, AbsorbGadgetWithLength};
+    use crate::sponge::{Absorb, AbsorbWithLength};
+    use ark_bls12_377::{Fq, Fq12, Fq12Config, Fq2, Fq2Config, Fq6, Fq6Config, G1Projective as G};
     use ark_ec::CurveGroup;
     use ark_ec::{
         short_weierstrass::{Projective as SWProjective, SWCurveConfig},
@@ -252,7 +351,9 @@ mod tests {
     };
     use ark_ed_on_bls12_377::EdwardsProjective;
     use ark_ff::PrimeField;
+    use ark_r1cs_std::boolean::Boolean;
     use ark_r1cs_std::fields::fp::FpVar;
+    use ark_r1cs_std::fields::{fp12::Fp12Var, fp2::Fp2Var, fp6_3over2::Fp6Var};
     use ark_r1cs_std::uint8::UInt8;
     use ark_r1cs_std::R1CSVar;
     use ark_r1cs_std::{
@@ -277,6 +378,11 @@ mod tests {
         let g_affine = g.into_affine();
         let native_point_bytes = g_affine.to_sponge_bytes_as_vec();
         let native_point_field = g_affine.to_sponge_field_elements_as_vec::<C::BaseField>();
+        assert_eq!(g.to_sponge_bytes_as_vec(), native_point_bytes);
+        assert_eq!(
+            g.to_sponge_field_elements_as_vec::<C::BaseField>(),
+            native_point_field
+        );
 
         let cs_point =
             SWProjectiveVar::<C, FpVar<C::BaseField>>::new_input(ns!(cs, "sw_projective"), || {
@@ -308,6 +414,11 @@ mod tests {
         let g_affine = g.into_affine();
         let native_point_bytes = g_affine.to_sponge_bytes_as_vec();
         let native_point_field = g_affine.to_sponge_field_elements_as_vec::<C::BaseField>();
+        assert_eq!(g.to_sponge_bytes_as_vec(), native_point_bytes);
+        assert_eq!(
+            g.to_sponge_field_elements_as_vec::<C::BaseField>(),
+            native_point_field
+        );
 
         let cs_point =
             TEAffineVar::<C, FpVar<C::BaseField>>::new_input(ns!(cs, "te_affine"), || Ok(g))?;
@@ -322,6 +433,44 @@ mod tests {
         Ok(())
     }
 
+    fn consistency_check_for<T: Absorb, V: AbsorbGadget<Fq>>(native: &T, var: &V) {
+        assert_eq!(
+            var.to_sponge_bytes().unwrap().value().unwrap(),
+            native.to_sponge_bytes_as_vec()
+        );
+        assert_eq!(
+            var.to_sponge_field_elements().unwrap().value().unwrap(),
+            native.to_sponge_field_elements_as_vec::<Fq>()
+        );
+    }
+
+    #[test]
+    fn extension_field_consistency_check() {
+        let cs = ConstraintSystem::<Fq>::new_ref();
+        let mut rng = test_rng();
+
+        let fq2 = Fq2::rand(&mut rng);
+        let fq2_var = Fp2Var::<Fq2Config>::new_witness(ns!(cs, "fq2"), || Ok(fq2)).unwrap();
+        consistency_check_for(&fq2, &fq2_var);
+
+        let fq6 = Fq6::rand(&mut rng);
+        let fq6_var = Fp6Var::<Fq6Config>::new_witness(ns!(cs, "fq6"), || Ok(fq6)).unwrap();
+        consistency_check_for(&fq6, &fq6_var);
+
+        let fq12 = Fq12::rand(&mut rng);
+        let fq12_var = Fp12Var::<Fq12Config>::new_witness(ns!(cs, "fq12"), || Ok(fq12)).unwrap();
+        consistency_check_for(&fq12, &fq12_var);
+        assert_eq!(fq12.to_sponge_field_elements_as_vec::<Fq>().len(), 12);
+
+        // arrays and tuples
+        let fq = Fq::rand(&mut rng);
+        let fq_var = FpVar::new_witness(ns!(cs, "fq"), || Ok(fq)).unwrap();
+        consistency_check_for(&[fq, fq], &[fq_var.clone(), fq_var.clone()]);
+        consistency_check_for(&(fq2, fq, 5u8), &(fq2_var, fq_var, UInt8::constant(5)));
+
+        assert!(cs.is_satisfied().unwrap());
+    }
+
     #[test]
     fn consistency_check() {
         // test constraint is consistent with native
@@ -358,4 +507,80 @@ mod tests {
 
         assert!(cs.is_satisfied().unwrap())
     }
+
+    #[derive(Absorb)]
+    struct Native {
+        a: u8,
+        b: Fq,
+        #[absorb(skip)]
+        #[allow(dead_code)]
+        c: bool,
+        #[absorb(with_length)]
+        d: Vec<Fq>,
+    }
+
+    #[derive(AbsorbGadget)]
+    struct NativeVar<F: PrimeField> {
+        a: UInt8<F>,
+        b: FpVar<F>,
+        #[absorb(skip)]
+        #[allow(dead_code)]
+        c: Boolean<F>,
+        #[absorb(with_length)]
+        d: Vec<FpVar<F>>,
+    }
+
+    #[derive(Absorb)]
+    enum NativeEnum {
+        A(Fq),
+        B {
+            a: u8,
+            #[absorb(with_length)]
+            b: Vec<Fq>,
+        },
+    }
+
+    #[derive(AbsorbGadget)]
+    #[absorb(field = Fq)]
+    enum NativeEnumVar {
+        A(FpVar<Fq>),
+        B {
+            a: UInt8<Fq>,
+            #[absorb(with_length)]
+            b: Vec<FpVar<Fq>>,
+        },
+    }
+
+    #[test]
+    fn derive_consistency_check() {
+        let cs = ConstraintSystem::<Fq>::new_ref();
+        let mut rng = test_rng();
+
+        let native = Native {
+            a: 1,
+            b: Fq::rand(&mut rng),
+            c: true,
+            d: (0..3).map(|_| Fq::rand(&mut rng)).collect(),
+        };
+        let var = NativeVar {
+            a: UInt8::new_witness(ns!(cs, "a"), || Ok(native.a)).unwrap(),
+            b: FpVar::new_witness(ns!(cs, "b"), || Ok(native.b)).unwrap(),
+            c: Boolean::new_witness(ns!(cs, "c"), || Ok(native.c)).unwrap(),
+            d: Vec::new_witness(ns!(cs, "d"), || Ok(native.d.clone())).unwrap(),
+        };
+        consistency_check_for(&native, &var);
+
+        let fq = Fq::rand(&mut rng);
+        let fq_var = FpVar::new_witness(ns!(cs, "fq"), || Ok(fq)).unwrap();
+        consistency_check_for(&NativeEnum::A(fq), &NativeEnumVar::A(fq_var));
+        consistency_check_for(
+            &NativeEnum::B { a: 2, b: native.d },
+            &NativeEnumVar::B {
+                a: UInt8::constant(2),
+                b: var.d,
+            },
+        );
+
+        assert!(cs.is_satisfied().unwrap());
+    }
 }
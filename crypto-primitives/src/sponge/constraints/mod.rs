@@ -119,6 +119,10 @@ pub trait CryptographicSpongeVar<CF: PrimeField, S: CryptographicSponge>: Clone
 
     /// Squeeze `sizes.len()` emulated field elements from the sponge, where the `i`-th element of
     /// the output has size `sizes[i]`.
+    ///
+    /// Each element is returned with the little-endian bits it is computed from. For
+    /// `FieldElementSize::Unbiased`, the element is the reduction of these bits modulo the
+    /// characteristic of `F`.
     fn squeeze_emulated_field_elements_with_sizes<F: PrimeField>(
         &mut self,
         sizes: &[FieldElementSize],
@@ -147,7 +151,32 @@ pub trait CryptographicSpongeVar<CF: PrimeField, S: CryptographicSponge>: Clone
             dest_bits.push(emulated_bits_le);
         }
 
-        let dest_gadgets = bits_le_to_emulated(cs, dest_bits.iter())?;
+        // Elements of more than `F::MODULUS_BIT_SIZE - 1` bits are split into chunks that fit
+        // in the normal form, and recombined with emulated arithmetic.
+        let chunk_size = (F::MODULUS_BIT_SIZE - 1) as usize;
+        let mut num_chunks = Vec::with_capacity(dest_bits.len());
+        let mut chunks = Vec::with_capacity(dest_bits.len());
+        for bits in &dest_bits {
+            if bits.len() <= chunk_size {
+                num_chunks.push(1);
+                chunks.push(bits.clone());
+            } else {
+                num_chunks.push(bits.len().div_ceil(chunk_size));
+                chunks.extend(bits.chunks(chunk_size).map(|chunk| chunk.to_vec()));
+            }
+        }
+
+        let shift = F::from(2u8).pow([chunk_size as u64]);
+        let mut chunk_gadgets = bits_le_to_emulated(cs, chunks.iter())?.into_iter();
+        let dest_gadgets = num_chunks
+            .into_iter()
+            .map(|num_chunks| {
+                let chunks = chunk_gadgets.by_ref().take(num_chunks).collect::<Vec<_>>();
+                let mut chunks = chunks.into_iter().rev();
+                let highest = chunks.next().unwrap();
+                chunks.fold(highest, |acc, chunk| acc * shift + chunk)
+            })
+            .collect();
 
         Ok((dest_gadgets, dest_bits))
     }
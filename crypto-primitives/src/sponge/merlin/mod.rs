@@ -1,4 +1,4 @@
-use crate::sponge::{Absorb, CryptographicSponge};
+use crate::sponge::{Absorb, CryptographicSponge, SpongeExt};
 #[cfg(not(feature = "std"))]
 use ark_std::vec::Vec;
 pub use merlin::Transcript;
@@ -31,3 +31,17 @@ impl CryptographicSponge for Transcript {
         dest[..num_bits].to_vec()
     }
 }
+
+/// Merlin does not expose the internal state of a `Transcript`,
+/// so the state of the sponge is the transcript itself, and it cannot be serialized.
+impl SpongeExt for Transcript {
+    type State = Transcript;
+
+    fn from_state(state: Self::State, _params: &Self::Config) -> Self {
+        state
+    }
+
+    fn into_state(self) -> Self::State {
+        self
+    }
+}
@@ -1,4 +1,8 @@
-use ark_ff::PrimeField;
+use ark_ff::{BigInteger, PrimeField};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, SerializationError, Valid, Validate,
+};
+use ark_std::io::{Read, Write};
 #[cfg(not(feature = "std"))]
 use ark_std::vec::Vec;
 
@@ -22,6 +26,9 @@ pub mod poseidon;
 /// [merlin]: https://merlin.cool/
 pub mod merlin;
 
+/// A labeled Fiat-Shamir transcript over any sponge
+pub mod transcript;
+
 #[cfg(test)]
 mod test;
 
@@ -33,6 +40,12 @@ pub enum FieldElementSize {
 
     /// Sample field elements from a subset of the field, specified by the maximum number of bits.
     Truncated(usize),
+
+    /// Sample field elements from the entire field, with a distribution that is statistically
+    /// close to uniform: `F::MODULUS_BIT_SIZE + λ` bits are sampled and reduced modulo the field
+    /// characteristic, where `λ` is the specified number of bits. The statistical distance to
+    /// the uniform distribution is at most `2^{-λ}`.
+    Unbiased(usize),
 }
 
 impl FieldElementSize {
@@ -42,6 +55,8 @@ impl FieldElementSize {
                 panic!("num_bits is greater than the capacity of the field.")
             }
             *num_bits
+        } else if let FieldElementSize::Unbiased(security_bits) = self {
+            F::MODULUS_BIT_SIZE as usize + security_bits
         } else {
             (F::MODULUS_BIT_SIZE - 1) as usize
         }
@@ -123,7 +138,8 @@ pub trait CryptographicSponge: Clone {
     /// TODO: Support general Field.
     ///
     /// Note that when `FieldElementSize` is `FULL`, the output is not strictly uniform. Output
-    /// space is uniform in \[0, 2^{F::MODULUS_BITS - 1}\]
+    /// space is uniform in \[0, 2^{F::MODULUS_BITS - 1}\]. Use `FieldElementSize::Unbiased`
+    /// for an output that is statistically close to uniform.
     fn squeeze_field_elements_with_sizes<F: PrimeField>(
         &mut self,
         sizes: &[FieldElementSize],
@@ -141,6 +157,26 @@ pub trait CryptographicSponge: Clone {
         )
     }
 
+    /// Squeeze `num_elements` uniformly distributed emulated field elements from the sponge, by
+    /// rejection sampling: `F::MODULUS_BIT_SIZE` bits are squeezed until they encode an integer
+    /// smaller than the field characteristic.
+    ///
+    /// The number of squeezed bits depends on the output, so this has no constraints
+    /// counterpart. In circuits, use `FieldElementSize::Unbiased` instead.
+    fn squeeze_field_elements_by_rejection<F: PrimeField>(
+        &mut self,
+        num_elements: usize,
+    ) -> Vec<F> {
+        let mut output = Vec::with_capacity(num_elements);
+        while output.len() < num_elements {
+            let bits = self.squeeze_bits(F::MODULUS_BIT_SIZE as usize);
+            if let Some(element) = F::from_bigint(F::BigInt::from_bits_le(&bits)) {
+                output.push(element);
+            }
+        }
+        output
+    }
+
     /// Creates a new sponge with applied domain separation.
     fn fork(&self, domain: &[u8]) -> Self {
         let mut new_sponge = self.clone();
@@ -191,7 +227,7 @@ pub trait SpongeExt: CryptographicSponge {
 }
 
 /// The mode structure for duplex sponges
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum DuplexSpongeMode {
     /// The sponge is currently absorbing data.
     Absorbing {
@@ -204,3 +240,48 @@ pub enum DuplexSpongeMode {
         next_squeeze_index: usize,
     },
 }
+
+impl CanonicalSerialize for DuplexSpongeMode {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        let (tag, index) = match self {
+            Self::Absorbing { next_absorb_index } => (0u8, next_absorb_index),
+            Self::Squeezing { next_squeeze_index } => (1u8, next_squeeze_index),
+        };
+        tag.serialize_with_mode(&mut writer, compress)?;
+        index.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        0u8.serialized_size(compress) + 0usize.serialized_size(compress)
+    }
+}
+
+impl Valid for DuplexSpongeMode {
+    fn check(&self) -> Result<(), SerializationError> {
+        Ok(())
+    }
+}
+
+impl CanonicalDeserialize for DuplexSpongeMode {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let tag = u8::deserialize_with_mode(&mut reader, compress, validate)?;
+        let index = usize::deserialize_with_mode(&mut reader, compress, validate)?;
+        match tag {
+            0 => Ok(Self::Absorbing {
+                next_absorb_index: index,
+            }),
+            1 => Ok(Self::Squeezing {
+                next_squeeze_index: index,
+            }),
+            _ => Err(SerializationError::InvalidData),
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use crate::sponge::poseidon::{find_poseidon_ark_and_mds, PoseidonConfig, PoseidonSponge};
+use crate::sponge::CryptographicSponge;
+use ark_ff::PrimeField;
+
+/// Limbs of the modulus of the scalar field of BN254, the only field supported by circomlib.
+const BN254_FR_MODULUS: [u64; 4] = [
+    0x43e1f593f0000001,
+    0x2833e84879b97091,
+    0xb85045b68181585d,
+    0x30644e72e131a029,
+];
+
+/// Number of full rounds used by circomlib.
+const FULL_ROUNDS: usize = 8;
+
+/// Number of partial rounds used by circomlib, indexed by `num_inputs - 1`.
+const PARTIAL_ROUNDS: [usize; 16] = [
+    56, 57, 56, 60, 60, 63, 64, 63, 60, 66, 60, 65, 70, 60, 64, 68,
+];
+
+/// The Poseidon parameters of [circomlib](https://github.com/iden3/circomlib), for BN254 Fr.
+///
+/// circomlib's round constants and MDS matrices were generated by the reference
+/// `generate_parameters_grain.sage` script, which is reproduced by [`find_poseidon_ark_and_mds`].
+/// Together with the round numbers of circomlib, this gives the exact same parameters.
+///
+/// Unlike [`PoseidonSponge`], circomlib's `poseidon([a, b, ...])` hashes a fixed number of
+/// inputs with a single permutation of `[0, a, b, ...]`, and outputs the first element of the
+/// state. This is implemented by [`PoseidonCircomConfig::hash`].
+#[derive(Clone, Debug)]
+pub struct PoseidonCircomConfig<F: PrimeField> {
+    /// The parameters of the permutation, of rate `num_inputs` and capacity 1.
+    pub config: PoseidonConfig<F>,
+}
+
+impl<F: PrimeField> PoseidonCircomConfig<F> {
+    /// Returns the parameters of circomlib's Poseidon for `num_inputs` inputs.
+    ///
+    /// Returns `None` if `F` is not the scalar field of BN254, or if `num_inputs` is not
+    /// between 1 and 16, as in circomlib.
+    pub fn new(num_inputs: usize) -> Option<Self> {
+        if F::MODULUS.as_ref() != BN254_FR_MODULUS || num_inputs == 0 {
+            return None;
+        }
+        let partial_rounds = *PARTIAL_ROUNDS.get(num_inputs - 1)?;
+
+        let (ark, mds) = find_poseidon_ark_and_mds::<F>(
+            F::MODULUS_BIT_SIZE as u64,
+            num_inputs,
+            FULL_ROUNDS as u64,
+            partial_rounds as u64,
+            0,
+        );
+        Some(Self {
+            config: PoseidonConfig::new(FULL_ROUNDS, partial_rounds, 5, mds, ark, num_inputs, 1),
+        })
+    }
+
+    /// The number of inputs of the hash function.
+    pub fn num_inputs(&self) -> usize {
+        self.config.rate
+    }
+
+    /// Computes circomlib's `poseidon(inputs)`.
+    ///
+    /// Panics if the length of `inputs` is not `self.num_inputs()`.
+    pub fn hash(&self, inputs: &[F]) -> F {
+        assert_eq!(
+            inputs.len(),
+            self.num_inputs(),
+            "incorrect number of inputs for circomlib's Poseidon"
+        );
+
+        let mut sponge = PoseidonSponge::new(&self.config);
+        sponge.state[1..].copy_from_slice(inputs);
+        sponge.permute();
+        sponge.state[0]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::sponge::poseidon::PoseidonCircomConfig;
+    use ark_bn254::Fr;
+    use ark_ff::MontFp;
+
+    #[test]
+    fn circomlib_vectors() {
+        // poseidon([1, 2, ..., n]), computed with circomlibjs for n <= 12.
+        let expected: [Fr; 16] = [
+            MontFp!(
+                "18586133768512220936620570745912940619677854269274689475585506675881198879027"
+            ),
+            MontFp!("7853200120776062878684798364095072458815029376092732009249414926327459813530"),
+            MontFp!("6542985608222806190361240322586112750744169038454362455181422643027100751666"),
+            MontFp!(
+                "18821383157269793795438455681495246036402687001665670618754263018637548127333"
+            ),
+            MontFp!("6183221330272524995739186171720101788151706631170188140075976616310159254464"),
+            MontFp!(
+                "20400040500897583745843009878988256314335038853985262692600694741116813247201"
+            ),
+            MontFp!(
+                "12748163991115452309045839028154629052133952896122405799815156419278439301912"
+            ),
+            MontFp!(
+                "18604317144381847857886385684060986177838410221561136253933256952257712543953"
+            ),
+            MontFp!(
+                "13589767895268936107593642967621470491511464502761040466226072462545218539640"
+            ),
+            MontFp!("3657500514307717306974218405144578736633140001277925127187636780142269815841"),
+            MontFp!("3572015662710076994097916907865950486270383304442561406230608893458731714472"),
+            MontFp!("2501997477381648492950318384533644783248002172679259592360114615426357826485"),
+            // For n > 12, computed with an implementation independent of this crate of the
+            // reference `generate_parameters_grain.sage` script and of the permutation.
+            MontFp!("7041832639553862712666971417715061873827921493498355005117622707743491651590"),
+            MontFp!("8354478399926161176778659061636406690034081872658507739535256090879947077494"),
+            MontFp!("4203130618016961831408770638653325366880478848856764494148034853759773445968"),
+            MontFp!("9989051620750914585850546081941653841776809718687451684622678807385399211877"),
+        ];
+
+        for (i, expected) in expected.iter().enumerate() {
+            let num_inputs = i + 1;
+            let config = PoseidonCircomConfig::<Fr>::new(num_inputs).unwrap();
+            let inputs: Vec<Fr> = (1..=num_inputs as u64).map(Fr::from).collect();
+            assert_eq!(config.hash(&inputs), *expected);
+        }
+    }
+
+    #[test]
+    fn unsupported_parameters() {
+        assert!(PoseidonCircomConfig::<Fr>::new(0).is_none());
+        assert!(PoseidonCircomConfig::<Fr>::new(16).is_some());
+        assert!(PoseidonCircomConfig::<Fr>::new(17).is_none());
+        assert!(PoseidonCircomConfig::<crate::sponge::test::Fr>::new(2).is_none());
+    }
+}
@@ -1,6 +1,9 @@
 use crate::sponge::constraints::AbsorbGadget;
 use crate::sponge::constraints::{CryptographicSpongeVar, SpongeWithGadget};
-use crate::sponge::poseidon::{PoseidonConfig, PoseidonSponge};
+use crate::sponge::poseidon::{
+    assert_state_matches_config, PoseidonCircomConfig, PoseidonConfig, PoseidonOptimizedConfig,
+    PoseidonSponge,
+};
 use crate::sponge::DuplexSpongeMode;
 
 use ark_ff::PrimeField;
@@ -29,6 +32,8 @@ pub struct PoseidonSpongeVar<F: PrimeField> {
     pub state: Vec<FpVar<F>>,
     /// The mode
     pub mode: DuplexSpongeMode,
+    /// Whether the optimized form of the parameters was valid when the sponge was created.
+    optimized: bool,
 }
 
 impl<F: PrimeField> SpongeWithGadget<F> for PoseidonSponge<F> {
@@ -36,6 +41,27 @@ impl<F: PrimeField> SpongeWithGadget<F> for PoseidonSponge<F> {
 }
 
 impl<F: PrimeField> PoseidonSpongeVar<F> {
+    /// Returns a sponge that resumes from an allocated `state` in `mode`,
+    /// for example the witnessed [`PoseidonSpongeState`](crate::sponge::poseidon::PoseidonSpongeState)
+    /// of a native sponge.
+    ///
+    /// Panics if `state` and `mode` do not match the dimensions of `parameters`.
+    pub fn from_state(
+        cs: ConstraintSystemRef<F>,
+        parameters: &PoseidonConfig<F>,
+        state: Vec<FpVar<F>>,
+        mode: DuplexSpongeMode,
+    ) -> Self {
+        assert_state_matches_config(&state, &mode, parameters);
+        Self {
+            cs,
+            parameters: parameters.clone(),
+            state,
+            mode,
+            optimized: parameters.optimized().is_some(),
+        }
+    }
+
     #[tracing::instrument(target = "r1cs", skip(self))]
     fn apply_s_box(
         &self,
@@ -81,9 +107,83 @@ impl<F: PrimeField> PoseidonSpongeVar<F> {
     }
 
     #[tracing::instrument(target = "r1cs", skip(self))]
-    fn permute(&mut self) -> Result<(), SynthesisError> {
+    fn apply_dense_mds(
+        &self,
+        state: &mut [FpVar<F>],
+        mds: &[Vec<F>],
+    ) -> Result<(), SynthesisError> {
+        let mut new_state = Vec::new();
+        for row in mds {
+            let mut cur = FpVar::<F>::zero();
+            for (state_elem, m) in state.iter().zip(row) {
+                cur += state_elem * *m;
+            }
+            new_state.push(cur);
+        }
+        state.clone_from_slice(&new_state);
+        Ok(())
+    }
+
+    /// Applies the permutation using the precomputed sparse matrices of `optimized`.
+    ///
+    /// In the partial rounds, only `state[0]` goes through the S-box, and the other elements are
+    /// only updated by adding a multiple of `state[0]`. As linear layers cost no constraints,
+    /// the constraint count is that of [`Self::permute`] without `optimized`, but each partial
+    /// round builds `2t - 1` terms of linear combinations instead of `t^2`.
+    #[tracing::instrument(target = "r1cs", skip(self, optimized))]
+    fn permute_optimized(
+        &self,
+        optimized: &PoseidonOptimizedConfig<F>,
+        state: &mut [FpVar<F>],
+    ) -> Result<(), SynthesisError> {
         let full_rounds_over_2 = self.parameters.full_rounds / 2;
+        for (i, round_ark) in optimized.full_ark[..full_rounds_over_2].iter().enumerate() {
+            for (state_elem, c) in state.iter_mut().zip(round_ark) {
+                *state_elem += *c;
+            }
+            self.apply_s_box(state, true)?;
+            if i + 1 == full_rounds_over_2 {
+                self.apply_dense_mds(state, &optimized.pre_sparse_mds)?;
+            } else {
+                self.apply_dense_mds(state, &optimized.mds)?;
+            }
+        }
+
+        for (c, sparse) in optimized.partial_ark.iter().zip(&optimized.sparse_mds) {
+            state[0] += *c;
+            self.apply_s_box(state, false)?;
+
+            let first = state[0].clone();
+            let mut new_first = FpVar::<F>::zero();
+            for (state_elem, m) in state.iter().zip(&sparse.row) {
+                new_first += state_elem * *m;
+            }
+            state[0] = new_first;
+            for (state_elem, m) in state[1..].iter_mut().zip(&sparse.col) {
+                *state_elem += &first * *m;
+            }
+        }
+
+        for round_ark in &optimized.full_ark[full_rounds_over_2..] {
+            for (state_elem, c) in state.iter_mut().zip(round_ark) {
+                *state_elem += *c;
+            }
+            self.apply_s_box(state, true)?;
+            self.apply_dense_mds(state, &optimized.mds)?;
+        }
+        Ok(())
+    }
+
+    #[tracing::instrument(target = "r1cs", skip(self))]
+    pub(crate) fn permute(&mut self) -> Result<(), SynthesisError> {
         let mut state = self.state.clone();
+        if let Some(optimized) = self.parameters.cached_optimized(self.optimized) {
+            self.permute_optimized(optimized, &mut state)?;
+            self.state = state;
+            return Ok(());
+        }
+
+        let full_rounds_over_2 = self.parameters.full_rounds / 2;
         for i in 0..full_rounds_over_2 {
             self.apply_ark(&mut state, i)?;
             self.apply_s_box(&mut state, true)?;
@@ -197,6 +297,7 @@ impl<F: PrimeField> CryptographicSpongeVar<F, PoseidonSponge<F>> for PoseidonSpo
             parameters: parameters.clone(),
             state,
             mode,
+            optimized: parameters.optimized().is_some(),
         }
     }
 
@@ -291,14 +392,35 @@ impl<F: PrimeField> CryptographicSpongeVar<F, PoseidonSponge<F>> for PoseidonSpo
     }
 }
 
+impl<F: PrimeField> PoseidonCircomConfig<F> {
+    /// The gadget counterpart of [`PoseidonCircomConfig::hash`].
+    ///
+    /// Panics if the length of `inputs` is not `self.num_inputs()`.
+    #[tracing::instrument(target = "r1cs", skip(self, inputs))]
+    pub fn hash_var(&self, inputs: &[FpVar<F>]) -> Result<FpVar<F>, SynthesisError> {
+        assert_eq!(
+            inputs.len(),
+            self.num_inputs(),
+            "incorrect number of inputs for circomlib's Poseidon"
+        );
+
+        let mut sponge = PoseidonSpongeVar::new(inputs.cs(), &self.config);
+        sponge.state[1..].clone_from_slice(inputs);
+        sponge.permute()?;
+        Ok(sponge.state.swap_remove(0))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::sponge::constraints::CryptographicSpongeVar;
     use crate::sponge::poseidon::constraints::PoseidonSpongeVar;
     use crate::sponge::poseidon::tests::poseidon_parameters_for_test;
-    use crate::sponge::poseidon::PoseidonSponge;
+    use crate::sponge::poseidon::{PoseidonConfig, PoseidonSponge};
     use crate::sponge::test::Fr;
-    use crate::sponge::{CryptographicSponge, FieldBasedCryptographicSponge, FieldElementSize};
+    use crate::sponge::{
+        CryptographicSponge, FieldBasedCryptographicSponge, FieldElementSize, SpongeExt,
+    };
     use ark_ff::{Field, PrimeField, UniformRand};
     use ark_r1cs_std::fields::fp::FpVar;
     use ark_r1cs_std::prelude::*;
@@ -324,10 +446,12 @@ mod tests {
             .collect();
 
         let sponge_params = poseidon_parameters_for_test();
+        let permutation_cost = permutation_constraints(&sponge_params);
 
         let mut native_sponge = PoseidonSponge::<Fr>::new(&sponge_params);
         let mut constraint_sponge = PoseidonSpongeVar::<Fr>::new(cs.clone(), &sponge_params);
 
+        let num_constraints = cs.num_constraints();
         native_sponge.absorb(&absorb1);
         constraint_sponge.absorb(&absorb1_var).unwrap();
 
@@ -336,7 +460,13 @@ mod tests {
 
         assert_eq!(squeeze2.value().unwrap(), squeeze1);
         assert!(cs.is_satisfied().unwrap());
+        // 128 permutations, and the capacity element is a constant in the first S-box layer
+        assert_eq!(
+            cs.num_constraints() - num_constraints,
+            128 * permutation_cost - s_box_constraints(sponge_params.alpha)
+        );
 
+        let num_constraints = cs.num_constraints();
         native_sponge.absorb(&absorb2);
         constraint_sponge.absorb(&absorb2_var).unwrap();
 
@@ -345,6 +475,57 @@ mod tests {
 
         assert_eq!(squeeze2.value().unwrap(), squeeze1);
         assert!(cs.is_satisfied().unwrap());
+        assert_eq!(cs.num_constraints() - num_constraints, 4 * permutation_cost);
+    }
+
+    /// Number of constraints of `x^alpha`, computed by square-and-multiply.
+    fn s_box_constraints(alpha: u64) -> usize {
+        (63 - alpha.leading_zeros() + alpha.count_ones() - 1) as usize
+    }
+
+    /// Number of constraints of a permutation whose state has no constant element:
+    /// one S-box per element in the full rounds, and a single S-box in the partial rounds.
+    fn permutation_constraints(params: &PoseidonConfig<Fr>) -> usize {
+        let width = params.rate + params.capacity;
+        (params.full_rounds * width + params.partial_rounds) * s_box_constraints(params.alpha)
+    }
+
+    #[test]
+    fn permutation_constraints_test() {
+        let mut rng = test_rng();
+        let mut sponge_params = poseidon_parameters_for_test();
+        let input: Vec<_> = (0..3).map(|_| Fr::rand(&mut rng)).collect();
+
+        for optimized in [true, false] {
+            if !optimized {
+                sponge_params.set_optimized(false);
+            }
+            let cs = ConstraintSystem::new_ref();
+            let input_var: Vec<_> = input
+                .iter()
+                .map(|v| FpVar::new_witness(ns!(cs, "input"), || Ok(*v)).unwrap())
+                .collect();
+
+            let mut native_sponge = PoseidonSponge::<Fr>::new(&sponge_params);
+            let mut constraint_sponge = PoseidonSpongeVar::<Fr>::new(cs.clone(), &sponge_params);
+            constraint_sponge.state = input_var;
+            native_sponge.state = input.clone();
+
+            constraint_sponge.permute().unwrap();
+            native_sponge.permute();
+
+            assert_eq!(
+                constraint_sponge.state.value().unwrap(),
+                native_sponge.state
+            );
+            // the sparse matrices do not change the constraint count, which is already one
+            // S-box per partial round, as the linear layers are free
+            assert_eq!(
+                cs.num_constraints(),
+                permutation_constraints(&sponge_params)
+            );
+            assert!(cs.is_satisfied().unwrap());
+        }
     }
 
     #[test]
@@ -381,4 +562,78 @@ mod tests {
         let bits = &bits[0];
         assert_eq!(bits.len() as u32, Fr::MODULUS_BIT_SIZE - 1);
     }
+
+    #[test]
+    fn squeeze_unbiased() {
+        type Fq = ark_ed_on_bls12_381::Fr;
+
+        let sponge_params = poseidon_parameters_for_test();
+        let sizes = [
+            FieldElementSize::Unbiased(128),
+            FieldElementSize::Full,
+            FieldElementSize::Unbiased(300),
+        ];
+        let mut native_sponge = PoseidonSponge::<Fr>::new(&sponge_params);
+        let expected = native_sponge.squeeze_field_elements_with_sizes::<Fq>(&sizes);
+
+        let cs = ConstraintSystem::new_ref();
+        let mut constraint_sponge = PoseidonSpongeVar::<Fr>::new(cs.clone(), &sponge_params);
+        let (squeeze, bits) = constraint_sponge
+            .squeeze_emulated_field_elements_with_sizes::<Fq>(&sizes)
+            .unwrap();
+        assert_eq!(squeeze.value().unwrap(), expected);
+        assert_eq!(bits[0].len() as u32, Fq::MODULUS_BIT_SIZE + 128);
+        assert_eq!(bits[2].len() as u32, Fq::MODULUS_BIT_SIZE + 300);
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn circom_hash_test() {
+        use crate::sponge::poseidon::PoseidonCircomConfig;
+
+        let mut rng = test_rng();
+        for num_inputs in [1, 2, 5] {
+            let cs = ConstraintSystem::<ark_bn254::Fr>::new_ref();
+            let config = PoseidonCircomConfig::<ark_bn254::Fr>::new(num_inputs).unwrap();
+
+            let inputs: Vec<_> = (0..num_inputs)
+                .map(|_| ark_bn254::Fr::rand(&mut rng))
+                .collect();
+            let inputs_var: Vec<_> = inputs
+                .iter()
+                .map(|v| FpVar::new_witness(ns!(cs, "input"), || Ok(*v)).unwrap())
+                .collect();
+
+            let hash = config.hash_var(&inputs_var).unwrap();
+            assert_eq!(hash.value().unwrap(), config.hash(&inputs));
+            assert!(cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn from_state_test() {
+        let mut rng = test_rng();
+        let cs = ConstraintSystem::new_ref();
+        let sponge_params = poseidon_parameters_for_test();
+
+        let absorb: Vec<_> = (0..5).map(|_| Fr::rand(&mut rng)).collect();
+        let mut native_sponge = PoseidonSponge::<Fr>::new(&sponge_params);
+        native_sponge.absorb(&absorb);
+
+        let checkpoint = native_sponge.clone().into_state();
+        let state_var =
+            Vec::<FpVar<Fr>>::new_witness(ns!(cs, "state"), || Ok(checkpoint.state.clone()))
+                .unwrap();
+        let mut constraint_sponge = PoseidonSpongeVar::<Fr>::from_state(
+            cs.clone(),
+            &sponge_params,
+            state_var,
+            checkpoint.mode,
+        );
+
+        let squeeze = native_sponge.squeeze_native_field_elements(2);
+        let squeeze_var = constraint_sponge.squeeze_field_elements(2).unwrap();
+        assert_eq!(squeeze_var.value().unwrap(), squeeze);
+        assert!(cs.is_satisfied().unwrap());
+    }
 }
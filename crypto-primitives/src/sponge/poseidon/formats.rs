@@ -0,0 +1,511 @@
+use crate::sponge::poseidon::PoseidonConfig;
+use crate::Error;
+use ark_ff::{BigInteger, PrimeField};
+use ark_std::fmt::Write;
+#[cfg(not(feature = "std"))]
+use ark_std::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+impl<F: PrimeField> PoseidonConfig<F> {
+    /// Exports the parameters in the output format of the [reference implementation][sage]:
+    /// the round constants and the MDS matrix are written as lists of hexadecimal numbers.
+    ///
+    /// The configuration does not record the security level, so it is given as
+    /// `security_level`, and written as the `M` parameter.
+    ///
+    /// [sage]: https://extgit.iaik.tugraz.at/krypto/hadeshash/-/blob/master/code/generate_parameters_grain.sage
+    pub fn to_sage_output(&self, security_level: u32) -> String {
+        let t = self.rate + self.capacity;
+        let quoted = |row: &[F]| {
+            row.iter()
+                .map(|x| format!("'{}'", to_hex(x)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        let mut output = String::new();
+        let _ = writeln!(
+            output,
+            "Params: n={}, t={}, alpha={}, M={}, R_F={}, R_P={}",
+            F::MODULUS_BIT_SIZE,
+            t,
+            self.alpha,
+            security_level,
+            self.full_rounds,
+            self.partial_rounds
+        );
+        let _ = writeln!(output, "Number of round constants: {}", self.ark.len() * t);
+        let _ = writeln!(output, "Round constants for GF(p):");
+        let _ = writeln!(output, "[{}]", quoted(&self.ark.concat()));
+        let _ = writeln!(output, "n: {}", F::MODULUS_BIT_SIZE);
+        let _ = writeln!(output, "t: {}", t);
+        let _ = writeln!(output, "N: {}", F::MODULUS_BIT_SIZE as usize * t);
+        let _ = writeln!(output, "Prime number: 0x{}", hex_digits(&F::MODULUS));
+        let rows = self
+            .mds
+            .iter()
+            .map(|row| format!("[{}]", quoted(row)))
+            .collect::<Vec<_>>();
+        let _ = writeln!(output, "MDS matrix:\n [{}]", rows.join(","));
+        output
+    }
+
+    /// Imports parameters exported by [`to_sage_output`](Self::to_sage_output) or by the
+    /// reference implementation, for the given rate and capacity.
+    ///
+    /// The state width, the number of rounds and the exponent are read from the `Params` line;
+    /// the other parameters of that line, such as the security level `M`, are ignored.
+    /// Returns an error if `rate + capacity` is zero, if the dimensions of the round constants
+    /// and of the MDS matrix do not match them, if the width is not `rate + capacity`, or if
+    /// the prime number differs from the modulus of `F`.
+    ///
+    /// The lists following `Round constants for GF(p):` and `MDS matrix:` are read with the
+    /// parser described in [`from_json`](Self::from_json), which accepts the single-quoted
+    /// strings of Python. Only the first occurrence of each label is used, and the text after
+    /// the lists is not checked.
+    pub fn from_sage_output(output: &str, rate: usize, capacity: usize) -> Result<Self, Error> {
+        let width = state_width(rate, capacity)?;
+        let params = output
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("Params:"))
+            .ok_or_else(|| invalid("missing `Params` line"))?;
+        let param = |name: &str| -> Result<usize, Error> {
+            params
+                .split(',')
+                .filter_map(|item| item.trim().split_once('='))
+                .find(|(key, _)| key.trim() == name)
+                .ok_or_else(|| invalid(format!("missing parameter `{name}`")))?
+                .1
+                .trim()
+                .parse()
+                .map_err(|_| invalid(format!("invalid parameter `{name}`")))
+        };
+        let t = param("t")?;
+        if t != width {
+            return Err(invalid(format!(
+                "the state width is {t}, but rate + capacity is {width}"
+            )));
+        }
+
+        if let Some(prime) = output
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("Prime number:"))
+        {
+            if parse_hex_bigint::<F>(prime.trim()) != Some(F::MODULUS) {
+                return Err(invalid("the prime number is not the modulus of the field"));
+            }
+        }
+
+        let after = |label: &str| -> Result<Value, Error> {
+            let start = output
+                .find(label)
+                .ok_or_else(|| invalid(format!("missing `{label}`")))?;
+            Parser::new(&output[start + label.len()..]).parse_value()
+        };
+        let constants = to_elements(after("Round constants for GF(p):")?)?;
+        let mds = to_matrix(after("MDS matrix:")?)?;
+        let ark = constants.chunks(t).map(|row| row.to_vec()).collect();
+
+        Self::checked_new(
+            param("R_F")?,
+            param("R_P")?,
+            param("alpha")? as u64,
+            mds,
+            ark,
+            rate,
+            capacity,
+        )
+    }
+
+    /// Exports the parameters as a JSON object, with the field elements as hexadecimal strings.
+    pub fn to_json(&self) -> String {
+        let row = |row: &[F]| {
+            let elements = row
+                .iter()
+                .map(|x| format!("\"{}\"", to_hex(x)))
+                .collect::<Vec<_>>();
+            format!("[{}]", elements.join(","))
+        };
+        let matrix = |m: &[Vec<F>]| {
+            let rows = m.iter().map(|r| row(r)).collect::<Vec<_>>();
+            format!("[{}]", rows.join(","))
+        };
+        format!(
+            "{{\"rate\":{},\"capacity\":{},\"alpha\":{},\"full_rounds\":{},\"partial_rounds\":{},\"ark\":{},\"mds\":{}}}",
+            self.rate,
+            self.capacity,
+            self.alpha,
+            self.full_rounds,
+            self.partial_rounds,
+            matrix(&self.ark),
+            matrix(&self.mds)
+        )
+    }
+
+    /// Imports parameters exported by [`to_json`](Self::to_json).
+    ///
+    /// Returns an error if `rate + capacity` is zero, or if the dimensions of the round
+    /// constants and of the MDS matrix do not match `full_rounds`, `partial_rounds`, `rate` and
+    /// `capacity`.
+    ///
+    /// The input is read by a minimal parser which only supports what `to_json` writes:
+    /// - strings may not contain escape sequences, and are used as written;
+    /// - numbers must be non-negative integers that fit in a `u64`, without sign, fraction or
+    ///   exponent;
+    /// - `true`, `false` and `null` are rejected;
+    /// - fields other than the seven written by `to_json` are ignored, and the first
+    ///   occurrence of a repeated field is used.
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        let mut parser = Parser::new(json);
+        let mut fields = match parser.parse_value()? {
+            Value::Object(fields) => fields,
+            _ => return Err(invalid("expected a JSON object")),
+        };
+        parser.expect_end()?;
+
+        let mut field = |name: &str| {
+            let index = fields
+                .iter()
+                .position(|(key, _)| key == name)
+                .ok_or_else(|| invalid(format!("missing field `{name}`")))?;
+            Ok::<_, Error>(fields.swap_remove(index).1)
+        };
+        let mut number = |name: &str| match field(name)? {
+            Value::Number(n) => Ok(n),
+            _ => Err(invalid(format!("`{name}` is not a number"))),
+        };
+        let mut size = |name: &str| {
+            usize::try_from(number(name)?)
+                .map_err(|_| invalid(format!("`{name}` does not fit in a usize")))
+        };
+        let rate = size("rate")?;
+        let capacity = size("capacity")?;
+        let full_rounds = size("full_rounds")?;
+        let partial_rounds = size("partial_rounds")?;
+        let alpha = number("alpha")?;
+        let ark = to_matrix(field("ark")?)?;
+        let mds = to_matrix(field("mds")?)?;
+
+        Self::checked_new(full_rounds, partial_rounds, alpha, mds, ark, rate, capacity)
+    }
+
+    /// Calls [`new`](Self::new), after checking the dimensions of `ark` and `mds`.
+    fn checked_new(
+        full_rounds: usize,
+        partial_rounds: usize,
+        alpha: u64,
+        mds: Vec<Vec<F>>,
+        ark: Vec<Vec<F>>,
+        rate: usize,
+        capacity: usize,
+    ) -> Result<Self, Error> {
+        let t = state_width(rate, capacity)?;
+        let rounds = full_rounds
+            .checked_add(partial_rounds)
+            .ok_or_else(|| invalid("the number of rounds overflows a usize"))?;
+        if ark.len() != rounds || ark.iter().any(|row| row.len() != t) {
+            return Err(invalid(format!("expected {rounds}x{t} round constants")));
+        }
+        if mds.len() != t || mds.iter().any(|row| row.len() != t) {
+            return Err(invalid(format!("expected a {t}x{t} MDS matrix")));
+        }
+        Ok(Self::new(
+            full_rounds,
+            partial_rounds,
+            alpha,
+            mds,
+            ark,
+            rate,
+            capacity,
+        ))
+    }
+}
+
+fn invalid(msg: impl ToString) -> Error {
+    Error::InvalidParameters(msg.to_string())
+}
+
+/// Returns `rate + capacity`, or an error if it is zero or overflows.
+fn state_width(rate: usize, capacity: usize) -> Result<usize, Error> {
+    match rate.checked_add(capacity) {
+        Some(0) => Err(invalid("the state width must be positive")),
+        Some(t) => Ok(t),
+        None => Err(invalid("the state width overflows a usize")),
+    }
+}
+
+/// Writes `x` in hexadecimal, padded to the size of the modulus as in the reference
+/// implementation.
+fn to_hex<F: PrimeField>(x: &F) -> String {
+    let digits = hex_digits(&x.into_bigint());
+    let width = (F::MODULUS_BIT_SIZE as usize).div_ceil(4);
+    format!("0x{digits:0>width$}")
+}
+
+fn hex_digits(x: &impl BigInteger) -> String {
+    let digits = x
+        .to_bytes_be()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>();
+    let digits = digits.trim_start_matches('0');
+    if digits.is_empty() {
+        "0".into()
+    } else {
+        digits.into()
+    }
+}
+
+fn parse_hex_bigint<F: PrimeField>(s: &str) -> Option<F::BigInt> {
+    let digits = s.strip_prefix("0x")?.trim_start_matches('0');
+    let mut bits = Vec::with_capacity(4 * digits.len());
+    for c in digits.chars() {
+        let digit = c.to_digit(16)?;
+        bits.extend((0..4).rev().map(|i| (digit >> i) & 1 == 1));
+    }
+    if bits.len() > 64 * F::BigInt::NUM_LIMBS {
+        return None;
+    }
+    Some(F::BigInt::from_bits_be(&bits))
+}
+
+fn to_elements<F: PrimeField>(value: Value) -> Result<Vec<F>, Error> {
+    match value {
+        Value::Array(values) => values
+            .into_iter()
+            .map(|value| match value {
+                Value::String(s) => parse_hex_bigint::<F>(&s)
+                    .and_then(F::from_bigint)
+                    .ok_or_else(|| invalid(format!("`{s}` is not a field element"))),
+                _ => Err(invalid("expected a field element")),
+            })
+            .collect(),
+        _ => Err(invalid("expected a list of field elements")),
+    }
+}
+
+fn to_matrix<F: PrimeField>(value: Value) -> Result<Vec<Vec<F>>, Error> {
+    match value {
+        Value::Array(rows) => rows.into_iter().map(to_elements).collect(),
+        _ => Err(invalid("expected a matrix of field elements")),
+    }
+}
+
+/// The values of the JSON format, and of the Python lists of the reference implementation,
+/// restricted as described in [`PoseidonConfig::from_json`].
+enum Value {
+    String(String),
+    Number(u64),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+struct Parser<'a> {
+    input: &'a str,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Self { input }
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.input = self.input.trim_start();
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.input.chars().next()
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), Error> {
+        if self.peek() == Some(c) {
+            self.input = &self.input[1..];
+            Ok(())
+        } else {
+            Err(invalid(format!("expected `{c}`")))
+        }
+    }
+
+    fn expect_end(&mut self) -> Result<(), Error> {
+        match self.peek() {
+            None => Ok(()),
+            Some(_) => Err(invalid("unexpected trailing characters")),
+        }
+    }
+
+    /// Parses the value at the beginning of the input.
+    fn parse_value(&mut self) -> Result<Value, Error> {
+        match self.peek() {
+            Some('[') => {
+                let items = self.parse_sequence('[', ']', Self::parse_value)?;
+                Ok(Value::Array(items))
+            }
+            Some('{') => {
+                let fields = self.parse_sequence('{', '}', |parser| {
+                    let key = parser.parse_string()?;
+                    parser.expect(':')?;
+                    Ok((key, parser.parse_value()?))
+                })?;
+                Ok(Value::Object(fields))
+            }
+            Some('"') | Some('\'') => Ok(Value::String(self.parse_string()?)),
+            Some(c) if c.is_ascii_digit() => {
+                let end = self
+                    .input
+                    .find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(self.input.len());
+                let number = self.input[..end]
+                    .parse()
+                    .map_err(|_| invalid("invalid number"))?;
+                self.input = &self.input[end..];
+                Ok(Value::Number(number))
+            }
+            _ => Err(invalid("expected a value")),
+        }
+    }
+
+    fn parse_sequence<T>(
+        &mut self,
+        open: char,
+        close: char,
+        mut item: impl FnMut(&mut Self) -> Result<T, Error>,
+    ) -> Result<Vec<T>, Error> {
+        self.expect(open)?;
+        let mut items = Vec::new();
+        if self.peek() == Some(close) {
+            self.input = &self.input[1..];
+            return Ok(items);
+        }
+        loop {
+            items.push(item(self)?);
+            if self.peek() == Some(',') {
+                self.input = &self.input[1..];
+            } else {
+                self.expect(close)?;
+                return Ok(items);
+            }
+        }
+    }
+
+    /// Parses a string between double or single quotes, without escape sequences.
+    fn parse_string(&mut self) -> Result<String, Error> {
+        let quote = match self.peek() {
+            Some(c @ ('"' | '\'')) => c,
+            _ => return Err(invalid("expected a string")),
+        };
+        let end = self.input[1..]
+            .find(quote)
+            .ok_or_else(|| invalid("unterminated string"))?;
+        let s = self.input[1..=end].to_string();
+        self.input = &self.input[end + 2..];
+        Ok(s)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::sponge::poseidon::{PoseidonConfig, PoseidonDefaultConfigField};
+    use crate::sponge::test::Fr;
+    use crate::Error;
+
+    fn assert_same(a: &PoseidonConfig<Fr>, b: &PoseidonConfig<Fr>) {
+        assert_eq!(
+            (a.full_rounds, a.partial_rounds, a.alpha, a.rate, a.capacity),
+            (b.full_rounds, b.partial_rounds, b.alpha, b.rate, b.capacity)
+        );
+        assert_eq!(a.ark, b.ark);
+        assert_eq!(a.mds, b.mds);
+    }
+
+    #[test]
+    fn sage_output_roundtrip() {
+        let config = Fr::get_default_poseidon_parameters(2, false).unwrap();
+        let output = config.to_sage_output(128);
+        assert!(output.starts_with("Params: n=255, t=3, alpha=17, M=128, R_F=8, R_P=31\n"));
+        assert_same(
+            &PoseidonConfig::from_sage_output(&output, 2, 1).unwrap(),
+            &config,
+        );
+        assert!(matches!(
+            PoseidonConfig::<Fr>::from_sage_output(&output, 3, 1),
+            Err(Error::InvalidParameters(_))
+        ));
+        assert!(matches!(
+            PoseidonConfig::<ark_bn254::Fr>::from_sage_output(&output, 2, 1),
+            Err(Error::InvalidParameters(_))
+        ));
+        assert!(matches!(
+            PoseidonConfig::<Fr>::from_sage_output(&output.replace("t=3", "t=0"), 0, 0),
+            Err(Error::InvalidParameters(_))
+        ));
+    }
+
+    #[test]
+    fn sage_output_import() {
+        let output = "Params: n=255, t=2, alpha=5, M=128, R_F=2, R_P=1
+Modulus = 52435875175126190479447740508185965837690552500527637822603658699938581184513
+Number of round constants: 6
+Round constants for GF(p):
+['0x0000000000000000000000000000000000000000000000000000000000000001', '0x0000000000000000000000000000000000000000000000000000000000000002', '0x0000000000000000000000000000000000000000000000000000000000000003', '0x0000000000000000000000000000000000000000000000000000000000000004', '0x0000000000000000000000000000000000000000000000000000000000000005', '0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000000']
+n: 255
+t: 2
+N: 510
+Result Algorithm 1:
+ [True, 0]
+Result Algorithm 2:
+ [True, None]
+Result Algorithm 3:
+ [True, None]
+Prime number: 0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001
+MDS matrix:
+ [['0x0000000000000000000000000000000000000000000000000000000000000007', '0x0000000000000000000000000000000000000000000000000000000000000008'],['0x0000000000000000000000000000000000000000000000000000000000000009', '0x000000000000000000000000000000000000000000000000000000000000000a']]
+";
+        let config = PoseidonConfig::<Fr>::from_sage_output(output, 1, 1).unwrap();
+        assert_eq!((config.full_rounds, config.partial_rounds), (2, 1));
+        assert_eq!(config.alpha, 5);
+        assert_eq!(config.ark[1], vec![Fr::from(3), Fr::from(4)]);
+        assert_eq!(config.ark[2][1], -Fr::from(1));
+        assert_eq!(config.mds[1], vec![Fr::from(9), Fr::from(10)]);
+
+        // the number of partial rounds does not match the round constants
+        let wrong_rounds = output.replace("R_P=1", "R_P=2");
+        assert!(PoseidonConfig::<Fr>::from_sage_output(&wrong_rounds, 1, 1).is_err());
+        // the last round constant is not reduced
+        let unreduced = output.replace("ffffffff00000000'", "ffffffff00000001'");
+        assert!(PoseidonConfig::<Fr>::from_sage_output(&unreduced, 1, 1).is_err());
+    }
+
+    #[test]
+    fn json_roundtrip() {
+        let config = PoseidonConfig::<Fr>::generate(2, 2, 5, 8, 57, 0).unwrap();
+        let json = config.to_json();
+        assert_same(&PoseidonConfig::from_json(&json).unwrap(), &config);
+
+        let wrong_capacity = json.replace("\"capacity\":2", "\"capacity\":1");
+        assert!(matches!(
+            PoseidonConfig::<Fr>::from_json(&wrong_capacity),
+            Err(Error::InvalidParameters(_))
+        ));
+        let wrong_rounds = json.replace("\"full_rounds\":8", "\"full_rounds\":6");
+        assert!(PoseidonConfig::<Fr>::from_json(&wrong_rounds).is_err());
+        assert!(PoseidonConfig::<Fr>::from_json(&json[..json.len() - 1]).is_err());
+
+        let max = u64::MAX.to_string();
+        for overflow in [
+            json.replace("\"rate\":2", &format!("\"rate\":{max}")),
+            json.replace(
+                "\"partial_rounds\":57",
+                &format!("\"partial_rounds\":{max}"),
+            ),
+        ] {
+            assert!(matches!(
+                PoseidonConfig::<Fr>::from_json(&overflow),
+                Err(Error::InvalidParameters(_))
+            ));
+        }
+    }
+}
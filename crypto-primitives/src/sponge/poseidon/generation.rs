@@ -0,0 +1,241 @@
+use crate::sponge::poseidon::round_numbers::is_valid_alpha;
+use crate::sponge::poseidon::traits::sample_poseidon_ark_and_cauchy_points;
+use crate::sponge::poseidon::PoseidonConfig;
+use crate::Error;
+use ark_ff::PrimeField;
+#[cfg(not(feature = "std"))]
+use ark_std::{format, vec::Vec};
+
+impl<F: PrimeField> PoseidonConfig<F> {
+    /// Generates the Poseidon parameters of the given dimensions and round numbers, with the
+    /// round constants and MDS matrix of the [reference implementation][sage]: they are sampled
+    /// from the Grain LFSR, after skipping `skip_matrices` MDS matrices.
+    ///
+    /// Returns an error if `alpha < 3`, if `x^alpha` is not a permutation of `F`, i.e. if
+    /// `gcd(alpha, p - 1) != 1`, or if the MDS matrix is unsuitable. The MDS matrix must be a
+    /// Cauchy matrix, so that it is invertible and MDS, and `M^i` must not have invariant
+    /// subspaces preserved by the partial rounds for `1 <= i <= 2 * (rate + capacity)`, so that
+    /// there are no infinitely long invariant subspace trails.
+    /// When the matrix fails these checks, the next ones can be tried by increasing
+    /// `skip_matrices`.
+    ///
+    /// The round numbers are not checked. Secure ones are computed by
+    /// [`find_poseidon_round_numbers`](super::find_poseidon_round_numbers).
+    ///
+    /// [sage]: https://extgit.iaik.tugraz.at/krypto/hadeshash/-/blob/master/code/generate_parameters_grain.sage
+    pub fn generate(
+        rate: usize,
+        capacity: usize,
+        alpha: u64,
+        full_rounds: usize,
+        partial_rounds: usize,
+        skip_matrices: usize,
+    ) -> Result<Self, Error> {
+        let width = rate + capacity;
+        if rate == 0 || capacity == 0 {
+            return Err(Error::InvalidParameters(
+                "the rate and the capacity must be positive".into(),
+            ));
+        }
+        // sizes of the fields of the Grain LFSR initial state
+        if width >= 1 << 12 || full_rounds >= 1 << 10 || partial_rounds >= 1 << 10 {
+            return Err(Error::InvalidParameters(
+                "the state width or the round numbers are too large".into(),
+            ));
+        }
+        if full_rounds % 2 == 1 {
+            return Err(Error::InvalidParameters(format!(
+                "the number of full rounds must be even, got {full_rounds}"
+            )));
+        }
+        if alpha < 3 {
+            return Err(Error::InvalidParameters(format!(
+                "alpha must be at least 3, got {alpha}"
+            )));
+        }
+        if !is_valid_alpha::<F>(alpha) {
+            return Err(Error::InvalidParameters(format!(
+                "x^{alpha} is not a permutation of the field: gcd(alpha, p - 1) != 1"
+            )));
+        }
+
+        let (ark, xs, ys) = sample_poseidon_ark_and_cauchy_points::<F>(
+            F::MODULUS_BIT_SIZE as u64,
+            width,
+            full_rounds as u64,
+            partial_rounds as u64,
+            skip_matrices as u64,
+        );
+
+        let has_duplicates = |v: &[F]| (0..v.len()).any(|i| v[i + 1..].contains(&v[i]));
+        if has_duplicates(&xs) || has_duplicates(&ys) {
+            return Err(Error::InvalidParameters(
+                "the MDS matrix is not a Cauchy matrix: duplicate points".into(),
+            ));
+        }
+        let mut mds = Vec::with_capacity(width);
+        for x in &xs {
+            let row = ys
+                .iter()
+                .map(|y| (*x + y).inverse())
+                .collect::<Option<Vec<_>>>()
+                .ok_or_else(|| {
+                    Error::InvalidParameters(
+                        "the MDS matrix is not a Cauchy matrix: x_i + y_j = 0".into(),
+                    )
+                })?;
+            mds.push(row);
+        }
+
+        let mut power = mds.clone();
+        for i in 1..=2 * width {
+            if has_invariant_subspace(&power) {
+                return Err(Error::InvalidParameters(format!(
+                    "the MDS matrix has invariant subspace trails: M^{i} has an invariant \
+                     subspace, try another `skip_matrices`"
+                )));
+            }
+            power = mat_mul(&power, &mds);
+        }
+
+        Ok(Self::new(
+            full_rounds,
+            partial_rounds,
+            alpha,
+            mds,
+            ark,
+            rate,
+            capacity,
+        ))
+    }
+}
+
+fn mat_mul<F: PrimeField>(a: &[Vec<F>], b: &[Vec<F>]) -> Vec<Vec<F>> {
+    a.iter()
+        .map(|row| {
+            (0..b[0].len())
+                .map(|j| row.iter().zip(b).map(|(a, b_row)| *a * b_row[j]).sum())
+                .collect()
+        })
+        .collect()
+}
+
+/// Returns whether `m` has a nontrivial invariant subspace that either contains `e_0`, or is
+/// contained in the hyperplane `x_0 = 0`, i.e. that the partial S-box layer also preserves.
+///
+/// The smallest invariant subspace containing `e_0` is spanned by the `m^j e_0`, and the
+/// largest one contained in the hyperplane is the kernel of the rows `e_0^T m^j`, for `j < t`.
+fn has_invariant_subspace<F: PrimeField>(m: &[Vec<F>]) -> bool {
+    let t = m.len();
+    let mut columns = Vec::with_capacity(t);
+    let mut rows = Vec::with_capacity(t);
+    let mut column: Vec<F> = (0..t)
+        .map(|i| if i == 0 { F::one() } else { F::zero() })
+        .collect();
+    let mut row = column.clone();
+    for _ in 0..t {
+        let next_column = m.iter().map(|m_row| dot(m_row, &column)).collect();
+        let next_row = (0..t)
+            .map(|j| row.iter().zip(m).map(|(r, m_row)| *r * m_row[j]).sum())
+            .collect();
+        columns.push(column);
+        rows.push(row);
+        column = next_column;
+        row = next_row;
+    }
+    rank(columns) < t || rank(rows) < t
+}
+
+fn dot<F: PrimeField>(a: &[F], b: &[F]) -> F {
+    a.iter().zip(b).map(|(a, b)| *a * b).sum()
+}
+
+/// Computes the rank of the matrix of the given rows, by Gaussian elimination.
+fn rank<F: PrimeField>(mut rows: Vec<Vec<F>>) -> usize {
+    let mut rank = 0;
+    for col in 0..rows.first().map_or(0, Vec::len) {
+        let pivot = match (rank..rows.len()).find(|&i| !rows[i][col].is_zero()) {
+            Some(pivot) => pivot,
+            None => continue,
+        };
+        rows.swap(rank, pivot);
+        let inv = rows[rank][col].inverse().unwrap();
+        for i in (rank + 1)..rows.len() {
+            let factor = rows[i][col] * inv;
+            for j in col..rows[i].len() {
+                let sub = rows[rank][j] * factor;
+                rows[i][j] -= sub;
+            }
+        }
+        rank += 1;
+    }
+    rank
+}
+
+#[cfg(test)]
+mod test {
+    use super::has_invariant_subspace;
+    use crate::sponge::poseidon::{PoseidonConfig, PoseidonDefaultConfigField};
+    use crate::sponge::test::Fr;
+    use crate::Error;
+
+    #[test]
+    fn generate_matches_default_parameters() {
+        let config = PoseidonConfig::<Fr>::generate(2, 1, 17, 8, 31, 0).unwrap();
+        let default = Fr::get_default_poseidon_parameters(2, false).unwrap();
+        assert_eq!(config.ark, default.ark);
+        assert_eq!(config.mds, default.mds);
+
+        let config = PoseidonConfig::<Fr>::generate(2, 2, 5, 8, 57, 0).unwrap();
+        assert_eq!(config.mds.len(), 4);
+        assert_eq!(config.ark.len(), 65);
+    }
+
+    #[test]
+    fn invariant_subspaces() {
+        let m = |rows: [[u64; 3]; 3]| -> Vec<Vec<Fr>> {
+            rows.iter()
+                .map(|row| row.iter().map(|x| Fr::from(*x)).collect())
+                .collect()
+        };
+        assert!(!has_invariant_subspace(&m([
+            [2, 3, 4],
+            [5, 7, 11],
+            [13, 17, 19]
+        ])));
+        // span(e_0, e_1) is invariant
+        assert!(has_invariant_subspace(&m([
+            [1, 2, 0],
+            [3, 4, 0],
+            [5, 6, 7]
+        ])));
+        // span(e_2) is invariant and contained in x_0 = 0
+        assert!(has_invariant_subspace(&m([
+            [1, 2, 0],
+            [3, 4, 0],
+            [0, 0, 7]
+        ])));
+    }
+
+    #[test]
+    fn generate_rejects_invalid_alpha() {
+        // 3 divides p - 1
+        assert!(matches!(
+            PoseidonConfig::<Fr>::generate(2, 1, 3, 8, 57, 0),
+            Err(Error::InvalidParameters(_))
+        ));
+        // x^1 is a permutation, but not a non-linear one
+        assert!(matches!(
+            PoseidonConfig::<Fr>::generate(2, 1, 1, 8, 57, 0),
+            Err(Error::InvalidParameters(message)) if message.contains("at least 3")
+        ));
+    }
+
+    #[test]
+    fn generate_rejects_odd_full_rounds() {
+        assert!(matches!(
+            PoseidonConfig::<Fr>::generate(2, 1, 5, 7, 57, 0),
+            Err(Error::InvalidParameters(_))
+        ));
+    }
+}
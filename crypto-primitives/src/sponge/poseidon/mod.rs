@@ -3,10 +3,14 @@ use crate::sponge::{
     DuplexSpongeMode, FieldBasedCryptographicSponge, FieldElementSize, SpongeExt,
 };
 use ark_ff::{BigInteger, PrimeField};
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, SerializationError, Valid, Validate,
+};
 use ark_std::any::TypeId;
+use ark_std::io::{Read, Write};
 #[cfg(not(feature = "std"))]
 use ark_std::vec::Vec;
+use sha2::{Digest, Sha256};
 
 /// constraints for Poseidon
 #[cfg(feature = "r1cs")]
@@ -22,8 +26,24 @@ pub use traits::*;
 
 mod grain_lfsr;
 
+mod round_numbers;
+pub use round_numbers::find_poseidon_round_numbers;
+pub(crate) use round_numbers::{is_valid_alpha, secure_poseidon_config};
+
+mod generation;
+
+mod formats;
+
+/// precomputed form of the Poseidon parameters for faster partial rounds
+pub mod optimized;
+pub use optimized::*;
+
+/// the Poseidon hash of circomlib
+pub mod circom;
+pub use circom::*;
+
 /// Config and RNG used
-#[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
+#[derive(Clone, Debug)]
 pub struct PoseidonConfig<F: PrimeField> {
     /// Number of rounds in a full-round operation.
     pub full_rounds: usize,
@@ -42,6 +62,16 @@ pub struct PoseidonConfig<F: PrimeField> {
     pub rate: usize,
     /// The capacity (in terms of number of field elements).
     pub capacity: usize,
+    /// The optimized form of the config, computed by [`PoseidonConfig::new`] and not
+    /// serialized. It is only used while the config is unchanged, see [`Self::optimized`].
+    optimized: Option<OptimizedCache<F>>,
+}
+
+/// A [`PoseidonOptimizedConfig`], with the fingerprint of the config it was computed from.
+#[derive(Clone, Debug)]
+struct OptimizedCache<F: PrimeField> {
+    fingerprint: [u8; 32],
+    config: PoseidonOptimizedConfig<F>,
 }
 
 #[derive(Clone)]
@@ -60,6 +90,8 @@ pub struct PoseidonSponge<F: PrimeField> {
     pub state: Vec<F>,
     /// Current mode (whether its absorbing or squeezing)
     pub mode: DuplexSpongeMode,
+    /// Whether the optimized form of the parameters was valid when the sponge was created.
+    optimized: bool,
 }
 
 impl<F: PrimeField> PoseidonSponge<F> {
@@ -95,7 +127,20 @@ impl<F: PrimeField> PoseidonSponge<F> {
         state.clone_from_slice(&new_state[..state.len()])
     }
 
-    fn permute(&mut self) {
+    /// Resets the sponge to its initial state, as returned by `new`, without cloning the parameters.
+    pub(crate) fn reset(&mut self) {
+        self.state.iter_mut().for_each(|elem| *elem = F::zero());
+        self.mode = DuplexSpongeMode::Absorbing {
+            next_absorb_index: 0,
+        };
+    }
+
+    pub(crate) fn permute(&mut self) {
+        if let Some(optimized) = self.parameters.cached_optimized(self.optimized) {
+            optimized.permute(&mut self.state);
+            return;
+        }
+
         let full_rounds_over_2 = self.parameters.full_rounds / 2;
         let mut state = self.state.clone();
         for i in 0..full_rounds_over_2 {
@@ -205,7 +250,7 @@ impl<F: PrimeField> PoseidonConfig<F> {
         for item in &mds {
             assert_eq!(item.len(), rate + capacity);
         }
-        Self {
+        let mut config = Self {
             full_rounds,
             partial_rounds,
             alpha,
@@ -213,7 +258,137 @@ impl<F: PrimeField> PoseidonConfig<F> {
             ark,
             rate,
             capacity,
+            optimized: None,
+        };
+        config.set_optimized(true);
+        config
+    }
+
+    /// Returns the optimized form of the config, which `PoseidonSponge` and its gadget use in
+    /// place of `ark` and `mds`.
+    ///
+    /// Returns `None` if it was removed by [`Self::set_optimized`], if the config has no
+    /// optimized form, or if the public fields were modified since it was computed, so that a
+    /// stale optimized form is never used.
+    ///
+    /// This hashes the round constants and the MDS matrix, so the sponges call it once, when
+    /// they are created, and not on every permutation.
+    pub fn optimized(&self) -> Option<&PoseidonOptimizedConfig<F>> {
+        self.optimized
+            .as_ref()
+            .filter(|cache| cache.fingerprint == self.fingerprint())
+            .map(|cache| &cache.config)
+    }
+
+    /// Returns the optimized form if `valid`, the result of an earlier call to
+    /// [`Self::optimized`], without checking it again.
+    pub(crate) fn cached_optimized(&self, valid: bool) -> Option<&PoseidonOptimizedConfig<F>> {
+        self.optimized
+            .as_ref()
+            .filter(|_| valid)
+            .map(|cache| &cache.config)
+    }
+
+    /// Returns a hash of the fields from which the optimized form is computed.
+    fn fingerprint(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update((self.full_rounds as u64).to_le_bytes());
+        hasher.update((self.partial_rounds as u64).to_le_bytes());
+        hasher.update(self.alpha.to_le_bytes());
+        for row in self.ark.iter().chain(&self.mds) {
+            hasher.update((row.len() as u64).to_le_bytes());
+            for elem in row {
+                hasher.update(elem.into_bigint().to_bytes_le());
+            }
+        }
+        hasher.finalize().into()
+    }
+
+    /// Recomputes the optimized form of the current fields if `enabled`, or removes it, so that
+    /// the reference permutation is used.
+    pub fn set_optimized(&mut self, enabled: bool) {
+        self.optimized = None;
+        if enabled {
+            self.optimized = PoseidonOptimizedConfig::new(self).map(|config| OptimizedCache {
+                fingerprint: self.fingerprint(),
+                config,
+            });
+        }
+    }
+}
+
+impl<F: PrimeField> CanonicalSerialize for PoseidonConfig<F> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.full_rounds
+            .serialize_with_mode(&mut writer, compress)?;
+        self.partial_rounds
+            .serialize_with_mode(&mut writer, compress)?;
+        self.alpha.serialize_with_mode(&mut writer, compress)?;
+        self.ark.serialize_with_mode(&mut writer, compress)?;
+        self.mds.serialize_with_mode(&mut writer, compress)?;
+        self.rate.serialize_with_mode(&mut writer, compress)?;
+        self.capacity.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.full_rounds.serialized_size(compress)
+            + self.partial_rounds.serialized_size(compress)
+            + self.alpha.serialized_size(compress)
+            + self.ark.serialized_size(compress)
+            + self.mds.serialized_size(compress)
+            + self.rate.serialized_size(compress)
+            + self.capacity.serialized_size(compress)
+    }
+}
+
+impl<F: PrimeField> Valid for PoseidonConfig<F> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.ark.check()?;
+        self.mds.check()
+    }
+}
+
+impl<F: PrimeField> CanonicalDeserialize for PoseidonConfig<F> {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let full_rounds = usize::deserialize_with_mode(&mut reader, compress, validate)?;
+        let partial_rounds = usize::deserialize_with_mode(&mut reader, compress, validate)?;
+        let alpha = u64::deserialize_with_mode(&mut reader, compress, validate)?;
+        let ark = Vec::deserialize_with_mode(&mut reader, compress, validate)?;
+        let mds = Vec::deserialize_with_mode(&mut reader, compress, validate)?;
+        let rate = usize::deserialize_with_mode(&mut reader, compress, validate)?;
+        let capacity = usize::deserialize_with_mode(&mut reader, compress, validate)?;
+
+        let t = rate
+            .checked_add(capacity)
+            .ok_or(SerializationError::InvalidData)?;
+        let rounds = full_rounds
+            .checked_add(partial_rounds)
+            .ok_or(SerializationError::InvalidData)?;
+        if ark.len() != rounds
+            || ark.iter().any(|row: &Vec<F>| row.len() != t)
+            || mds.len() != t
+            || mds.iter().any(|row: &Vec<F>| row.len() != t)
+        {
+            return Err(SerializationError::InvalidData);
         }
+
+        Ok(Self::new(
+            full_rounds,
+            partial_rounds,
+            alpha,
+            mds,
+            ark,
+            rate,
+            capacity,
+        ))
     }
 }
 
@@ -230,6 +405,7 @@ impl<F: PrimeField> CryptographicSponge for PoseidonSponge<F> {
             parameters: parameters.clone(),
             state,
             mode,
+            optimized: parameters.optimized().is_some(),
         }
     }
 
@@ -344,17 +520,23 @@ impl<F: PrimeField> FieldBasedCryptographicSponge<F> for PoseidonSponge<F> {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
 /// Stores the state of a Poseidon Sponge. Does not store any parameter.
+///
+/// It can be serialized to checkpoint a sponge, and resumed with [`SpongeExt::from_state`].
 pub struct PoseidonSpongeState<F: PrimeField> {
-    state: Vec<F>,
-    mode: DuplexSpongeMode,
+    /// Current sponge's state (current elements in the permutation block)
+    pub state: Vec<F>,
+    /// Current mode (whether its absorbing or squeezing)
+    pub mode: DuplexSpongeMode,
 }
 
 impl<CF: PrimeField> SpongeExt for PoseidonSponge<CF> {
     type State = PoseidonSpongeState<CF>;
 
+    /// Panics if `state` does not match the dimensions of `params`.
     fn from_state(state: Self::State, params: &Self::Config) -> Self {
+        assert_state_matches_config(&state.state, &state.mode, params);
         let mut sponge = Self::new(params);
         sponge.mode = state.mode;
         sponge.state = state.state;
@@ -369,14 +551,37 @@ impl<CF: PrimeField> SpongeExt for PoseidonSponge<CF> {
     }
 }
 
+/// Panics if a sponge state and mode cannot be used with `params`.
+pub(crate) fn assert_state_matches_config<T, F: PrimeField>(
+    state: &[T],
+    mode: &DuplexSpongeMode,
+    params: &PoseidonConfig<F>,
+) {
+    assert_eq!(
+        state.len(),
+        params.rate + params.capacity,
+        "the state does not match the width of the sponge"
+    );
+    let index = match mode {
+        DuplexSpongeMode::Absorbing { next_absorb_index } => next_absorb_index,
+        DuplexSpongeMode::Squeezing { next_squeeze_index } => next_squeeze_index,
+    };
+    assert!(
+        *index <= params.rate,
+        "the mode does not match the rate of the sponge"
+    );
+}
+
 #[cfg(test)]
 mod test {
     use crate::sponge::poseidon::PoseidonDefaultConfigField;
     use crate::sponge::test::Fr;
     use crate::sponge::{
-        poseidon::PoseidonSponge, CryptographicSponge, FieldBasedCryptographicSponge,
+        poseidon::{PoseidonConfig, PoseidonSponge, PoseidonSpongeState},
+        CryptographicSponge, DuplexSpongeMode, FieldBasedCryptographicSponge, SpongeExt,
     };
     use ark_ff::MontFp;
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
 
     #[test]
     fn test_poseidon_sponge_consistency() {
@@ -402,4 +607,55 @@ mod test {
             )
         );
     }
+
+    #[test]
+    fn test_poseidon_sponge_checkpoint() {
+        let sponge_param = Fr::get_default_poseidon_parameters(2, false).unwrap();
+
+        let mut sponge = PoseidonSponge::<Fr>::new(&sponge_param);
+        sponge.absorb(&vec![Fr::from(0u8), Fr::from(1u8), Fr::from(2u8)]);
+        // checkpoint once while absorbing, and once while squeezing
+        for _ in 0..2 {
+            let mut bytes = Vec::new();
+            sponge
+                .clone()
+                .into_state()
+                .serialize_compressed(&mut bytes)
+                .unwrap();
+            let state = PoseidonSpongeState::<Fr>::deserialize_compressed(&bytes[..]).unwrap();
+            assert_eq!(state, sponge.clone().into_state());
+            let mut resumed = PoseidonSponge::from_state(state, &sponge_param);
+
+            assert_eq!(
+                resumed.squeeze_native_field_elements(3),
+                sponge.squeeze_native_field_elements(3)
+            );
+        }
+        assert!(matches!(sponge.mode, DuplexSpongeMode::Squeezing { .. }));
+
+        let mut bytes = Vec::new();
+        DuplexSpongeMode::Absorbing {
+            next_absorb_index: 1,
+        }
+        .serialize_compressed(&mut bytes)
+        .unwrap();
+        bytes[0] = 2;
+        assert!(DuplexSpongeMode::deserialize_compressed(&bytes[..]).is_err());
+    }
+
+    #[test]
+    fn test_poseidon_config_deserialization_overflow() {
+        let config = Fr::get_default_poseidon_parameters(2, false).unwrap();
+        for (rate, partial_rounds) in [(usize::MAX, config.partial_rounds), (2, usize::MAX)] {
+            let mut overflowing = config.clone();
+            overflowing.rate = rate;
+            overflowing.partial_rounds = partial_rounds;
+            let mut bytes = Vec::new();
+            overflowing.serialize_compressed(&mut bytes).unwrap();
+            assert!(matches!(
+                PoseidonConfig::<Fr>::deserialize_compressed(&bytes[..]),
+                Err(SerializationError::InvalidData)
+            ));
+        }
+    }
 }
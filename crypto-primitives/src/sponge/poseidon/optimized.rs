@@ -0,0 +1,290 @@
+use crate::sponge::poseidon::PoseidonConfig;
+use ark_ff::PrimeField;
+#[cfg(not(feature = "std"))]
+use ark_std::vec::Vec;
+
+/// A sparse matrix of the form
+/// ```text
+/// | row[0]  row[1] ... row[t-1] |
+/// | col[0]  1      ... 0        |
+/// | ...     ...    ... ...      |
+/// | col[t-2] 0     ... 1        |
+/// ```
+/// used in place of the MDS matrix in the partial rounds.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SparseMdsMatrix<F: PrimeField> {
+    /// The first row of the matrix.
+    pub row: Vec<F>,
+    /// The first column of the matrix, without its first element.
+    pub col: Vec<F>,
+}
+
+impl<F: PrimeField> SparseMdsMatrix<F> {
+    fn apply(&self, state: &mut [F]) {
+        let first = state[0];
+        state[0] = self.row.iter().zip(state.iter()).map(|(m, s)| *m * s).sum();
+        for (state_elem, col_elem) in state[1..].iter_mut().zip(self.col.iter()) {
+            *state_elem += first * col_elem;
+        }
+    }
+}
+
+/// The precomputed form of a [`PoseidonConfig`] described in Appendix B of the
+/// [Poseidon paper](https://eprint.iacr.org/2019/458).
+///
+/// In every partial round, the round constants are folded into the first element of the state
+/// and the MDS matrix is replaced by a [`SparseMdsMatrix`]. The remaining dense part of the
+/// MDS matrices is merged into the last full round before the partial rounds.
+/// The resulting permutation is equal to the one defined by the original config.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PoseidonOptimizedConfig<F: PrimeField> {
+    /// Exponent used in S-boxes.
+    pub alpha: u64,
+    /// The MDS matrix used by the full rounds.
+    pub mds: Vec<Vec<F>>,
+    /// Round constants of the full rounds, indexed by `full_ark[full_round_num][state_element_index]`.
+    pub full_ark: Vec<Vec<F>>,
+    /// Round constants of the partial rounds, added to the first element of the state.
+    pub partial_ark: Vec<F>,
+    /// The dense matrix used by the last full round before the partial rounds.
+    pub pre_sparse_mds: Vec<Vec<F>>,
+    /// The sparse matrices used by the partial rounds.
+    pub sparse_mds: Vec<SparseMdsMatrix<F>>,
+}
+
+impl<F: PrimeField> PoseidonOptimizedConfig<F> {
+    /// Computes the optimized form of `config`.
+    ///
+    /// Returns `None` if the config has no partial rounds, has less than two full rounds or
+    /// if the MDS matrix cannot be decomposed.
+    pub fn new(config: &PoseidonConfig<F>) -> Option<Self> {
+        let t = config.rate + config.capacity;
+        let full_rounds_over_2 = config.full_rounds / 2;
+        if config.partial_rounds == 0 || full_rounds_over_2 == 0 || t < 2 {
+            return None;
+        }
+        let partial_end = full_rounds_over_2 + config.partial_rounds;
+
+        // Fold the constants of the partial rounds forward: only the first element passes
+        // through the S-box, so the other elements can be added in the next round instead.
+        let mut full_ark = config.ark[..full_rounds_over_2].to_vec();
+        let mut partial_ark = Vec::with_capacity(config.partial_rounds);
+        let mut carry = vec![F::zero(); t];
+        for round_ark in &config.ark[full_rounds_over_2..partial_end] {
+            let mut rest: Vec<F> = round_ark.iter().zip(&carry).map(|(c, d)| *c + d).collect();
+            partial_ark.push(rest[0]);
+            rest[0] = F::zero();
+            carry = mat_vec_mul(&config.mds, &rest);
+        }
+        full_ark.push(
+            config.ark[partial_end]
+                .iter()
+                .zip(&carry)
+                .map(|(c, d)| *c + d)
+                .collect(),
+        );
+        full_ark.extend_from_slice(&config.ark[partial_end + 1..]);
+
+        // Decompose the MDS matrices backwards, as `M = M'' * M'`, where `M''` is sparse and
+        // `M'` only acts on the elements that do not go through the S-box.
+        // `M'` is then merged into the matrix of the previous round.
+        let mut sparse_mds = Vec::with_capacity(config.partial_rounds);
+        let mut cur = config.mds.clone();
+        for _ in 0..config.partial_rounds {
+            let sub: Vec<Vec<F>> = cur[1..].iter().map(|row| row[1..].to_vec()).collect();
+            let sub_inv = invert(&sub)?;
+
+            let mut row = vec![cur[0][0]];
+            row.extend((0..t - 1).map(|j| {
+                cur[0][1..]
+                    .iter()
+                    .zip(&sub_inv)
+                    .map(|(v, inv_row)| *v * inv_row[j])
+                    .sum::<F>()
+            }));
+            let col = cur[1..].iter().map(|r| r[0]).collect();
+            sparse_mds.push(SparseMdsMatrix { row, col });
+
+            let mut dense = vec![vec![F::zero(); t]; t];
+            dense[0][0] = F::one();
+            for i in 1..t {
+                for j in 1..t {
+                    dense[i][j] = sub[i - 1][j - 1];
+                }
+            }
+            cur = mat_mul(&dense, &config.mds);
+        }
+        sparse_mds.reverse();
+
+        Some(Self {
+            alpha: config.alpha,
+            mds: config.mds.clone(),
+            full_ark,
+            partial_ark,
+            pre_sparse_mds: cur,
+            sparse_mds,
+        })
+    }
+
+    /// Applies the Poseidon permutation to `state`.
+    pub fn permute(&self, state: &mut [F]) {
+        let full_rounds_over_2 = self.full_ark.len() / 2;
+
+        for (i, round_ark) in self.full_ark[..full_rounds_over_2].iter().enumerate() {
+            let mds = if i + 1 == full_rounds_over_2 {
+                &self.pre_sparse_mds
+            } else {
+                &self.mds
+            };
+            self.apply_full_round(state, round_ark, mds);
+        }
+        for (c, sparse) in self.partial_ark.iter().zip(&self.sparse_mds) {
+            state[0] += c;
+            state[0] = state[0].pow([self.alpha]);
+            sparse.apply(state);
+        }
+        for round_ark in &self.full_ark[full_rounds_over_2..] {
+            self.apply_full_round(state, round_ark, &self.mds);
+        }
+    }
+
+    fn apply_full_round(&self, state: &mut [F], round_ark: &[F], mds: &[Vec<F>]) {
+        for (elem, c) in state.iter_mut().zip(round_ark) {
+            *elem += c;
+            *elem = elem.pow([self.alpha]);
+        }
+        let new_state = mat_vec_mul(mds, state);
+        state.clone_from_slice(&new_state);
+    }
+}
+
+fn mat_vec_mul<F: PrimeField>(mat: &[Vec<F>], vec: &[F]) -> Vec<F> {
+    mat.iter()
+        .map(|row| row.iter().zip(vec).map(|(m, v)| *m * v).sum())
+        .collect()
+}
+
+fn mat_mul<F: PrimeField>(a: &[Vec<F>], b: &[Vec<F>]) -> Vec<Vec<F>> {
+    a.iter()
+        .map(|row| {
+            (0..b[0].len())
+                .map(|j| row.iter().zip(b).map(|(x, b_row)| *x * b_row[j]).sum())
+                .collect()
+        })
+        .collect()
+}
+
+/// Inverts a square matrix by Gauss-Jordan elimination.
+fn invert<F: PrimeField>(mat: &[Vec<F>]) -> Option<Vec<Vec<F>>> {
+    let n = mat.len();
+    let mut a = mat.to_vec();
+    let mut inv: Vec<Vec<F>> = (0..n)
+        .map(|i| (0..n).map(|j| F::from((i == j) as u8)).collect())
+        .collect();
+
+    for col in 0..n {
+        let pivot = (col..n).find(|&row| !a[row][col].is_zero())?;
+        a.swap(col, pivot);
+        inv.swap(col, pivot);
+
+        let pivot_inv = a[col][col].inverse()?;
+        a[col].iter_mut().for_each(|x| *x *= pivot_inv);
+        inv[col].iter_mut().for_each(|x| *x *= pivot_inv);
+
+        for row in 0..n {
+            if row != col && !a[row][col].is_zero() {
+                let factor = a[row][col];
+                for j in 0..n {
+                    let (a_col, inv_col) = (a[col][j], inv[col][j]);
+                    a[row][j] -= factor * a_col;
+                    inv[row][j] -= factor * inv_col;
+                }
+            }
+        }
+    }
+
+    Some(inv)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::sponge::poseidon::{
+        poseidon_parameters_for_test, PoseidonConfig, PoseidonDefaultConfigField, PoseidonSponge,
+    };
+    use crate::sponge::test::Fr;
+    use crate::sponge::CryptographicSponge;
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use ark_std::{test_rng, UniformRand};
+
+    fn check_against_reference(config: &PoseidonConfig<Fr>) {
+        assert!(config.optimized().is_some());
+        let mut reference = config.clone();
+        reference.set_optimized(false);
+
+        let mut rng = test_rng();
+        for _ in 0..10 {
+            let state: Vec<Fr> = (0..config.rate + config.capacity)
+                .map(|_| Fr::rand(&mut rng))
+                .collect();
+
+            let mut optimized_sponge = PoseidonSponge::new(config);
+            optimized_sponge.state = state.clone();
+            optimized_sponge.permute();
+
+            let mut reference_sponge = PoseidonSponge::new(&reference);
+            reference_sponge.state = state;
+            reference_sponge.permute();
+
+            assert_eq!(optimized_sponge.state, reference_sponge.state);
+        }
+    }
+
+    #[test]
+    fn optimized_permutation_matches_reference() {
+        check_against_reference(&poseidon_parameters_for_test());
+        for rate in 2..=8 {
+            check_against_reference(&Fr::get_default_poseidon_parameters(rate, false).unwrap());
+            check_against_reference(&Fr::get_default_poseidon_parameters(rate, true).unwrap());
+        }
+    }
+
+    #[test]
+    fn optimized_config_is_not_serialized() {
+        let config = Fr::get_default_poseidon_parameters(3, false).unwrap();
+        let mut reference = config.clone();
+        reference.set_optimized(false);
+
+        let mut bytes = Vec::new();
+        config.serialize_compressed(&mut bytes).unwrap();
+        let mut reference_bytes = Vec::new();
+        reference
+            .serialize_compressed(&mut reference_bytes)
+            .unwrap();
+        assert_eq!(bytes, reference_bytes);
+        assert_eq!(bytes.len(), config.compressed_size());
+
+        let deserialized = PoseidonConfig::<Fr>::deserialize_compressed(&bytes[..]).unwrap();
+        assert_eq!(deserialized.ark, config.ark);
+        assert_eq!(deserialized.mds, config.mds);
+        assert_eq!(deserialized.optimized(), config.optimized());
+    }
+
+    #[test]
+    fn modified_config_is_not_optimized() {
+        let mut config = Fr::get_default_poseidon_parameters(3, false).unwrap();
+        config.ark[1][0] += Fr::from(1u64);
+        assert!(config.optimized().is_none());
+
+        // the modified config is used by the reference permutation
+        let mut reference = config.clone();
+        reference.set_optimized(false);
+        let mut sponge = PoseidonSponge::new(&config);
+        sponge.permute();
+        let mut reference_sponge = PoseidonSponge::new(&reference);
+        reference_sponge.permute();
+        assert_eq!(sponge.state, reference_sponge.state);
+
+        config.set_optimized(true);
+        check_against_reference(&config);
+    }
+}
@@ -0,0 +1,253 @@
+use crate::sponge::poseidon::PoseidonConfig;
+use crate::Error;
+use ark_ff::{BigInteger, PrimeField};
+use ark_std::cmp::{max, min};
+
+/// Computes the number of full and partial rounds of a Poseidon permutation of `width`
+/// field elements with S-box `x^alpha`, so that it provides `security_level` bits of security.
+///
+/// The round numbers are the cheapest (in number of S-boxes) that satisfy the statistical,
+/// interpolation and Gröbner basis bounds of the [Poseidon paper](https://eprint.iacr.org/2019/458),
+/// including the Gröbner basis attack of [ABM23](https://eprint.iacr.org/2023/537).
+/// As in the reference implementation (`calc_round_numbers.py`), a security margin of two full
+/// rounds and 7.5% of the partial rounds is added.
+///
+/// Returns `(full_rounds, partial_rounds)`, or `None` if `width < 2`, if `alpha < 3`, or if
+/// no secure round numbers are found.
+/// Note that `alpha` must also satisfy `gcd(alpha, p - 1) = 1` for `x^alpha` to be a permutation.
+pub fn find_poseidon_round_numbers<F: PrimeField>(
+    width: usize,
+    alpha: u64,
+    security_level: u32,
+) -> Option<(usize, usize)> {
+    if width < 2 || alpha < 3 {
+        return None;
+    }
+
+    // (cost, full_rounds, partial_rounds) of the best round numbers found so far
+    let mut best: Option<(usize, usize, usize)> = None;
+    for partial_rounds in 1..500 {
+        // the cost only grows with the number of partial rounds
+        if let Some((cost, ..)) = best {
+            if width * 6 + partial_rounds > cost {
+                break;
+            }
+        }
+
+        for full_rounds in (4..100).step_by(2) {
+            if !is_secure::<F>(width, full_rounds, partial_rounds, alpha, security_level) {
+                continue;
+            }
+            let full_rounds = full_rounds + 2;
+            let partial_rounds = ceil(partial_rounds as f64 * 1.075) as usize;
+            let cost = width * full_rounds + partial_rounds;
+            let is_better = match best {
+                None => true,
+                Some((best_cost, best_full_rounds, _)) => {
+                    cost < best_cost || (cost == best_cost && full_rounds < best_full_rounds)
+                }
+            };
+            if is_better {
+                best = Some((cost, full_rounds, partial_rounds));
+            }
+            // more full rounds only increase the cost
+            break;
+        }
+    }
+
+    best.map(|(_, full_rounds, partial_rounds)| (full_rounds, partial_rounds))
+}
+
+/// Generates Poseidon parameters of the given rate and capacity 1 with 128 bits of security,
+/// using the smallest exponent `alpha` for which `x^alpha` is a permutation of `F`.
+pub(crate) fn secure_poseidon_config<F: PrimeField>(
+    rate: usize,
+) -> Result<PoseidonConfig<F>, Error> {
+    const SECURITY_LEVEL: u32 = 128;
+
+    let alpha = (3..)
+        .step_by(2)
+        .find(|alpha| is_valid_alpha::<F>(*alpha))
+        .unwrap();
+    let (full_rounds, partial_rounds) =
+        find_poseidon_round_numbers::<F>(rate + 1, alpha, SECURITY_LEVEL).ok_or_else(|| {
+            Error::InvalidParameters(format!(
+                "no secure Poseidon round numbers for alpha = {alpha}"
+            ))
+        })?;
+    PoseidonConfig::generate(rate, 1, alpha, full_rounds, partial_rounds, 0)
+}
+
+/// Returns whether `x^alpha` is a permutation of `F`, i.e. whether `gcd(alpha, p - 1) = 1`.
+pub(crate) fn is_valid_alpha<F: PrimeField>(alpha: u64) -> bool {
+    if alpha == 0 {
+        return false;
+    }
+    let mut modulus_minus_one = F::MODULUS;
+    let _ = modulus_minus_one.sub_with_borrow(&F::BigInt::from(1u64));
+    let rem = modulus_minus_one
+        .as_ref()
+        .iter()
+        .rev()
+        .fold(0u128, |rem, limb| {
+            ((rem << 64) | *limb as u128) % alpha as u128
+        });
+
+    let (mut a, mut b) = (alpha, rem as u64);
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a == 1
+}
+
+fn is_secure<F: PrimeField>(
+    t: usize,
+    full_rounds: usize,
+    partial_rounds: usize,
+    alpha: u64,
+    security_level: u32,
+) -> bool {
+    let log2_p = log2_modulus::<F>();
+    let n = F::MODULUS_BIT_SIZE as f64;
+    let (t_f, r_p, m) = (t as f64, partial_rounds as f64, security_level as f64);
+    let log2_alpha = log2(alpha as f64);
+
+    // statistical attacks
+    let statistical = if m <= floor(log2_p - (alpha as f64 - 1.0) / 2.0) as f64 * (t_f + 1.0) {
+        6
+    } else {
+        10
+    };
+    // interpolation attack
+    let interpolation =
+        1 + ceil(m.min(n) / log2_alpha) + ceil(log2(t_f) / log2_alpha) - partial_rounds as i64;
+    // Gröbner basis attacks
+    let groebner_1 = ceil(m.min(log2_p) / log2_alpha - r_p);
+    let groebner_2 = ceil(t_f - 1.0 + (m / (t_f + 1.0)).min(log2_p / 2.0) / log2_alpha - r_p);
+    let groebner_3 = ceil((t_f - 2.0 + m / (2.0 * log2_alpha) - r_p) / (t_f - 1.0));
+    let min_full_rounds = max(
+        max(statistical, interpolation),
+        max(groebner_1, max(groebner_2, groebner_3)),
+    );
+    if (full_rounds as i64) < min_full_rounds {
+        return false;
+    }
+
+    // Gröbner basis attack of ABM23
+    let r = (t / 3) as u64;
+    let (r_f, r_p, alpha) = (full_rounds as u64, partial_rounds as u64, alpha as u128);
+    let over = ((r_f - 1) * t as u64 + r_p + r + r * (r_f / 2) + r_p) as u128 + alpha;
+    let under = (r * (r_f / 2) + r_p) as u128 + alpha;
+    ceil(2.0 * log2_binomial(over, under)) >= security_level as i64
+}
+
+/// Returns `log2(n choose k)`.
+fn log2_binomial(n: u128, k: u128) -> f64 {
+    let k = min(k, n - k);
+    (1..=k)
+        .map(|i| log2((n - k + i) as f64) - log2(i as f64))
+        .sum()
+}
+
+/// Returns `log2(p)`, computed from the 64 most significant bits of the modulus.
+fn log2_modulus<F: PrimeField>() -> f64 {
+    let bits = F::MODULUS_BIT_SIZE;
+    let shift = bits.saturating_sub(64);
+    let top = F::MODULUS >> shift;
+    shift as f64 + log2(top.as_ref()[0] as f64)
+}
+
+// `f64::log2`, `f64::floor` and `f64::ceil` are not available in `core`.
+
+fn log2(x: f64) -> f64 {
+    // x = 2^exp * m, with m in [1, 2)
+    let bits = x.to_bits();
+    let exp = ((bits >> 52) & 0x7ff) as i64 - 1023;
+    let m = f64::from_bits((bits & ((1 << 52) - 1)) | (1023 << 52));
+
+    // ln(m) = 2 * atanh((m - 1) / (m + 1))
+    let z = (m - 1.0) / (m + 1.0);
+    let z2 = z * z;
+    let mut term = z;
+    let mut atanh = 0.0;
+    for k in 0..40 {
+        atanh += term / (2 * k + 1) as f64;
+        term *= z2;
+    }
+    exp as f64 + 2.0 * atanh / core::f64::consts::LN_2
+}
+
+fn floor(x: f64) -> i64 {
+    let i = x as i64;
+    if (i as f64) > x {
+        i - 1
+    } else {
+        i
+    }
+}
+
+fn ceil(x: f64) -> i64 {
+    let i = x as i64;
+    if (i as f64) < x {
+        i + 1
+    } else {
+        i
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{find_poseidon_round_numbers, is_valid_alpha, log2};
+    use crate::sponge::test::Fr;
+
+    #[test]
+    fn log2_is_accurate() {
+        for x in [
+            1.0,
+            2.0,
+            3.0,
+            5.0,
+            17.0,
+            0.3,
+            1e-5,
+            12345.678,
+            2f64.powi(200) * 3.0,
+        ] {
+            assert!((log2(x) - x.log2()).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn round_numbers_match_reference() {
+        // values computed with `calc_round_numbers.py` from the reference implementation
+        for width in 2..=5 {
+            assert_eq!(
+                find_poseidon_round_numbers::<Fr>(width, 5, 128),
+                Some((8, 56))
+            );
+        }
+        for width in 6..=9 {
+            assert_eq!(
+                find_poseidon_round_numbers::<Fr>(width, 5, 128),
+                Some((8, 57))
+            );
+        }
+        assert_eq!(find_poseidon_round_numbers::<Fr>(3, 17, 128), Some((8, 31)));
+        assert_eq!(
+            find_poseidon_round_numbers::<Fr>(3, 257, 128),
+            Some((8, 13))
+        );
+
+        assert_eq!(find_poseidon_round_numbers::<Fr>(1, 5, 128), None);
+        assert_eq!(find_poseidon_round_numbers::<Fr>(3, 1, 128), None);
+    }
+
+    #[test]
+    fn valid_alpha() {
+        // 3 divides p - 1 for the scalar field of BLS12-381
+        assert!(!is_valid_alpha::<Fr>(3));
+        assert!(is_valid_alpha::<Fr>(5));
+        assert!(is_valid_alpha::<Fr>(17));
+        assert!(!is_valid_alpha::<Fr>(0));
+    }
+}
@@ -1,6 +1,8 @@
 use crate::sponge::poseidon::{PoseidonConfig, PoseidonDefaultConfigField, PoseidonSponge};
 use crate::sponge::test::Fr;
-use crate::sponge::{Absorb, AbsorbWithLength, CryptographicSponge, FieldBasedCryptographicSponge};
+use crate::sponge::{
+    Absorb, AbsorbWithLength, CryptographicSponge, FieldBasedCryptographicSponge, FieldElementSize,
+};
 use crate::{absorb, collect_sponge_bytes, collect_sponge_field_elements};
 use ark_ff::{One, PrimeField, UniformRand};
 use ark_std::test_rng;
@@ -314,6 +316,43 @@ fn test_squeeze_cast_native() {
     assert_eq!(squeezed1, squeezed2);
 }
 
+#[test]
+fn test_squeeze_unbiased() {
+    type Fq = ark_ed_on_bls12_381::Fr;
+
+    let mut rng = test_rng();
+    let sponge_param = poseidon_parameters_for_test();
+    let elem = Fr::rand(&mut rng);
+    let mut sponge1 = PoseidonSponge::<Fr>::new(&sponge_param);
+    sponge1.absorb(&elem);
+    let mut sponge2 = sponge1.clone();
+
+    // `Unbiased(λ)` reduces `MODULUS_BIT_SIZE + λ` bits
+    let squeezed1 =
+        sponge1.squeeze_field_elements_with_sizes::<Fq>(&[FieldElementSize::Unbiased(128); 2]);
+    let num_bits = Fq::MODULUS_BIT_SIZE as usize + 128;
+    let squeezed2: Vec<Fq> = sponge2
+        .squeeze_bits(2 * num_bits)
+        .chunks(num_bits)
+        .map(|bits| {
+            let bytes: Vec<u8> = bits
+                .chunks(8)
+                .map(|bits| {
+                    bits.iter()
+                        .enumerate()
+                        .fold(0u8, |byte, (i, &bit)| byte | ((bit as u8) << i))
+                })
+                .collect();
+            Fq::from_le_bytes_mod_order(&bytes)
+        })
+        .collect();
+    assert_eq!(squeezed1, squeezed2);
+
+    let squeezed = sponge1.squeeze_field_elements_by_rejection::<Fq>(3);
+    assert_eq!(squeezed.len(), 3);
+    assert_ne!(squeezed[0], squeezed[1]);
+}
+
 #[test]
 fn test_macros() {
     let sponge_param = poseidon_parameters_for_test();
@@ -1038,13 +1077,5 @@ pub(crate) fn poseidon_parameters_for_test<F: PrimeField>() -> PoseidonConfig<F>
     let partial_rounds = total_rounds - full_rounds;
     let capacity = 1;
     let rate = 2;
-    PoseidonConfig {
-        full_rounds,
-        partial_rounds,
-        alpha,
-        ark,
-        mds,
-        rate,
-        capacity,
-    }
+    PoseidonConfig::new(full_rounds, partial_rounds, alpha, mds, ark, rate, capacity)
 }
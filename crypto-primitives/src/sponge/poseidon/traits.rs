@@ -87,15 +87,15 @@ pub fn get_default_poseidon_parameters_internal<P: PoseidonDefaultConfig<N>, con
                 param.skip_matrices as u64,
             );
 
-            return Some(PoseidonConfig {
-                full_rounds: param.full_rounds,
-                partial_rounds: param.partial_rounds,
-                alpha: param.alpha as u64,
-                ark,
+            return Some(PoseidonConfig::new(
+                param.full_rounds,
+                param.partial_rounds,
+                param.alpha as u64,
                 mds,
-                rate: param.rate,
-                capacity: 1,
-            });
+                ark,
+                param.rate,
+                1,
+            ));
         }
     }
 
@@ -110,23 +110,44 @@ pub fn find_poseidon_ark_and_mds<F: PrimeField>(
     partial_rounds: u64,
     skip_matrices: u64,
 ) -> (Vec<Vec<F>>, Vec<Vec<F>>) {
-    let mut lfsr = PoseidonGrainLFSR::new(
-        false,
+    let (ark, xs, ys) = sample_poseidon_ark_and_cauchy_points::<F>(
         prime_bits,
-        (rate + 1) as u64,
+        rate + 1,
         full_rounds,
         partial_rounds,
+        skip_matrices,
     );
 
+    let mut mds = Vec::<Vec<F>>::with_capacity(rate + 1);
+    mds.resize(rate + 1, vec![F::zero(); rate + 1]);
+    for i in 0..(rate + 1) {
+        for j in 0..(rate + 1) {
+            mds[i][j] = (xs[i] + &ys[j]).inverse().unwrap();
+        }
+    }
+
+    (ark, mds)
+}
+
+/// Samples from the Poseidon Grain LFSR the round constants of a state of `width` elements,
+/// and the points `xs` and `ys` defining the Cauchy MDS matrix `mds[i][j] = 1 / (xs[i] + ys[j])`.
+pub(crate) fn sample_poseidon_ark_and_cauchy_points<F: PrimeField>(
+    prime_bits: u64,
+    width: usize,
+    full_rounds: u64,
+    partial_rounds: u64,
+    skip_matrices: u64,
+) -> (Vec<Vec<F>>, Vec<F>, Vec<F>) {
+    let mut lfsr =
+        PoseidonGrainLFSR::new(false, prime_bits, width as u64, full_rounds, partial_rounds);
+
     let mut ark = Vec::<Vec<F>>::with_capacity((full_rounds + partial_rounds) as usize);
     for _ in 0..(full_rounds + partial_rounds) {
-        ark.push(lfsr.get_field_elements_rejection_sampling(rate + 1));
+        ark.push(lfsr.get_field_elements_rejection_sampling(width));
     }
 
-    let mut mds = Vec::<Vec<F>>::with_capacity(rate + 1);
-    mds.resize(rate + 1, vec![F::zero(); rate + 1]);
     for _ in 0..skip_matrices {
-        let _ = lfsr.get_field_elements_mod_p::<F>(2 * (rate + 1));
+        let _ = lfsr.get_field_elements_mod_p::<F>(2 * width);
     }
 
     // a qualifying matrix must satisfy the following requirements
@@ -134,16 +155,10 @@ pub fn find_poseidon_ark_and_mds<F: PrimeField>(
     // - there is no i and j such that x[i] + y[j] = p
     // - the resultant MDS passes all the three tests
 
-    let xs = lfsr.get_field_elements_mod_p::<F>(rate + 1);
-    let ys = lfsr.get_field_elements_mod_p::<F>(rate + 1);
+    let xs = lfsr.get_field_elements_mod_p::<F>(width);
+    let ys = lfsr.get_field_elements_mod_p::<F>(width);
 
-    for i in 0..(rate + 1) {
-        for j in 0..(rate + 1) {
-            mds[i][j] = (xs[i] + &ys[j]).inverse().unwrap();
-        }
-    }
-
-    (ark, mds)
+    (ark, xs, ys)
 }
 
 impl<const N: usize, P: PoseidonDefaultConfig<N>> PoseidonDefaultConfigField for Fp<P, N> {
@@ -0,0 +1,123 @@
+use crate::sponge::constraints::{AbsorbGadget, CryptographicSpongeVar};
+use crate::sponge::transcript::{frame_label, CHALLENGE_TAG, MESSAGE_TAG};
+use crate::sponge::{Absorb, CryptographicSponge};
+use ark_ff::PrimeField;
+use ark_r1cs_std::fields::emulated_fp::EmulatedFpVar;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_r1cs_std::uint8::UInt8;
+use ark_relations::r1cs::SynthesisError;
+#[cfg(not(feature = "std"))]
+use ark_std::vec::Vec;
+
+/// The gadget counterpart of [`FiatShamirTranscript`](crate::sponge::transcript::FiatShamirTranscript).
+///
+/// Labels are absorbed as constants using exactly the same framing as the native transcript,
+/// so that a circuit produces the same challenges as the prover.
+pub trait FiatShamirTranscriptVar<CF: PrimeField, S: CryptographicSponge>:
+    CryptographicSpongeVar<CF, S>
+{
+    /// Absorb `message` into the transcript under `label`.
+    fn append_message(
+        &mut self,
+        label: &[u8],
+        message: &impl AbsorbGadget<CF>,
+    ) -> Result<(), SynthesisError> {
+        absorb_label(self, MESSAGE_TAG, label)?;
+        self.absorb(message)
+    }
+
+    /// Squeeze a challenge native field element under `label`.
+    fn challenge_scalar(&mut self, label: &[u8]) -> Result<FpVar<CF>, SynthesisError> {
+        absorb_label(self, CHALLENGE_TAG, label)?;
+        Ok(self.squeeze_field_elements(1)?.remove(0))
+    }
+
+    /// Squeeze a challenge emulated field element under `label`.
+    fn challenge_emulated_scalar<F: PrimeField>(
+        &mut self,
+        label: &[u8],
+    ) -> Result<EmulatedFpVar<F, CF>, SynthesisError> {
+        absorb_label(self, CHALLENGE_TAG, label)?;
+        Ok(self.squeeze_emulated_field_elements::<F>(1)?.0.remove(0))
+    }
+
+    /// Squeeze `num_bytes` challenge bytes under `label`.
+    fn challenge_bytes(
+        &mut self,
+        label: &[u8],
+        num_bytes: usize,
+    ) -> Result<Vec<UInt8<CF>>, SynthesisError> {
+        absorb_label(self, CHALLENGE_TAG, label)?;
+        self.squeeze_bytes(num_bytes)
+    }
+}
+
+impl<CF: PrimeField, S: CryptographicSponge, V: CryptographicSpongeVar<CF, S>>
+    FiatShamirTranscriptVar<CF, S> for V
+{
+}
+
+fn absorb_label<CF: PrimeField, S: CryptographicSponge>(
+    sponge: &mut impl CryptographicSpongeVar<CF, S>,
+    tag: u8,
+    label: &[u8],
+) -> Result<(), SynthesisError> {
+    let elems: Vec<CF> = frame_label(tag, label).to_sponge_field_elements_as_vec();
+    let elem_vars = elems.into_iter().map(FpVar::Constant).collect::<Vec<_>>();
+    sponge.absorb(&elem_vars)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sponge::constraints::CryptographicSpongeVar;
+    use crate::sponge::poseidon::constraints::PoseidonSpongeVar;
+    use crate::sponge::poseidon::{poseidon_parameters_for_test, PoseidonSponge};
+    use crate::sponge::test::Fr;
+    use crate::sponge::transcript::constraints::FiatShamirTranscriptVar;
+    use crate::sponge::transcript::FiatShamirTranscript;
+    use crate::sponge::CryptographicSponge;
+    use ark_bls12_377::Fq;
+    use ark_r1cs_std::alloc::AllocVar;
+    use ark_r1cs_std::fields::fp::FpVar;
+    use ark_r1cs_std::uint8::UInt8;
+    use ark_r1cs_std::R1CSVar;
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_relations::*;
+    use ark_std::{test_rng, UniformRand};
+
+    #[test]
+    fn native_and_gadget_transcripts_agree() {
+        let mut rng = test_rng();
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let sponge_params = poseidon_parameters_for_test();
+
+        let msg1: Vec<_> = (0..5).map(|_| Fr::rand(&mut rng)).collect();
+        let msg1_var: Vec<_> = msg1
+            .iter()
+            .map(|v| FpVar::new_witness(ns!(cs, "msg1"), || Ok(*v)).unwrap())
+            .collect();
+        let msg2 = vec![1u8, 2, 3, 4];
+        let msg2_var = UInt8::new_witness_vec(ns!(cs, "msg2"), &msg2).unwrap();
+
+        let mut native = PoseidonSponge::<Fr>::new(&sponge_params);
+        let mut gadget = PoseidonSpongeVar::<Fr>::new(cs.clone(), &sponge_params);
+
+        native.append_message(b"commitment", &msg1);
+        gadget.append_message(b"commitment", &msg1_var).unwrap();
+        let c1: Fr = native.challenge_scalar(b"alpha");
+        let c1_var = gadget.challenge_scalar(b"alpha").unwrap();
+        assert_eq!(c1, c1_var.value().unwrap());
+
+        native.append_message(b"opening", &msg2);
+        gadget.append_message(b"opening", &msg2_var).unwrap();
+        let c2: Fq = native.challenge_scalar(b"beta");
+        let c2_var = gadget.challenge_emulated_scalar::<Fq>(b"beta").unwrap();
+        assert_eq!(c2, c2_var.value().unwrap());
+
+        let c3 = native.challenge_bytes(b"gamma", 40);
+        let c3_var = gadget.challenge_bytes(b"gamma", 40).unwrap();
+        assert_eq!(c3, c3_var.value().unwrap());
+
+        assert!(cs.is_satisfied().unwrap());
+    }
+}
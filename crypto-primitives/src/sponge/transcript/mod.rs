@@ -0,0 +1,108 @@
+use crate::sponge::{Absorb, CryptographicSponge};
+use ark_ff::PrimeField;
+#[cfg(not(feature = "std"))]
+use ark_std::vec::Vec;
+
+/// constraints for the labeled transcript
+#[cfg(feature = "r1cs")]
+pub mod constraints;
+
+/// Tag prepended to the label of an appended message.
+pub(crate) const MESSAGE_TAG: u8 = 0;
+/// Tag prepended to the label of a squeezed challenge.
+pub(crate) const CHALLENGE_TAG: u8 = 1;
+
+/// Encodes `label` together with the operation `tag` as `tag || len(label) || label`.
+///
+/// The same bytes are absorbed by the native transcript and by its gadget counterpart,
+/// so a verifier circuit replays the exact transcript of the prover.
+pub(crate) fn frame_label(tag: u8, label: &[u8]) -> Vec<u8> {
+    let mut frame = vec![tag];
+    label.len().to_sponge_bytes(&mut frame);
+    frame.extend_from_slice(label);
+    frame
+}
+
+/// A labeled Fiat-Shamir transcript.
+///
+/// Every message and every challenge is bound to a label, which is absorbed into the
+/// underlying sponge before the message is absorbed or the challenge is squeezed.
+/// This trait is implemented for every [`CryptographicSponge`], so the same protocol code can
+/// run over [`PoseidonSponge`](crate::sponge::poseidon::PoseidonSponge), a merlin
+/// [`Transcript`](crate::sponge::merlin::Transcript) or any other sponge.
+///
+/// Note that merlin's `Transcript` has inherent methods with the same names, so for it the
+/// methods of this trait must be called as `FiatShamirTranscript::append_message(&mut t, ..)`.
+pub trait FiatShamirTranscript: CryptographicSponge {
+    /// Absorb `message` into the transcript under `label`.
+    fn append_message(&mut self, label: &[u8], message: &impl Absorb) {
+        self.absorb(&frame_label(MESSAGE_TAG, label));
+        self.absorb(message);
+    }
+
+    /// Squeeze a challenge field element under `label`.
+    fn challenge_scalar<F: PrimeField>(&mut self, label: &[u8]) -> F {
+        self.absorb(&frame_label(CHALLENGE_TAG, label));
+        self.squeeze_field_elements::<F>(1)[0]
+    }
+
+    /// Squeeze `num_bytes` challenge bytes under `label`.
+    fn challenge_bytes(&mut self, label: &[u8], num_bytes: usize) -> Vec<u8> {
+        self.absorb(&frame_label(CHALLENGE_TAG, label));
+        self.squeeze_bytes(num_bytes)
+    }
+}
+
+impl<S: CryptographicSponge> FiatShamirTranscript for S {}
+
+#[cfg(test)]
+mod tests {
+    use crate::sponge::merlin::Transcript;
+    use crate::sponge::poseidon::{poseidon_parameters_for_test, PoseidonSponge};
+    use crate::sponge::test::Fr;
+    use crate::sponge::transcript::FiatShamirTranscript;
+    use crate::sponge::CryptographicSponge;
+    use ark_std::{test_rng, UniformRand};
+
+    #[test]
+    fn labels_separate_challenges() {
+        let mut rng = test_rng();
+        let sponge_params = poseidon_parameters_for_test();
+        let message = (0..4).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+
+        let challenge = |msg_label: &[u8], chal_label: &[u8]| -> Fr {
+            let mut transcript = PoseidonSponge::<Fr>::new(&sponge_params);
+            transcript.append_message(msg_label, &message);
+            transcript.challenge_scalar(chal_label)
+        };
+
+        assert_eq!(challenge(b"msg", b"chal"), challenge(b"msg", b"chal"));
+        assert_ne!(challenge(b"msg", b"chal"), challenge(b"msg2", b"chal"));
+        assert_ne!(challenge(b"msg", b"chal"), challenge(b"msg", b"chal2"));
+
+        // a label is not interchangeable with a message prefix
+        let mut transcript = PoseidonSponge::<Fr>::new(&sponge_params);
+        transcript.append_message(b"", &message);
+        let mut other = PoseidonSponge::<Fr>::new(&sponge_params);
+        other.absorb(&message);
+        assert_ne!(
+            transcript.challenge_bytes(b"chal", 32),
+            other.challenge_bytes(b"chal", 32)
+        );
+    }
+
+    #[test]
+    fn merlin_transcript() {
+        let mut t1 = Transcript::new(b"test");
+        let mut t2 = Transcript::new(b"test");
+        FiatShamirTranscript::append_message(&mut t1, b"a", &42u64);
+        FiatShamirTranscript::append_message(&mut t2, b"a", &42u64);
+        let c1: Fr = t1.challenge_scalar(b"c");
+        let c2: Fr = t2.challenge_scalar(b"c");
+        assert_eq!(c1, c2);
+        assert_ne!(
+            FiatShamirTranscript::challenge_bytes(&mut t1, b"c", 16),
+            FiatShamirTranscript::challenge_bytes(&mut t2, b"d", 16)
+        );
+    }
+}
@@ -1,94 +1,289 @@
 extern crate proc_macro;
 use proc_macro::TokenStream;
-use proc_macro2::TokenStream as TokenStream2;
-use quote::quote;
-use syn::{parse_macro_input, Data, DeriveInput, Fields, Index};
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, Attribute, Data, DeriveInput, Error, Fields, Ident, Index, Result, Type,
+};
 
-#[proc_macro_derive(Absorb)]
+/// Derives `Absorb` for a struct or an enum.
+///
+/// The fields are absorbed in order. The fields of an enum are prefixed with the index of the
+/// variant, as a `u8`.
+///
+/// The following field attributes are supported:
+/// - `#[absorb(skip)]`: the field is not absorbed.
+/// - `#[absorb(with_length)]`: the field is absorbed with its length, using `AbsorbWithLength`.
+#[proc_macro_derive(Absorb, attributes(absorb))]
 pub fn derive_absorb(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
+    expand(&input, &Target::Native)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
 
-    let name = input.ident;
-    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
-
-    let len = if let Data::Struct(ref data_struct) = input.data {
-        data_struct.fields.len()
-    } else {
-        panic!(
-            "`Absorb` can only be derived for structs, {} is not a struct",
-            name
-        );
-    };
+/// Derives `AbsorbGadget` for a struct or an enum of circuit variables, with the same encoding
+/// as `#[derive(Absorb)]` on the corresponding native type.
+///
+/// The constraint field is the first type parameter, unless it is specified with
+/// `#[absorb(field = ConstraintF)]` on the type. The variant of an enum must be known at
+/// circuit-generation time, so its index is absorbed as a constant.
+///
+/// The field attributes of `#[derive(Absorb)]` are supported, `#[absorb(with_length)]` using
+/// `AbsorbGadgetWithLength`.
+#[proc_macro_derive(AbsorbGadget, attributes(absorb))]
+pub fn derive_absorb_gadget(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    constraint_field(&input)
+        .and_then(|field| expand(&input, &Target::Gadget(Box::new(field))))
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
 
-    let mut to_sponge_bytes = Vec::<TokenStream2>::with_capacity(len);
-    let mut to_sponge_field_elements = Vec::<TokenStream2>::with_capacity(len);
+enum Target {
+    Native,
+    /// The gadget, over the given constraint field.
+    Gadget(Box<Type>),
+}
 
-    match input.data {
-        Data::Struct(ref data_struct) => match data_struct.fields {
-            Fields::Named(ref fields) => {
-                let _: Vec<_> = fields
-                    .named
-                    .iter()
-                    .map(|f| {
-                        let name = &f.ident;
-                        to_sponge_bytes.push(quote! {
-                            Absorb::to_sponge_bytes(&self.#name, dest);
-                        });
-                    })
-                    .collect();
+#[derive(Default)]
+struct FieldAttributes {
+    skip: bool,
+    with_length: bool,
+}
 
-                let _: Vec<_> = fields
-                    .named
-                    .iter()
-                    .map(|f| {
-                        let name = &f.ident;
-                        to_sponge_field_elements.push(quote! {
-                            Absorb::to_sponge_field_elements(&self.#name, dest);
-                        });
-                    })
-                    .collect();
+fn field_attributes(attrs: &[Attribute]) -> Result<FieldAttributes> {
+    let mut result = FieldAttributes::default();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("absorb")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("skip") {
+                result.skip = true;
+                Ok(())
+            } else if meta.path.is_ident("with_length") {
+                result.with_length = true;
+                Ok(())
+            } else {
+                Err(meta.error("expected `skip` or `with_length`"))
             }
-            Fields::Unnamed(ref fields) => {
-                let _: Vec<_> = fields
-                    .unnamed
-                    .iter()
-                    .enumerate()
-                    .map(|(i, _)| {
-                        let index = Index::from(i);
-                        to_sponge_bytes.push(quote! {
-                            Absorb::to_sponge_bytes(&self.#index, dest);
-                        });
-                    })
-                    .collect();
+        })?;
+    }
+    Ok(result)
+}
 
-                let _: Vec<_> = fields
-                    .unnamed
-                    .iter()
-                    .enumerate()
-                    .map(|(i, _)| {
-                        let index = Index::from(i);
-                        to_sponge_field_elements.push(quote! {
-                            Absorb::to_sponge_field_elements(&self.#index, dest);
-                        });
-                    })
-                    .collect();
+fn constraint_field(input: &DeriveInput) -> Result<Type> {
+    let mut field = None;
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("absorb"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("field") {
+                field = Some(meta.value()?.parse::<Type>()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `field`"))
             }
-            _ => panic!("Absorb only supports named and unnamed fields"),
-        },
-        // this should be unreachable, we already checked
-        _ => panic!("Absorb only supports structs"),
+        })?;
+    }
+    if let Some(field) = field {
+        return Ok(field);
+    }
+
+    let param = input.generics.type_params().next().ok_or_else(|| {
+        Error::new(
+            Span::call_site(),
+            "`AbsorbGadget` requires the constraint field as the first type parameter, \
+             or specified with `#[absorb(field = ...)]`",
+        )
+    })?;
+    let ident = &param.ident;
+    Ok(syn::parse_quote!(#ident))
+}
+
+/// Returns the statements that absorb `value`, a reference to a field, for both
+/// `to_sponge_bytes` and `to_sponge_field_elements`.
+fn absorb_field(
+    target: &Target,
+    value: &TokenStream2,
+    attrs: &FieldAttributes,
+) -> (TokenStream2, TokenStream2) {
+    match (target, attrs.with_length) {
+        (Target::Native, false) => (
+            quote! { Absorb::to_sponge_bytes(#value, dest); },
+            quote! { Absorb::to_sponge_field_elements(#value, dest); },
+        ),
+        (Target::Native, true) => (
+            quote! { AbsorbWithLength::to_sponge_bytes_with_length(#value, dest); },
+            quote! { AbsorbWithLength::to_sponge_field_elements_with_length(#value, dest); },
+        ),
+        (Target::Gadget(cf), false) => (
+            quote! { output.append(&mut AbsorbGadget::<#cf>::to_sponge_bytes(#value)?); },
+            quote! {
+                output.append(&mut AbsorbGadget::<#cf>::to_sponge_field_elements(#value)?);
+            },
+        ),
+        (Target::Gadget(cf), true) => (
+            quote! {
+                output.append(
+                    &mut AbsorbGadgetWithLength::<#cf>::to_sponge_bytes_with_length(#value)?
+                );
+            },
+            quote! {
+                output.append(
+                    &mut AbsorbGadgetWithLength::<#cf>::to_sponge_field_elements_with_length(
+                        #value
+                    )?
+                );
+            },
+        ),
     }
+}
 
-    quote! {
-        impl #impl_generics Absorb for #name #ty_generics #where_clause {
-            fn to_sponge_bytes(&self, dest: &mut Vec<u8>) {
-                #( #to_sponge_bytes )*
+/// Returns the statements that absorb the index of an enum variant.
+fn absorb_discriminant(target: &Target, index: u8) -> (TokenStream2, TokenStream2) {
+    match target {
+        Target::Native => (
+            quote! { Absorb::to_sponge_bytes(&#index, dest); },
+            quote! { Absorb::to_sponge_field_elements(&#index, dest); },
+        ),
+        Target::Gadget(cf) => (
+            quote! { output.push(::ark_r1cs_std::uint8::UInt8::constant(#index)); },
+            quote! {
+                output.push(::ark_r1cs_std::fields::fp::FpVar::Constant(
+                    <#cf as ::core::convert::From<u8>>::from(#index)
+                ));
+            },
+        ),
+    }
+}
+
+/// Returns the pattern binding the fields of a variant, and the statements that absorb them.
+fn absorb_fields(
+    target: &Target,
+    fields: &Fields,
+    access: impl Fn(usize, Option<&Ident>) -> TokenStream2,
+) -> Result<(Vec<TokenStream2>, Vec<TokenStream2>)> {
+    let mut to_sponge_bytes = Vec::with_capacity(fields.len());
+    let mut to_sponge_field_elements = Vec::with_capacity(fields.len());
+    for (i, field) in fields.iter().enumerate() {
+        let attrs = field_attributes(&field.attrs)?;
+        if attrs.skip {
+            continue;
+        }
+        let (bytes, elems) = absorb_field(target, &access(i, field.ident.as_ref()), &attrs);
+        to_sponge_bytes.push(bytes);
+        to_sponge_field_elements.push(elems);
+    }
+    Ok((to_sponge_bytes, to_sponge_field_elements))
+}
+
+fn expand(input: &DeriveInput, target: &Target) -> Result<TokenStream2> {
+    let (to_sponge_bytes, to_sponge_field_elements) = match &input.data {
+        Data::Struct(data_struct) => {
+            let (bytes, elems) =
+                absorb_fields(target, &data_struct.fields, |i, ident| match ident {
+                    Some(ident) => quote! { &self.#ident },
+                    None => {
+                        let index = Index::from(i);
+                        quote! { &self.#index }
+                    }
+                })?;
+            (quote! { #( #bytes )* }, quote! { #( #elems )* })
+        }
+        Data::Enum(data_enum) => {
+            if data_enum.variants.len() > 256 {
+                return Err(Error::new_spanned(
+                    &input.ident,
+                    "enums with more than 256 variants cannot be absorbed",
+                ));
             }
+            let mut bytes_arms = Vec::with_capacity(data_enum.variants.len());
+            let mut elems_arms = Vec::with_capacity(data_enum.variants.len());
+            for (index, variant) in data_enum.variants.iter().enumerate() {
+                let binding = |i: usize| format_ident!("__field_{}", i);
+                let (bytes, elems) = absorb_fields(target, &variant.fields, |i, _| {
+                    let binding = binding(i);
+                    quote! { #binding }
+                })?;
+                let (discriminant_bytes, discriminant_elems) =
+                    absorb_discriminant(target, index as u8);
 
-            fn to_sponge_field_elements<FieldType: PrimeField>(&self, dest: &mut Vec<FieldType>) {
-                #( #to_sponge_field_elements )*
+                let variant_ident = &variant.ident;
+                let pattern = match &variant.fields {
+                    Fields::Named(fields) => {
+                        let names = fields.named.iter().map(|f| &f.ident);
+                        let bindings = (0..fields.named.len()).map(binding);
+                        quote! { Self::#variant_ident { #( #names: #bindings ),* } }
+                    }
+                    Fields::Unnamed(fields) => {
+                        let bindings = (0..fields.unnamed.len()).map(binding);
+                        quote! { Self::#variant_ident ( #( #bindings ),* ) }
+                    }
+                    Fields::Unit => quote! { Self::#variant_ident },
+                };
+                bytes_arms.push(quote! {
+                    #[allow(unused_variables)]
+                    #pattern => { #discriminant_bytes #( #bytes )* }
+                });
+                elems_arms.push(quote! {
+                    #[allow(unused_variables)]
+                    #pattern => { #discriminant_elems #( #elems )* }
+                });
             }
+            (
+                quote! { match self { #( #bytes_arms )* } },
+                quote! { match self { #( #elems_arms )* } },
+            )
         }
-    }
-    .into()
+        Data::Union(_) => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "`Absorb` and `AbsorbGadget` cannot be derived for unions",
+            ))
+        }
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(match target {
+        Target::Native => quote! {
+            impl #impl_generics Absorb for #name #ty_generics #where_clause {
+                fn to_sponge_bytes(&self, dest: &mut Vec<u8>) {
+                    #to_sponge_bytes
+                }
+
+                fn to_sponge_field_elements<FieldType: PrimeField>(&self, dest: &mut Vec<FieldType>) {
+                    #to_sponge_field_elements
+                }
+            }
+        },
+        Target::Gadget(cf) => quote! {
+            impl #impl_generics AbsorbGadget<#cf> for #name #ty_generics #where_clause {
+                #[allow(unused_mut)]
+                fn to_sponge_bytes(
+                    &self,
+                ) -> ::core::result::Result<
+                    Vec<::ark_r1cs_std::uint8::UInt8<#cf>>,
+                    ::ark_relations::r1cs::SynthesisError,
+                > {
+                    let mut output = Vec::new();
+                    #to_sponge_bytes
+                    Ok(output)
+                }
+
+                #[allow(unused_mut)]
+                fn to_sponge_field_elements(
+                    &self,
+                ) -> ::core::result::Result<
+                    Vec<::ark_r1cs_std::fields::fp::FpVar<#cf>>,
+                    ::ark_relations::r1cs::SynthesisError,
+                > {
+                    let mut output = Vec::new();
+                    #to_sponge_field_elements
+                    Ok(output)
+                }
+            }
+        },
+    })
 }